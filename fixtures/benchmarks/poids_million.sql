@@ -0,0 +1,7 @@
+-- Un million de pesées pour la ruche 2, une toutes les 30 secondes du 1er janvier au 14 décembre 2025.
+-- À charger dans une base jetable : psql bees_bench -f fixtures/benchmarks/poids_million.sql
+INSERT INTO poids (id_ruche, poids_ruche, horodatage)
+SELECT 2, 30000 + (random() * 20000)::int, TIMESTAMP '2025-01-01' + i * INTERVAL '30 seconds'
+FROM generate_series(0, 999999) i;
+
+ANALYZE poids;
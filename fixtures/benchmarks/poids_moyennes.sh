@@ -0,0 +1,15 @@
+#!/bin/sh
+# Temps de réponse (3 essais) des moyennes de pesées de la ruche 2, serveur lancé sur $URL
+URL=${URL:-http://localhost:3000/api}
+
+for requete in \
+    "average?year=2025" "average?year=2025&poids=net" \
+    "monthly-average?year=2025" "monthly-average?year=2025&poids=net" \
+    "evolution?years=2024,2025,2026" "evolution?years=2024,2025,2026&poids=net"
+do
+    temps=""
+    for essai in 1 2 3; do
+        temps="$temps $(curl -s -o /dev/null -w '%{time_total}' "$URL/poids/ruche/2/$requete")"
+    done
+    echo "$requete :$temps"
+done
@@ -0,0 +1,23 @@
+ALTER TABLE ruche
+    ADD COLUMN nombre_cadres_corp INT4,
+    ADD COLUMN nombre_hausses INT4,
+    ADD COLUMN nombre_cadre_couvain INT4,
+    ADD COLUMN nombre_cadre_nourriture INT4,
+    ADD COLUMN nombre_cadre_libre INT4;
+
+-- Restauration des valeurs du dernier relevé
+UPDATE ruche
+SET
+    nombre_cadres_corp = derniere.nombre_cadres_corp,
+    nombre_hausses = derniere.nombre_hausses,
+    nombre_cadre_couvain = derniere.nombre_cadre_couvain,
+    nombre_cadre_nourriture = derniere.nombre_cadre_nourriture,
+    nombre_cadre_libre = derniere.nombre_cadre_libre
+FROM (
+    SELECT DISTINCT ON (id_ruche) *
+    FROM configuration_ruche
+    ORDER BY id_ruche, date_releve DESC, id DESC
+) AS derniere
+WHERE derniere.id_ruche = ruche.id;
+
+DROP TABLE configuration_ruche;
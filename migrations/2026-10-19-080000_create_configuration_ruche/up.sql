@@ -0,0 +1,38 @@
+-- Historique des configurations de cadres d'une ruche
+CREATE TABLE configuration_ruche (
+    id SERIAL PRIMARY KEY,
+    id_ruche INT4 NOT NULL REFERENCES ruche(id) ON DELETE CASCADE,
+    nombre_cadres_corp INT4,
+    nombre_hausses INT4,
+    nombre_cadre_couvain INT4,
+    nombre_cadre_nourriture INT4,
+    nombre_cadre_libre INT4,
+    date_releve TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_configuration_ruche_ruche_date ON configuration_ruche (id_ruche, date_releve);
+
+-- Reprise des valeurs actuelles comme premier relevé de chaque ruche
+INSERT INTO configuration_ruche (
+    id_ruche,
+    nombre_cadres_corp,
+    nombre_hausses,
+    nombre_cadre_couvain,
+    nombre_cadre_nourriture,
+    nombre_cadre_libre
+)
+SELECT
+    id,
+    nombre_cadres_corp,
+    nombre_hausses,
+    nombre_cadre_couvain,
+    nombre_cadre_nourriture,
+    nombre_cadre_libre
+FROM ruche;
+
+ALTER TABLE ruche
+    DROP COLUMN nombre_cadres_corp,
+    DROP COLUMN nombre_hausses,
+    DROP COLUMN nombre_cadre_couvain,
+    DROP COLUMN nombre_cadre_nourriture,
+    DROP COLUMN nombre_cadre_libre;
@@ -0,0 +1,3 @@
+DROP TABLE pose_hausse;
+
+ALTER TABLE materiel DROP COLUMN poids_tare;
@@ -0,0 +1,19 @@
+-- Poids à vide du matériel (hausses, corps, toits), en grammes
+ALTER TABLE materiel ADD COLUMN poids_tare INT4;
+
+-- Historique de pose et de retrait des hausses sur les ruches
+CREATE TABLE pose_hausse (
+    id SERIAL PRIMARY KEY,
+    id_materiel INT4 NOT NULL REFERENCES materiel(id) ON DELETE CASCADE,
+    id_ruche INT4 NOT NULL REFERENCES ruche(id) ON DELETE CASCADE,
+    date_pose DATE NOT NULL DEFAULT CURRENT_DATE,
+    date_retrait DATE,
+    poids_miel_extrait INT4,
+    id_production INT4 REFERENCES production(id) ON DELETE SET NULL,
+    CHECK (date_retrait IS NULL OR date_retrait >= date_pose)
+);
+
+CREATE INDEX idx_pose_hausse_ruche ON pose_hausse (id_ruche);
+
+-- Une hausse ne peut être posée que sur une seule ruche à la fois
+CREATE UNIQUE INDEX idx_pose_hausse_en_place ON pose_hausse (id_materiel) WHERE date_retrait IS NULL;
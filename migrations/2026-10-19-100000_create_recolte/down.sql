@@ -0,0 +1,9 @@
+ALTER TABLE pose_hausse
+    DROP CONSTRAINT pose_hausse_poids_check,
+    DROP COLUMN poids_vide,
+    DROP COLUMN poids_plein,
+    DROP COLUMN id_recolte;
+
+DROP TABLE recolte;
+DROP TABLE lot_production;
+DROP TABLE lot_miel;
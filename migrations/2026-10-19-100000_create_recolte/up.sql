@@ -0,0 +1,31 @@
+-- Lots de miel issus d'une ou plusieurs productions
+CREATE TABLE lot_miel (
+    id SERIAL PRIMARY KEY,
+    numero_lot VARCHAR(50) NOT NULL UNIQUE,
+    type_miel VARCHAR(50),
+    date_extraction DATE,
+    taux_humidite FLOAT8
+);
+
+CREATE TABLE lot_production (
+    id_lot INT4 NOT NULL REFERENCES lot_miel(id) ON DELETE CASCADE,
+    id_production INT4 NOT NULL REFERENCES production(id) ON DELETE CASCADE,
+    PRIMARY KEY (id_lot, id_production)
+);
+
+-- Session de récolte regroupant les ruches récoltées le même jour
+CREATE TABLE recolte (
+    id SERIAL PRIMARY KEY,
+    id_apiculteur INT4 REFERENCES utilisateur(id) ON DELETE SET NULL,
+    date_recolte DATE NOT NULL,
+    type_miel VARCHAR(50),
+    taux_humidite FLOAT8,
+    id_lot INT4 REFERENCES lot_miel(id) ON DELETE SET NULL
+);
+
+-- Pesées des hausses récoltées
+ALTER TABLE pose_hausse
+    ADD COLUMN id_recolte INT4 REFERENCES recolte(id) ON DELETE SET NULL,
+    ADD COLUMN poids_plein INT4,
+    ADD COLUMN poids_vide INT4,
+    ADD CONSTRAINT pose_hausse_poids_check CHECK (poids_plein IS NULL OR poids_vide IS NULL OR poids_plein >= poids_vide);
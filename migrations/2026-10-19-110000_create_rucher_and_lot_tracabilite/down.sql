@@ -0,0 +1,9 @@
+DROP INDEX idx_lot_production_production;
+
+DROP TABLE lot_pot;
+
+ALTER TABLE lot_miel DROP COLUMN dluo;
+
+ALTER TABLE ruche DROP COLUMN id_rucher;
+
+DROP TABLE rucher;
@@ -0,0 +1,26 @@
+-- Ruchers (emplacements regroupant des ruches)
+CREATE TABLE rucher (
+    id SERIAL PRIMARY KEY,
+    id_apiculteur INT4 REFERENCES utilisateur(id) ON DELETE CASCADE,
+    nom_rucher VARCHAR(50),
+    localisation VARCHAR(255),
+    latitude FLOAT8,
+    longitude FLOAT8
+);
+
+ALTER TABLE ruche ADD COLUMN id_rucher INT4 REFERENCES rucher(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_ruche_rucher ON ruche (id_rucher);
+
+-- Date limite d'utilisation optimale du lot
+ALTER TABLE lot_miel ADD COLUMN dluo DATE;
+
+-- Nombre de pots conditionnés par contenance (en grammes)
+CREATE TABLE lot_pot (
+    id_lot INT4 NOT NULL REFERENCES lot_miel(id) ON DELETE CASCADE,
+    contenance INT4 NOT NULL CHECK (contenance > 0),
+    nombre_pots INT4 NOT NULL DEFAULT 0 CHECK (nombre_pots >= 0),
+    PRIMARY KEY (id_lot, contenance)
+);
+
+CREATE INDEX idx_lot_production_production ON lot_production (id_production);
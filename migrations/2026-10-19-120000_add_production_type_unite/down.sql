@@ -0,0 +1,6 @@
+ALTER TABLE production
+    DROP COLUMN variete_miel,
+    DROP COLUMN unite,
+    DROP COLUMN type_produit;
+
+DROP TABLE unite_mesure;
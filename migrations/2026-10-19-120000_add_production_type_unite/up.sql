@@ -0,0 +1,27 @@
+-- Unités de mesure et facteur de conversion vers l'unité de base de leur grandeur
+CREATE TABLE unite_mesure (
+    code VARCHAR(10) PRIMARY KEY,
+    unite_base VARCHAR(10) NOT NULL,
+    facteur FLOAT8 NOT NULL CHECK (facteur > 0)
+);
+
+INSERT INTO unite_mesure (code, unite_base, facteur) VALUES
+    ('mg', 'g', 0.001),
+    ('g', 'g', 1),
+    ('kg', 'g', 1000),
+    ('ml', 'ml', 1),
+    ('l', 'ml', 1000);
+
+-- Les productions existantes sont des récoltes de miel en grammes
+ALTER TABLE production
+    ADD COLUMN type_produit VARCHAR(20) NOT NULL DEFAULT 'miel'
+        CHECK (type_produit IN ('miel', 'pollen', 'propolis', 'cire', 'gelee_royale')),
+    ADD COLUMN unite VARCHAR(10) NOT NULL DEFAULT 'g' REFERENCES unite_mesure(code),
+    ADD COLUMN variete_miel VARCHAR(50);
+
+-- Reprise de la variété des productions issues d'une session de récolte
+UPDATE production
+SET variete_miel = recolte.type_miel
+FROM pose_hausse
+JOIN recolte ON recolte.id = pose_hausse.id_recolte
+WHERE pose_hausse.id_production = production.id;
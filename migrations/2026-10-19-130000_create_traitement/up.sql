@@ -0,0 +1,21 @@
+-- Registre d'élevage : traitements médicamenteux administrés aux ruches
+CREATE TABLE traitement (
+    id SERIAL PRIMARY KEY,
+    id_ruche INT4 NOT NULL REFERENCES ruche(id) ON DELETE CASCADE,
+    produit VARCHAR(100) NOT NULL,
+    -- Numéro d'autorisation de mise sur le marché
+    numero_amm VARCHAR(50),
+    -- Numéro de lot du médicament
+    numero_lot_produit VARCHAR(50),
+    dose VARCHAR(100),
+    date_debut DATE NOT NULL,
+    -- Absente tant que le traitement est en cours
+    date_fin DATE,
+    -- Délai d'attente en jours après la fin du traitement, au plus un an
+    delai_attente INT4 NOT NULL DEFAULT 0 CHECK (delai_attente BETWEEN 0 AND 365),
+    veterinaire VARCHAR(100),
+    numero_ordonnance VARCHAR(50),
+    CONSTRAINT traitement_dates_check CHECK (date_fin IS NULL OR date_fin >= date_debut)
+);
+
+CREATE INDEX idx_traitement_ruche_date ON traitement (id_ruche, date_debut);
@@ -0,0 +1,3 @@
+DROP TABLE alerte;
+DROP TABLE seuil_varroa;
+DROP TABLE comptage_varroa;
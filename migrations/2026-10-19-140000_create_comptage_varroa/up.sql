@@ -0,0 +1,66 @@
+-- Comptages de varroas
+-- Le taux d'infestation est exprimé en varroas pour 100 abeilles pour les prélèvements
+-- (lavage à l'alcool, sucre glace) et en varroas tombés par jour pour les langes (chute naturelle)
+CREATE TABLE comptage_varroa (
+    id SERIAL PRIMARY KEY,
+    id_ruche INT4 NOT NULL REFERENCES ruche(id) ON DELETE CASCADE,
+    date_comptage DATE NOT NULL DEFAULT CURRENT_DATE,
+    methode VARCHAR(20) NOT NULL CHECK (methode IN ('lavage_alcool', 'sucre_glace', 'chute_naturelle')),
+    nombre_varroas INT4 NOT NULL CHECK (nombre_varroas >= 0),
+    -- Taille de l'échantillon d'abeilles (prélèvements)
+    nombre_abeilles INT4 CHECK (nombre_abeilles > 0),
+    -- Durée de pose du lange en jours (chute naturelle)
+    duree_jours INT4 CHECK (duree_jours > 0),
+    commentaire TEXT,
+    taux_infestation FLOAT8 GENERATED ALWAYS AS (
+        CASE
+            WHEN methode = 'chute_naturelle' THEN nombre_varroas::float8 / duree_jours
+            ELSE nombre_varroas::float8 * 100 / nombre_abeilles
+        END
+    ) STORED,
+    CONSTRAINT comptage_varroa_echantillon_check CHECK (
+        (methode = 'chute_naturelle' AND duree_jours IS NOT NULL)
+        OR (methode <> 'chute_naturelle' AND nombre_abeilles IS NOT NULL)
+    )
+);
+
+CREATE INDEX idx_comptage_varroa_ruche_date ON comptage_varroa (id_ruche, date_comptage);
+
+-- Seuils d'alerte saisonniers, dans l'unité du taux d'infestation de la méthode.
+-- Une saison peut chevaucher la fin de l'année (mois_debut > mois_fin)
+CREATE TABLE seuil_varroa (
+    id SERIAL PRIMARY KEY,
+    methode VARCHAR(20) NOT NULL CHECK (methode IN ('lavage_alcool', 'sucre_glace', 'chute_naturelle')),
+    libelle VARCHAR(50),
+    mois_debut INT4 NOT NULL CHECK (mois_debut BETWEEN 1 AND 12),
+    mois_fin INT4 NOT NULL CHECK (mois_fin BETWEEN 1 AND 12),
+    seuil FLOAT8 NOT NULL CHECK (seuil >= 0)
+);
+
+INSERT INTO seuil_varroa (methode, libelle, mois_debut, mois_fin, seuil) VALUES
+    ('lavage_alcool', 'printemps', 3, 6, 2),
+    ('lavage_alcool', 'été', 7, 8, 3),
+    ('lavage_alcool', 'automne', 9, 10, 2),
+    ('lavage_alcool', 'hiver', 11, 2, 1),
+    ('sucre_glace', 'printemps', 3, 6, 2),
+    ('sucre_glace', 'été', 7, 8, 3),
+    ('sucre_glace', 'automne', 9, 10, 2),
+    ('sucre_glace', 'hiver', 11, 2, 1),
+    ('chute_naturelle', 'printemps', 3, 5, 3),
+    ('chute_naturelle', 'été', 6, 8, 10),
+    ('chute_naturelle', 'automne', 9, 10, 5),
+    ('chute_naturelle', 'hiver', 11, 2, 1);
+
+-- Alertes levées sur une ruche, ouvertes tant qu'elles ne sont pas résolues
+CREATE TABLE alerte (
+    id SERIAL PRIMARY KEY,
+    id_ruche INT4 REFERENCES ruche(id) ON DELETE CASCADE,
+    type_alerte VARCHAR(30) NOT NULL,
+    message TEXT NOT NULL,
+    valeur FLOAT8,
+    seuil FLOAT8,
+    date_alerte TIMESTAMP NOT NULL DEFAULT NOW(),
+    date_resolution TIMESTAMP
+);
+
+CREATE INDEX idx_alerte_ouverte ON alerte (id_ruche) WHERE date_resolution IS NULL;
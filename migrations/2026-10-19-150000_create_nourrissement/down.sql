@@ -0,0 +1,3 @@
+DROP TABLE seuil_reserve;
+DROP TABLE nourrissement;
+DROP TABLE type_nourriture;
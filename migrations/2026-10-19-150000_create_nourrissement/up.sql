@@ -0,0 +1,38 @@
+-- Types de nourriture et part de chaque apport convertie en réserves (sucres stockés)
+CREATE TABLE type_nourriture (
+    code VARCHAR(20) PRIMARY KEY,
+    libelle VARCHAR(50) NOT NULL,
+    facteur_reserve FLOAT8 NOT NULL CHECK (facteur_reserve >= 0)
+);
+
+INSERT INTO type_nourriture (code, libelle, facteur_reserve) VALUES
+    ('sirop_1_1', 'Sirop 1:1', 0.5),
+    ('sirop_2_1', 'Sirop 2:1', 0.66),
+    ('candi', 'Candi', 0.9),
+    ('galette_proteinee', 'Galette protéinée', 0);
+
+-- Nourrissements des ruches, quantités en grammes
+CREATE TABLE nourrissement (
+    id SERIAL PRIMARY KEY,
+    id_ruche INT4 NOT NULL REFERENCES ruche(id) ON DELETE CASCADE,
+    date_nourrissement DATE NOT NULL DEFAULT CURRENT_DATE,
+    type_nourriture VARCHAR(20) NOT NULL REFERENCES type_nourriture(code),
+    quantite INT4 NOT NULL CHECK (quantite > 0),
+    commentaire TEXT
+);
+
+CREATE INDEX idx_nourrissement_ruche_date ON nourrissement (id_ruche, date_nourrissement);
+
+-- Seuils saisonniers de réserves hivernales en grammes.
+-- Une saison peut chevaucher la fin de l'année (mois_debut > mois_fin)
+CREATE TABLE seuil_reserve (
+    id SERIAL PRIMARY KEY,
+    libelle VARCHAR(50),
+    mois_debut INT4 NOT NULL CHECK (mois_debut BETWEEN 1 AND 12),
+    mois_fin INT4 NOT NULL CHECK (mois_fin BETWEEN 1 AND 12),
+    seuil INT4 NOT NULL CHECK (seuil >= 0)
+);
+
+INSERT INTO seuil_reserve (libelle, mois_debut, mois_fin, seuil) VALUES
+    ('automne', 9, 11, 15000),
+    ('hiver', 12, 2, 8000);
@@ -0,0 +1,4 @@
+DROP FUNCTION tare_ruche(INT4, DATE);
+DROP TRIGGER materiel_affectation ON materiel;
+DROP FUNCTION materiel_affectation();
+DROP TABLE affectation_materiel;
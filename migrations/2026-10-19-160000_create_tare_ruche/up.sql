@@ -0,0 +1,103 @@
+-- Historique des affectations du matériel aux ruches, afin de dater le matériel fixe dans la tare. Une ligne est
+-- enregistrée à chaque création de matériel sur une ruche et à chaque changement de ruche, quel que soit le chemin
+-- d'écriture ; les affectations antérieures à cette migration ne sont pas connues
+CREATE TABLE affectation_materiel (
+    id SERIAL PRIMARY KEY,
+    id_materiel INT4 NOT NULL REFERENCES materiel(id) ON DELETE CASCADE,
+    id_ruche_precedente INT4 REFERENCES ruche(id) ON DELETE SET NULL,
+    id_ruche INT4 REFERENCES ruche(id) ON DELETE SET NULL,
+    date_affectation TIMESTAMP NOT NULL DEFAULT (now() AT TIME ZONE 'UTC')
+);
+
+CREATE FUNCTION materiel_affectation() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' AND NEW.id_ruche IS NOT NULL
+        OR TG_OP = 'UPDATE' AND NEW.id_ruche IS DISTINCT FROM OLD.id_ruche THEN
+        INSERT INTO affectation_materiel (id_materiel, id_ruche_precedente, id_ruche)
+        VALUES (NEW.id, CASE WHEN TG_OP = 'UPDATE' THEN OLD.id_ruche END, NEW.id_ruche);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER materiel_affectation
+    AFTER INSERT OR UPDATE OF id_ruche ON materiel
+    FOR EACH ROW EXECUTE FUNCTION materiel_affectation();
+
+CREATE INDEX idx_affectation_materiel_ruche ON affectation_materiel (id_ruche);
+CREATE INDEX idx_affectation_materiel_ruche_precedente ON affectation_materiel (id_ruche_precedente);
+CREATE INDEX idx_affectation_materiel_materiel ON affectation_materiel (id_materiel, date_affectation);
+
+-- Tare d'une ruche à une date, en grammes :
+--   matériel fixe présent sur la ruche ce jour-là (corps, plancher, toit...), hors hausses : un matériel y est
+--     s'il y a été affecté en dernier avant la fin de ce jour, à défaut d'affectation antérieure s'il y était
+--     avant sa première affectation postérieure, et à défaut d'historique s'il y est actuellement,
+--   + hausses posées ce jour-là (posées le jour de la pose, retirées le jour du retrait),
+--   + hausses du dernier relevé de configuration non suivies individuellement,
+--     comptées au poids moyen des hausses de l'inventaire
+CREATE FUNCTION tare_ruche(ruche_id INT4, jour DATE) RETURNS INT4 AS $$
+    WITH hausses_posees AS (
+        SELECT COUNT(*) AS nombre, COALESCE(SUM(m.poids_tare), 0) AS tare
+        FROM pose_hausse ph
+        JOIN materiel m ON m.id = ph.id_materiel
+        WHERE ph.id_ruche = ruche_id
+          AND ph.date_pose <= jour
+          AND (ph.date_retrait IS NULL OR ph.date_retrait > jour)
+    )
+    SELECT (
+        COALESCE((
+            SELECT SUM(m.poids_tare)
+            FROM materiel m
+            LEFT JOIN LATERAL (
+                SELECT TRUE AS connue, a.id_ruche
+                FROM affectation_materiel a
+                WHERE a.id_materiel = m.id AND a.date_affectation < jour + 1
+                ORDER BY a.date_affectation DESC, a.id DESC
+                LIMIT 1
+            ) avant ON TRUE
+            LEFT JOIN LATERAL (
+                SELECT TRUE AS connue, a.id_ruche_precedente
+                FROM affectation_materiel a
+                WHERE a.id_materiel = m.id AND a.date_affectation >= jour + 1
+                ORDER BY a.date_affectation, a.id
+                LIMIT 1
+            ) apres ON TRUE
+            WHERE (
+                    m.id_ruche = ruche_id
+                    OR EXISTS (
+                        SELECT 1 FROM affectation_materiel a
+                        WHERE a.id_materiel = m.id AND (a.id_ruche = ruche_id OR a.id_ruche_precedente = ruche_id)
+                    )
+                )
+              AND CASE
+                    WHEN avant.connue THEN avant.id_ruche
+                    WHEN apres.connue THEN apres.id_ruche_precedente
+                    ELSE m.id_ruche
+                  END = ruche_id
+              AND m.type_materiel IS DISTINCT FROM 'hausse'
+              AND NOT EXISTS (
+                  SELECT 1 FROM pose_hausse ph WHERE ph.id_materiel = m.id AND ph.date_retrait IS NULL
+              )
+        ), 0)
+        + hp.tare
+        + GREATEST(COALESCE((
+            SELECT c.nombre_hausses
+            FROM configuration_ruche c
+            WHERE c.id_ruche = ruche_id AND c.date_releve < jour + 1
+            ORDER BY c.date_releve DESC
+            LIMIT 1
+        ), 0) - hp.nombre, 0)
+        * COALESCE((
+            SELECT AVG(m.poids_tare) FROM materiel m WHERE m.type_materiel = 'hausse'
+        ), 0)
+    )::int4
+    FROM hausses_posees hp
+$$ LANGUAGE sql STABLE STRICT;
+
+COMMENT ON FUNCTION tare_ruche(INT4, DATE) IS
+    'Tare d''une ruche à une date, en grammes : matériel fixe présent ce jour-là d''après affectation_materiel, '
+    'hausses posées ce jour-là et hausses du dernier relevé de configuration non suivies individuellement. '
+    'Limites : les affectations antérieures à l''historique sont inconnues (un matériel sans historique est compté '
+    'sur sa ruche actuelle, un matériel déplacé avant l''historique sur la ruche quittée lors de sa première '
+    'affectation connue), le poids de tare et le type du matériel sont ceux du jour et le matériel supprimé '
+    'disparaît des tares passées.';
@@ -0,0 +1,17 @@
+-- Appareils connectés (balances, capteurs) associés à une ruche
+CREATE TABLE appareil (
+    id SERIAL PRIMARY KEY,
+    numero_serie VARCHAR(64) NOT NULL UNIQUE,
+    modele VARCHAR(50) NOT NULL,
+    version_firmware VARCHAR(30),
+    -- Ruche à laquelle sont attribuées les mesures de l'appareil
+    id_ruche INT4 REFERENCES ruche(id) ON DELETE SET NULL,
+    id_apiculteur INT4 REFERENCES utilisateur(id) ON DELETE CASCADE,
+    -- Pourcentage de charge de la batterie
+    niveau_batterie INT4 CHECK (niveau_batterie BETWEEN 0 AND 100),
+    derniere_connexion TIMESTAMP,
+    -- Empreinte Argon2 de la clé d'API de l'appareil
+    cle_api VARCHAR(255)
+);
+
+CREATE INDEX idx_appareil_ruche ON appareil (id_ruche);
@@ -0,0 +1,3 @@
+ALTER TABLE alerte
+    DROP COLUMN id_appareil,
+    DROP COLUMN id_apiculteur;
@@ -0,0 +1,6 @@
+-- Alertes portant sur un appareil connecté, adressées à l'apiculteur qui en est propriétaire
+ALTER TABLE alerte
+    ADD COLUMN id_appareil INT4 REFERENCES appareil(id) ON DELETE CASCADE,
+    ADD COLUMN id_apiculteur INT4 REFERENCES utilisateur(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_alerte_appareil_ouverte ON alerte (id_appareil) WHERE date_resolution IS NULL;
@@ -0,0 +1,5 @@
+DROP TABLE releve_capteur;
+
+ALTER TABLE appareil
+    DROP COLUMN dev_eui,
+    DROP COLUMN dernier_rssi;
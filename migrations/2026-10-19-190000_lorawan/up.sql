@@ -0,0 +1,17 @@
+-- Identifiant LoRaWAN des appareils (DevEUI, en majuscules) et qualité de leur dernière réception
+ALTER TABLE appareil
+    ADD COLUMN dev_eui VARCHAR(16) UNIQUE CHECK (dev_eui ~ '^[0-9A-F]{16}$'),
+    ADD COLUMN dernier_rssi INT4;
+
+-- Relevés des capteurs d'une ruche autres que le poids
+CREATE TABLE releve_capteur (
+    id SERIAL PRIMARY KEY,
+    id_ruche INT4 NOT NULL REFERENCES ruche(id) ON DELETE CASCADE,
+    id_appareil INT4 REFERENCES appareil(id) ON DELETE SET NULL,
+    type_mesure VARCHAR(30) NOT NULL CHECK (type_mesure IN ('temperature', 'humidite')),
+    valeur FLOAT8 NOT NULL,
+    unite VARCHAR(10) NOT NULL,
+    date_releve TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_releve_capteur_ruche ON releve_capteur (id_ruche, type_mesure, date_releve);
@@ -0,0 +1,8 @@
+DELETE FROM releve_capteur WHERE type_mesure NOT IN ('temperature', 'humidite');
+
+ALTER TABLE releve_capteur
+    DROP CONSTRAINT releve_capteur_type_mesure_fkey,
+    ADD CONSTRAINT releve_capteur_type_mesure_check CHECK (type_mesure IN ('temperature', 'humidite')),
+    ALTER COLUMN date_releve DROP DEFAULT;
+
+DROP TABLE type_mesure;
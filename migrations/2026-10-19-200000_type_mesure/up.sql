@@ -0,0 +1,18 @@
+-- Grandeurs mesurées par les capteurs des ruches, avec leur unité
+CREATE TABLE type_mesure (
+    code VARCHAR(30) PRIMARY KEY,
+    libelle VARCHAR(50) NOT NULL,
+    unite VARCHAR(10) NOT NULL
+);
+
+INSERT INTO type_mesure (code, libelle, unite) VALUES
+    ('temperature', 'Température intérieure', '°C'),
+    ('humidite', 'Humidité relative intérieure', '%'),
+    ('frequence_son', 'Fréquence sonore dominante', 'Hz'),
+    ('entrees', 'Entrées au trou de vol', 'abeilles'),
+    ('sorties', 'Sorties au trou de vol', 'abeilles');
+
+ALTER TABLE releve_capteur
+    DROP CONSTRAINT releve_capteur_type_mesure_check,
+    ADD CONSTRAINT releve_capteur_type_mesure_fkey FOREIGN KEY (type_mesure) REFERENCES type_mesure(code),
+    ALTER COLUMN date_releve SET DEFAULT NOW();
@@ -0,0 +1,4 @@
+DROP TRIGGER poids_horodatage ON poids;
+DROP FUNCTION poids_horodatage();
+
+ALTER TABLE poids DROP COLUMN horodatage;
@@ -0,0 +1,30 @@
+-- Instant de la pesée, pour les séries à haute fréquence des balances connectées.
+-- Les pesées existantes sont datées du début de leur jour
+ALTER TABLE poids ADD COLUMN horodatage TIMESTAMP;
+UPDATE poids SET horodatage = date_creation::timestamp;
+
+-- Tient l'instant et la date de la pesée en cohérence quel que soit le chemin d'écriture : une pesée sans
+-- instant est datée du début de son jour, et la date suit l'instant. Changer la date d'une pesée sans
+-- préciser l'instant conserve son heure
+CREATE FUNCTION poids_horodatage() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'UPDATE'
+        AND NEW.date_creation IS DISTINCT FROM OLD.date_creation
+        AND NEW.horodatage IS NOT DISTINCT FROM OLD.horodatage THEN
+        NEW.horodatage := NEW.date_creation + COALESCE(OLD.horodatage::time, TIME '00:00');
+    END IF;
+
+    IF NEW.horodatage IS NULL THEN
+        NEW.horodatage := NEW.date_creation::timestamp;
+    ELSE
+        NEW.date_creation := NEW.horodatage::date;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER poids_horodatage
+    BEFORE INSERT OR UPDATE ON poids
+    FOR EACH ROW EXECUTE FUNCTION poids_horodatage();
+
+CREATE INDEX idx_poids_ruche_horodatage ON poids (id_ruche, horodatage);
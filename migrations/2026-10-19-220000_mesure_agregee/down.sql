@@ -0,0 +1,6 @@
+DROP INDEX idx_releve_capteur_non_agrege;
+DROP INDEX idx_poids_non_agrege;
+ALTER TABLE releve_capteur DROP COLUMN agrege;
+ALTER TABLE poids DROP COLUMN agrege;
+DROP TABLE mesure_journaliere;
+DROP TABLE mesure_horaire;
@@ -0,0 +1,43 @@
+-- Agrégats horaires et journaliers des pesées (type `poids`) et des relevés de capteurs, qui prennent le relais
+-- des mesures brutes une fois celles-ci élaguées. La somme permet de recomposer des moyennes sur des intervalles
+-- plus larges, la première et la dernière mesure de calculer des variations ; leur instant permet de fusionner une
+-- mesure arrivée en retard avec l'agrégat existant de son intervalle
+CREATE TABLE mesure_horaire (
+    id_ruche INT4 NOT NULL REFERENCES ruche(id) ON DELETE CASCADE,
+    type_mesure VARCHAR(30) NOT NULL,
+    debut TIMESTAMP NOT NULL,
+    nombre_mesures INT4 NOT NULL,
+    somme FLOAT8 NOT NULL,
+    minimum FLOAT8 NOT NULL,
+    maximum FLOAT8 NOT NULL,
+    premiere FLOAT8 NOT NULL,
+    derniere FLOAT8 NOT NULL,
+    instant_premiere TIMESTAMP,
+    instant_derniere TIMESTAMP,
+    PRIMARY KEY (id_ruche, type_mesure, debut)
+);
+
+CREATE TABLE mesure_journaliere (
+    id_ruche INT4 NOT NULL REFERENCES ruche(id) ON DELETE CASCADE,
+    type_mesure VARCHAR(30) NOT NULL,
+    debut TIMESTAMP NOT NULL,
+    nombre_mesures INT4 NOT NULL,
+    somme FLOAT8 NOT NULL,
+    minimum FLOAT8 NOT NULL,
+    maximum FLOAT8 NOT NULL,
+    premiere FLOAT8 NOT NULL,
+    derniere FLOAT8 NOT NULL,
+    instant_premiere TIMESTAMP,
+    instant_derniere TIMESTAMP,
+    PRIMARY KEY (id_ruche, type_mesure, debut)
+);
+
+CREATE INDEX idx_mesure_horaire_debut ON mesure_horaire (debut);
+
+-- Les mesures brutes déjà agrégées sont marquées, afin que les mesures arrivées en retard ou antidatées soient
+-- ajoutées aux agrégats existants lors de l'agrégation suivante
+ALTER TABLE poids ADD COLUMN agrege BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE releve_capteur ADD COLUMN agrege BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_poids_non_agrege ON poids (horodatage) WHERE NOT agrege;
+CREATE INDEX idx_releve_capteur_non_agrege ON releve_capteur (date_releve) WHERE NOT agrege;
@@ -0,0 +1 @@
+DROP INDEX idx_poids_ruche_date;
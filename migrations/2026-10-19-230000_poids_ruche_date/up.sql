@@ -0,0 +1,2 @@
+-- Les moyennes des pesées filtrent les pesées d'une ruche par date
+CREATE INDEX idx_poids_ruche_date ON poids (id_ruche, date_creation);
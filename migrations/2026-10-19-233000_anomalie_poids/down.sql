@@ -0,0 +1,3 @@
+DROP TABLE correction_poids;
+ALTER TABLE poids DROP COLUMN exclu;
+DROP TABLE anomalie_poids;
@@ -0,0 +1,30 @@
+-- Pesées invraisemblables signalées par la détection d'anomalies, à confirmer ou rejeter par l'apiculteur
+CREATE TABLE anomalie_poids (
+    id SERIAL PRIMARY KEY,
+    id_poids INT4 NOT NULL REFERENCES poids(id) ON DELETE CASCADE,
+    type_anomalie VARCHAR(20) NOT NULL CHECK (type_anomalie IN ('saut', 'negatif', 'bloque')),
+    detail TEXT NOT NULL,
+    statut VARCHAR(20) NOT NULL DEFAULT 'a_verifier' CHECK (statut IN ('a_verifier', 'confirmee', 'rejetee')),
+    date_detection TIMESTAMP NOT NULL DEFAULT NOW(),
+    date_decision TIMESTAMP,
+    UNIQUE (id_poids, type_anomalie)
+);
+
+-- Une pesée faisant l'objet d'une anomalie non rejetée est écartée des agrégations
+ALTER TABLE poids ADD COLUMN exclu BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Historique des corrections apportées aux pesées
+CREATE TABLE correction_poids (
+    id SERIAL PRIMARY KEY,
+    id_poids INT4 REFERENCES poids(id) ON DELETE SET NULL,
+    ancienne_ruche INT4,
+    nouvelle_ruche INT4,
+    ancien_poids INT4,
+    nouveau_poids INT4,
+    ancien_horodatage TIMESTAMP,
+    nouvel_horodatage TIMESTAMP,
+    motif TEXT,
+    date_correction TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_correction_poids_poids ON correction_poids (id_poids);
@@ -0,0 +1,13 @@
+-- Observations météo journalières d'un rucher, saisies ou importées d'un fournisseur
+CREATE TABLE observation_meteo (
+    id SERIAL PRIMARY KEY,
+    id_rucher INT4 NOT NULL REFERENCES rucher(id) ON DELETE CASCADE,
+    date_observation DATE NOT NULL,
+    temperature_min FLOAT8,
+    temperature_max FLOAT8,
+    precipitations FLOAT8 CHECK (precipitations >= 0),
+    vent FLOAT8 CHECK (vent >= 0),
+    source VARCHAR(30) NOT NULL DEFAULT 'manuelle',
+    CHECK (temperature_min <= temperature_max),
+    UNIQUE (id_rucher, date_observation)
+);
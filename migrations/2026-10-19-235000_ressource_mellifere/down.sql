@@ -0,0 +1 @@
+DROP TABLE ressource_mellifere;
@@ -0,0 +1,13 @@
+-- Ressources mellifères autour d'un rucher, avec leur période de floraison attendue chaque année
+CREATE TABLE ressource_mellifere (
+    id SERIAL PRIMARY KEY,
+    id_rucher INT4 NOT NULL REFERENCES rucher(id) ON DELETE CASCADE,
+    espece VARCHAR(100) NOT NULL,
+    -- Seuls le jour et le mois comptent ; une fin antérieure au début s'entend l'année suivante
+    debut_floraison DATE NOT NULL,
+    fin_floraison DATE NOT NULL,
+    -- Surface en hectares
+    surface FLOAT8 CHECK (surface >= 0)
+);
+
+CREATE INDEX idx_ressource_mellifere_rucher ON ressource_mellifere (id_rucher);
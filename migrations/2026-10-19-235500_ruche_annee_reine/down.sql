@@ -0,0 +1 @@
+ALTER TABLE ruche DROP COLUMN annee_reine;
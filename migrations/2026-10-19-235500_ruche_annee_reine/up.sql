@@ -0,0 +1,2 @@
+-- Année de naissance de la reine, dont découle la couleur de marquage
+ALTER TABLE ruche ADD COLUMN annee_reine INT4 CHECK (annee_reine >= 1900);
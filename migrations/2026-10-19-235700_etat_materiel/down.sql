@@ -0,0 +1 @@
+ALTER TABLE materiel DROP CONSTRAINT materiel_etat_materiel_check;
@@ -0,0 +1,7 @@
+-- Vocabulaire des états de matériel ; les valeurs déjà saisies hors vocabulaire sont conservées
+-- (contrainte non validée) mais doivent être corrigées à la prochaine modification du matériel
+UPDATE materiel SET etat_materiel = LOWER(BTRIM(etat_materiel)) WHERE etat_materiel IS NOT NULL;
+
+ALTER TABLE materiel ADD CONSTRAINT materiel_etat_materiel_check CHECK (
+    etat_materiel IN ('disponible', 'en_service', 'a_reparer', 'endommage', 'mauvais', 'hors_service')
+) NOT VALID;
@@ -0,0 +1,111 @@
+use actix_web::{web, HttpResponse, Result, get, put};
+use crate::db::Pool;
+use crate::services::alerte_service;
+use diesel::result::Error as DieselError;
+use serde::Deserialize;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Filtres des alertes
+#[derive(Deserialize)]
+pub struct AlertesQuery {
+    id_ruche: Option<i32>,
+    id_apiculteur: Option<i32>,
+    // Uniquement les alertes non résolues
+    #[serde(default)]
+    ouvertes: bool,
+}
+
+// Obtenir les alertes
+#[get("/alertes")]
+pub async fn get_alertes(pool: web::Data<Pool>, query: web::Query<AlertesQuery>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        alerte_service::get_alertes(&mut conn, query.id_ruche, query.id_apiculteur, query.ouvertes)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(alertes) => Ok(HttpResponse::Ok().json(alertes)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Obtenir les alertes d'une ruche
+#[get("/ruches/{id}/alertes")]
+pub async fn get_alertes_by_ruche_id(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    query: web::Query<AlertesQuery>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        alerte_service::get_alertes(&mut conn, Some(id.into_inner()), query.id_apiculteur, query.ouvertes)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(alertes) => Ok(HttpResponse::Ok().json(alertes)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Marquer une alerte comme résolue
+#[put("/alertes/{id}/resolution")]
+pub async fn resoudre_alerte(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        alerte_service::resoudre_alerte(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(alerte) => Ok(HttpResponse::Ok().json(alerte)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Alerte introuvable")),
+        Err(e) => {
+            error!("Erreur lors de la résolution de l'alerte: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+use actix_web::{web, HttpResponse, Result, get, post, put};
+use crate::db::Pool;
+use crate::models::anomalie_poids_models::DecisionAnomalie;
+use crate::services::anomalie_poids_service;
+use chrono::NaiveDateTime;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::Deserialize;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Statut des anomalies à lister (a_verifier, confirmee ou rejetee), toutes par défaut
+#[derive(Deserialize)]
+pub struct AnomaliesQuery {
+    statut: Option<String>,
+}
+
+// Instant à partir de la veille duquel relancer la détection, tout l'historique par défaut
+#[derive(Deserialize)]
+pub struct DetectionQuery {
+    depuis: Option<NaiveDateTime>,
+}
+
+// Récupérer les anomalies des pesées d'une ruche
+#[get("/poids/ruche/{ruche_id}/anomalies")]
+pub async fn get_anomalies_by_ruche_id(
+    pool: web::Data<Pool>,
+    ruche_id: web::Path<i32>,
+    query: web::Query<AnomaliesQuery>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        anomalie_poids_service::get_anomalies_by_ruche_id(&mut conn, ruche_id.into_inner(), query.into_inner().statut)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(anomalies) => Ok(HttpResponse::Ok().json(anomalies)),
+        Err(e) => {
+            error!("Erreur lors de la récupération des anomalies: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Relancer la détection des anomalies des pesées d'une ruche
+#[post("/poids/ruche/{ruche_id}/anomalies/detection")]
+pub async fn detecter_anomalies(
+    pool: web::Data<Pool>,
+    ruche_id: web::Path<i32>,
+    query: web::Query<DetectionQuery>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        anomalie_poids_service::detecter_anomalies(&mut conn, ruche_id.into_inner(), query.into_inner().depuis)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(anomalies) => Ok(HttpResponse::Ok().json(anomalies)),
+        Err(e) => {
+            error!("Erreur lors de la détection des anomalies: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Confirmer ou rejeter une anomalie
+#[put("/poids/anomalies/{id}")]
+pub async fn decider_anomalie(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    decision: web::Json<DecisionAnomalie>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        anomalie_poids_service::decider_anomalie(&mut conn, id.into_inner(), decision.into_inner().statut)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(anomalie) => Ok(HttpResponse::Ok().json(anomalie)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Anomalie introuvable")),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Statut inconnu : a_verifier, confirmee ou rejetee attendu"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la décision sur l'anomalie: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
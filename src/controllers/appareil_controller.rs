@@ -0,0 +1,275 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result, get, post, put, delete};
+use crate::db::Pool;
+use crate::middleware::auth::Auth;
+use crate::models::appareil_models::{NewAppareil, UpdateAppareil, MesureAppareil, SurveillanceAppareils};
+use crate::models::utilisateur_models::TokenClaims;
+use crate::services::appareil_service;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// En-tête portant la clé d'API d'un appareil
+const EN_TETE_CLE_API: &str = "X-Api-Key";
+
+// Enregistrer la mesure d'un appareil authentifié par sa clé d'API
+#[post("/devices/mesures")]
+pub async fn create_mesure(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    mesure: web::Json<MesureAppareil>
+) -> Result<HttpResponse> {
+    let cle = match req.headers().get(EN_TETE_CLE_API).and_then(|v| v.to_str().ok()) {
+        Some(cle) => cle.to_string(),
+        None => return Ok(HttpResponse::Unauthorized().json("Clé d'API manquante")),
+    };
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        appareil_service::enregistrer_mesure(&mut conn, &cle, mesure.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(Some(poids)) => Ok(HttpResponse::Created().json(poids)),
+        Ok(None) => Ok(HttpResponse::Conflict().json("Appareil associé à aucune ruche, mesure ignorée")),
+        Err(DieselError::NotFound) => Ok(HttpResponse::Unauthorized().json("Clé d'API invalide")),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Le niveau de batterie doit être compris entre 0 et 100"))
+        }
+        Err(e) => {
+            error!("Erreur lors de l'enregistrement de la mesure: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Obtenir les appareils de l'apiculteur authentifié avec leur état de santé
+#[get("/devices", wrap = "Auth")]
+pub async fn get_all_appareils(
+    pool: web::Data<Pool>,
+    surveillance: web::Data<SurveillanceAppareils>,
+    claims: web::ReqData<TokenClaims>
+) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        appareil_service::get_all_appareils(&mut conn, &surveillance, Some(apiculteur_id))
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(appareils) => Ok(HttpResponse::Ok().json(appareils)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Obtenir un appareil de l'apiculteur authentifié par ID
+#[get("/devices/{id}", wrap = "Auth")]
+pub async fn get_appareil_by_id(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    claims: web::ReqData<TokenClaims>
+) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        appareil_service::get_appareil_by_id(&mut conn, id.into_inner(), apiculteur_id)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(appareil) => Ok(HttpResponse::Ok().json(appareil)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Appareil introuvable")),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Enregistrer un appareil de l'apiculteur authentifié, sa clé d'API n'est renvoyée qu'à cette occasion
+#[post("/devices", wrap = "Auth")]
+pub async fn create_appareil(
+    pool: web::Data<Pool>,
+    new_appareil: web::Json<NewAppareil>,
+    claims: web::ReqData<TokenClaims>
+) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        appareil_service::create_appareil(&mut conn, new_appareil.into_inner(), apiculteur_id)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(appareil) => Ok(HttpResponse::Created().json(appareil)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Ruche introuvable")),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            Ok(HttpResponse::Conflict().json("Un appareil porte déjà ce numéro de série ou ce DevEUI"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Ruche ou apiculteur inconnu"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Le DevEUI doit comporter 16 chiffres hexadécimaux"))
+        }
+        Err(e) => {
+            error!("Erreur lors de l'enregistrement de l'appareil: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Mettre à jour un appareil de l'apiculteur authentifié
+#[put("/devices/{id}", wrap = "Auth")]
+pub async fn update_appareil(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    appareil: web::Json<UpdateAppareil>,
+    claims: web::ReqData<TokenClaims>
+) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        appareil_service::update_appareil(&mut conn, id.into_inner(), appareil.into_inner(), apiculteur_id)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(appareil) => Ok(HttpResponse::Ok().json(appareil)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Appareil ou ruche introuvable")),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            Ok(HttpResponse::Conflict().json("Un appareil porte déjà ce numéro de série ou ce DevEUI"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Ruche ou apiculteur inconnu"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Le DevEUI doit comporter 16 chiffres hexadécimaux"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la mise à jour de l'appareil: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Attribuer une nouvelle clé d'API à un appareil de l'apiculteur authentifié
+#[post("/devices/{id}/cle", wrap = "Auth")]
+pub async fn regenerer_cle(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    claims: web::ReqData<TokenClaims>
+) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        appareil_service::regenerer_cle(&mut conn, id.into_inner(), apiculteur_id)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(appareil) => Ok(HttpResponse::Ok().json(appareil)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Appareil introuvable")),
+        Err(e) => {
+            error!("Erreur lors de la génération de la clé d'API: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Supprimer un appareil de l'apiculteur authentifié
+#[delete("/devices/{id}", wrap = "Auth")]
+pub async fn delete_appareil(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    claims: web::ReqData<TokenClaims>
+) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        appareil_service::delete_appareil(&mut conn, id.into_inner(), apiculteur_id)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            error!("Erreur lors de la suppression de l'appareil: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
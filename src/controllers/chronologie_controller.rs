@@ -0,0 +1,84 @@
+use actix_web::{web, HttpResponse, Result, get};
+use crate::db::Pool;
+use crate::models::chronologie_models::TYPES_EVENEMENT;
+use crate::services::chronologie_service;
+use diesel::result::Error as DieselError;
+use serde::Deserialize;
+use log::error;
+
+// Nombre d'événements par page par défaut et maximal
+const PAR_PAGE_DEFAUT: i64 = 50;
+const PAR_PAGE_MAX: i64 = 200;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Page à partir de 1, taille de page et types d'événement séparés par des virgules (tous par défaut)
+#[derive(Deserialize)]
+pub struct ChronologieQuery {
+    page: Option<i64>,
+    par_page: Option<i64>,
+    types: Option<String>,
+}
+
+// Récupérer la chronologie d'une ruche
+#[get("/ruches/{id}/timeline")]
+pub async fn get_chronologie(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    query: web::Query<ChronologieQuery>
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let page = query.page.unwrap_or(1);
+    let par_page = query.par_page.unwrap_or(PAR_PAGE_DEFAUT);
+    if page < 1 || !(1..=PAR_PAGE_MAX).contains(&par_page) {
+        return Ok(HttpResponse::BadRequest().json(format!(
+            "La page doit être au moins 1 et la taille de page comprise entre 1 et {}",
+            PAR_PAGE_MAX
+        )));
+    }
+    // Le décalage de la page doit tenir sur un entier
+    if (page - 1).checked_mul(par_page).is_none() {
+        return Ok(HttpResponse::BadRequest().json("Numéro de page trop grand"));
+    }
+    let types: Vec<String> = match query.types {
+        Some(types) => types.split(',').map(|t| t.trim().to_string()).collect(),
+        None => TYPES_EVENEMENT.iter().map(|t| t.to_string()).collect(),
+    };
+    if let Some(inconnu) = types.iter().find(|t| !TYPES_EVENEMENT.contains(&t.as_str())) {
+        return Ok(HttpResponse::BadRequest().json(format!(
+            "Type d'événement inconnu : {} ({} attendus)",
+            inconnu,
+            TYPES_EVENEMENT.join(", ")
+        )));
+    }
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        chronologie_service::get_chronologie(&mut conn, id.into_inner(), types, page, par_page)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(chronologie) => Ok(HttpResponse::Ok().json(chronologie)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Ruche introuvable")),
+        Err(e) => {
+            error!("Erreur lors de la récupération de la chronologie: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
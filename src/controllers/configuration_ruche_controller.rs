@@ -0,0 +1,75 @@
+use actix_web::{web, HttpResponse, Result, get, post};
+use crate::db::Pool;
+use crate::models::configuration_ruche_models::NewConfigurationRuche;
+use crate::services::configuration_ruche_service;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Historique des configurations de cadres d'une ruche
+#[get("/ruches/{id}/configurations")]
+pub async fn get_configurations_by_ruche_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        configuration_ruche_service::get_configurations_by_ruche_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(configurations) => Ok(HttpResponse::Ok().json(configurations)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Enregistrer un relevé de cadres daté (visite, inspection)
+#[post("/ruches/{id}/configurations")]
+pub async fn create_configuration(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    new_configuration: web::Json<NewConfigurationRuche>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let mut new_configuration = new_configuration.into_inner();
+    new_configuration.id_ruche = id.into_inner();
+
+    let result = match web::block(move || {
+        configuration_ruche_service::create_configuration(&mut conn, new_configuration)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(configuration) => Ok(HttpResponse::Created().json(configuration)),
+        Err(e) => {
+            error!("Erreur lors de l'enregistrement de la configuration: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,235 @@
+use actix_web::{web, HttpResponse, Result, get, post, put, delete};
+use crate::db::Pool;
+use crate::models::floraison_models::{NewRessourceMellifere, UpdateRessourceMellifere};
+use crate::services::floraison_service;
+use chrono::{Days, NaiveDate, Utc};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::Deserialize;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Horizon maximal des floraisons à venir, en jours
+const JOURS_MAX: u64 = 365;
+
+// Date de la vue (date du jour par défaut) et nombre de jours des floraisons à venir (14 par défaut)
+#[derive(Deserialize)]
+pub struct FloraisonsQuery {
+    date: Option<NaiveDate>,
+    jours: Option<u64>,
+}
+
+// Période des productions, jours inclus
+#[derive(Deserialize)]
+pub struct PeriodeQuery {
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+}
+
+// Récupérer les ressources mellifères d'un rucher
+#[get("/ruchers/{id}/ressources-melliferes")]
+pub async fn get_ressources_by_rucher_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        floraison_service::get_ressources_by_rucher_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(ressources) => Ok(HttpResponse::Ok().json(ressources)),
+        Err(e) => {
+            error!("Erreur lors de la récupération des ressources mellifères: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Ajouter une ressource mellifère à un rucher
+#[post("/ruchers/{id}/ressources-melliferes")]
+pub async fn create_ressource(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    new_ressource: web::Json<NewRessourceMellifere>
+) -> Result<HttpResponse> {
+    let mut new_ressource = new_ressource.into_inner();
+    new_ressource.id_rucher = id.into_inner();
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        floraison_service::create_ressource(&mut conn, new_ressource)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(ressource) => Ok(HttpResponse::Created().json(ressource)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Surface négative"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::NotFound().json("Rucher introuvable"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la création de la ressource mellifère: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Mettre à jour une ressource mellifère
+#[put("/ressources-melliferes/{id}")]
+pub async fn update_ressource(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    ressource: web::Json<UpdateRessourceMellifere>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        floraison_service::update_ressource(&mut conn, id.into_inner(), ressource.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(ressource) => Ok(HttpResponse::Ok().json(ressource)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Ressource mellifère introuvable")),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Surface négative"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la mise à jour de la ressource mellifère: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Supprimer une ressource mellifère
+#[delete("/ressources-melliferes/{id}")]
+pub async fn delete_ressource(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        floraison_service::delete_ressource(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            error!("Erreur lors de la suppression de la ressource mellifère: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Récupérer les floraisons du moment autour d'un rucher
+#[get("/ruchers/{id}/floraisons")]
+pub async fn get_floraisons_du_moment(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    query: web::Query<FloraisonsQuery>
+) -> Result<HttpResponse> {
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    let jours = query.jours.unwrap_or(14);
+    // Les floraisons à venir sont cherchées jusqu'au dernier jour de l'horizon, qui doit rester dans le calendrier
+    if jours > JOURS_MAX || date.checked_add_days(Days::new(JOURS_MAX + 1)).is_none() {
+        return Ok(HttpResponse::BadRequest().json(format!(
+            "L'horizon doit être d'au plus {} jours après une date valide",
+            JOURS_MAX
+        )));
+    }
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        floraison_service::get_floraisons_du_moment(&mut conn, id.into_inner(), date, jours)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(floraisons) => Ok(HttpResponse::Ok().json(floraisons)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Rucher introuvable")),
+        Err(e) => {
+            error!("Erreur lors de la récupération des floraisons: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Récupérer les productions d'une ruche avec les floraisons qui les ont précédées
+#[get("/productions/ruche/{ruche_id}/floraisons")]
+pub async fn get_productions_floraisons(
+    pool: web::Data<Pool>,
+    ruche_id: web::Path<i32>,
+    query: web::Query<PeriodeQuery>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        floraison_service::get_productions_floraisons(&mut conn, ruche_id.into_inner(), query.debut, query.fin)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(productions) => Ok(HttpResponse::Ok().json(productions)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Ruche introuvable")),
+        Err(e) => {
+            error!("Erreur lors de la récupération des productions et floraisons: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
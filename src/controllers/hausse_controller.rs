@@ -0,0 +1,204 @@
+use actix_web::{web, HttpResponse, Result, get, post};
+use crate::db::Pool;
+use crate::models::hausse_models::{NewPoseHausse, RetraitHausse, RecolteHausses, ErreurHausse};
+use crate::services::hausse_service;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Hausses actuellement posées sur une ruche
+#[get("/ruches/{id}/hausses")]
+pub async fn get_hausses_by_ruche_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        hausse_service::get_hausses_by_ruche_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(hausses) => Ok(HttpResponse::Ok().json(hausses)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Historique des poses de hausses sur une ruche
+#[get("/ruches/{id}/hausses/historique")]
+pub async fn get_poses_by_ruche_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        hausse_service::get_poses_by_ruche_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(poses) => Ok(HttpResponse::Ok().json(poses)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Poser une hausse sur une ruche
+#[post("/ruches/{id}/hausses")]
+pub async fn add_hausse(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    new_pose: web::Json<NewPoseHausse>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let mut new_pose = new_pose.into_inner();
+    new_pose.id_ruche = id.into_inner();
+
+    let result = match web::block(move || {
+        hausse_service::add_hausse(&mut conn, new_pose)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(pose) => Ok(HttpResponse::Created().json(pose)),
+        Err(ErreurHausse::Base(DieselError::NotFound)) => Ok(HttpResponse::NotFound().json("Matériel introuvable")),
+        Err(e @ ErreurHausse::PasUneHausse) => Ok(HttpResponse::BadRequest().json(e.to_string())),
+        Err(ErreurHausse::Base(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _))) => {
+            Ok(HttpResponse::Conflict().json("Cette hausse est déjà posée sur une ruche"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la pose de la hausse: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Historique des poses d'une hausse
+#[get("/hausses/{id}/poses")]
+pub async fn get_poses_by_materiel_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        hausse_service::get_poses_by_materiel_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(poses) => Ok(HttpResponse::Ok().json(poses)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Retirer une hausse, avec le poids de miel extrait lors d'une récolte
+#[post("/hausses/{id}/retrait")]
+pub async fn remove_hausse(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    retrait: web::Json<RetraitHausse>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        hausse_service::remove_hausse(&mut conn, id.into_inner(), retrait.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(pose) => Ok(HttpResponse::Ok().json(pose)),
+        Err(ErreurHausse::Base(DieselError::NotFound)) => {
+            Ok(HttpResponse::NotFound().json("Cette hausse n'est posée sur aucune ruche"))
+        }
+        Err(ErreurHausse::Base(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _))) => {
+            Ok(HttpResponse::BadRequest().json("La date de retrait précède la date de pose"))
+        }
+        Err(e @ ErreurHausse::PeseesIncoherentes) => Ok(HttpResponse::BadRequest().json(e.to_string())),
+        Err(e) => {
+            error!("Erreur lors du retrait de la hausse: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Générer les productions à partir des hausses récoltées à une date donnée
+#[post("/productions/hausses")]
+pub async fn create_productions_from_hausses(
+    pool: web::Data<Pool>,
+    recolte: web::Json<RecolteHausses>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        hausse_service::create_productions_from_hausses(&mut conn, recolte.date_recolte)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(productions) => Ok(HttpResponse::Created().json(productions)),
+        Err(e @ ErreurHausse::ProductionHorsLimites { .. }) => Ok(HttpResponse::BadRequest().json(e.to_string())),
+        Err(e) => {
+            error!("Erreur lors de la génération des productions: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
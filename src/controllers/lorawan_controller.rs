@@ -0,0 +1,89 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result, post};
+use crate::db::Pool;
+use crate::models::lorawan_models::UplinkLoRaWan;
+use crate::services::lorawan_service;
+use diesel::result::Error as DieselError;
+use serde::Deserialize;
+use log::{error, warn};
+use std::env;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// En-tête portant le jeton partagé avec le serveur de réseau
+const EN_TETE_JETON_WEBHOOK: &str = "X-Webhook-Token";
+
+// Événement notifié par ChirpStack, qui envoie tous ses événements à la même adresse
+#[derive(Deserialize)]
+pub struct WebhookQuery {
+    event: Option<String>,
+}
+
+// Recevoir un uplink d'un serveur de réseau LoRaWAN (The Things Stack ou ChirpStack)
+#[post("/lorawan/uplink")]
+pub async fn receive_uplink(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    query: web::Query<WebhookQuery>,
+    corps: web::Json<serde_json::Value>
+) -> Result<HttpResponse> {
+    let jeton = match env::var("LORAWAN_WEBHOOK_TOKEN") {
+        Ok(jeton) => jeton,
+        Err(_) => return Ok(HttpResponse::ServiceUnavailable().json("Webhook LoRaWAN non configuré")),
+    };
+    if req.headers().get(EN_TETE_JETON_WEBHOOK).and_then(|v| v.to_str().ok()) != Some(jeton.as_str()) {
+        return Ok(HttpResponse::Unauthorized().json("Jeton du webhook invalide"));
+    }
+
+    // Seuls les uplinks portent des mesures
+    if query.event.as_deref().is_some_and(|event| event != "up") {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let uplink = match serde_json::from_value::<UplinkLoRaWan>(corps.into_inner()) {
+        Ok(uplink) => match uplink.normaliser() {
+            Ok(uplink) => uplink,
+            Err(e) => return Ok(HttpResponse::BadRequest().json(format!("Charge utile base64 invalide: {}", e))),
+        },
+        Err(_) => return Ok(HttpResponse::BadRequest().json("Format d'uplink non reconnu")),
+    };
+    let dev_eui = uplink.dev_eui.clone();
+
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        lorawan_service::enregistrer_uplink(&mut conn, uplink)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(Some(resultat)) => Ok(HttpResponse::Created().json(resultat)),
+        Ok(None) => Ok(HttpResponse::Conflict().json("Appareil associé à aucune ruche, mesures ignorées")),
+        Err(DieselError::NotFound) => {
+            warn!("Uplink reçu d'un appareil inconnu : {}", dev_eui);
+            Ok(HttpResponse::NotFound().json(format!("Aucun appareil de DevEUI {}", dev_eui)))
+        }
+        Err(DieselError::DeserializationError(e)) => {
+            warn!("Uplink de {} indécodable : {}", dev_eui, e);
+            Ok(HttpResponse::UnprocessableEntity().json(e.to_string()))
+        }
+        Err(e) => {
+            error!("Erreur lors de l'enregistrement de l'uplink: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
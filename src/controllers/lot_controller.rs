@@ -0,0 +1,257 @@
+use actix_web::{web, HttpResponse, Result, get, post, put, delete};
+use crate::db::Pool;
+use crate::models::lot_models::{NewLotMiel, UpdateLotMiel, LotPot};
+use crate::services::lot_service;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Obtenir tous les lots
+#[get("/lots")]
+pub async fn get_all_lots(pool: web::Data<Pool>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        lot_service::get_all_lots(&mut conn)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(lots) => Ok(HttpResponse::Ok().json(lots)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Obtenir un lot avec ses productions et son conditionnement
+#[get("/lots/{id}")]
+pub async fn get_lot_by_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        lot_service::get_lot_detail(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(lot) => Ok(HttpResponse::Ok().json(lot)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Lot introuvable")),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Ruches et ruchers ayant contribué à un lot
+#[get("/lots/{id}/tracabilite")]
+pub async fn get_tracabilite_lot(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        lot_service::get_tracabilite_lot(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(tracabilite) => Ok(HttpResponse::Ok().json(tracabilite)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Lot introuvable")),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Lots auxquels une ruche a contribué
+#[get("/ruches/{id}/lots")]
+pub async fn get_lots_by_ruche_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        lot_service::get_lots_by_ruche_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(lots) => Ok(HttpResponse::Ok().json(lots)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Créer un lot à partir de productions
+#[post("/lots")]
+pub async fn create_lot(pool: web::Data<Pool>, new_lot: web::Json<NewLotMiel>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        lot_service::create_lot(&mut conn, new_lot.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(lot) => Ok(HttpResponse::Created().json(lot)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            Ok(HttpResponse::Conflict().json("Ce numéro de lot existe déjà"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Contenance ou nombre de pots invalide"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Production inconnue"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la création du lot: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Mettre à jour un lot
+#[put("/lots/{id}")]
+pub async fn update_lot(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    lot: web::Json<UpdateLotMiel>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        lot_service::update_lot(&mut conn, id.into_inner(), lot.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(lot) => Ok(HttpResponse::Ok().json(lot)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Lot introuvable")),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            Ok(HttpResponse::Conflict().json("Ce numéro de lot existe déjà"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la mise à jour du lot: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Remplacer le conditionnement d'un lot
+#[put("/lots/{id}/pots")]
+pub async fn replace_pots(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    pots: web::Json<Vec<LotPot>>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        lot_service::replace_pots(&mut conn, id.into_inner(), pots.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(pots) => Ok(HttpResponse::Ok().json(pots)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Contenance ou nombre de pots invalide"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la mise à jour du conditionnement: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Supprimer un lot
+#[delete("/lots/{id}")]
+pub async fn delete_lot(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        lot_service::delete_lot(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            error!("Erreur lors de la suppression du lot: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
@@ -1,8 +1,9 @@
 use actix_web::{web, HttpResponse, Result, get, post, put, delete};
 use crate::db::Pool;
-use crate::models::materiel_models::{Materiel, NewMateriel, UpdateMateriel};
+use crate::models::materiel_models::{etat_materiel_valide, Materiel, NewMateriel, UpdateMateriel, ETATS_MATERIEL};
 use crate::services::materiel_service;
 use chrono::NaiveDate;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use serde::Deserialize;
 use log::{error};
 use serde::Serialize;
@@ -30,6 +31,11 @@ fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnecti
     })
 }
 
+/// Réponse à un état de matériel hors vocabulaire
+fn etat_materiel_inconnu() -> HttpResponse {
+    HttpResponse::BadRequest().json(format!("État de matériel inconnu, valeurs possibles : {}", ETATS_MATERIEL.join(", ")))
+}
+
 /// Récupère tous les matériels
 #[get("/materiels")]
 pub async fn get_all_materiels(pool: web::Data<Pool>) -> Result<HttpResponse> {
@@ -86,6 +92,10 @@ pub async fn get_materiel_by_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Re
 /// Crée un nouveau matériel
 #[post("/materiels")]
 pub async fn create_materiel(pool: web::Data<Pool>, new_materiel: web::Json<NewMateriel>) -> Result<HttpResponse> {
+    if !etat_materiel_valide(new_materiel.etat_materiel.as_deref()) {
+        return Ok(etat_materiel_inconnu());
+    }
+
     let mut conn = match get_connection(&pool) {
         Ok(conn) => conn,
         Err(e) => return Ok(e),
@@ -103,6 +113,7 @@ pub async fn create_materiel(pool: web::Data<Pool>, new_materiel: web::Json<NewM
 
     match result {
         Ok(materiel) => Ok(HttpResponse::Created().json(materiel)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => Ok(etat_materiel_inconnu()),
         Err(e) => {
             error!("Erreur lors de la création du matériel: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
@@ -117,6 +128,10 @@ pub async fn update_materiel(
     id: web::Path<i32>,
     materiel: web::Json<UpdateMateriel>
 ) -> Result<HttpResponse> {
+    if !etat_materiel_valide(materiel.etat_materiel.as_deref()) {
+        return Ok(etat_materiel_inconnu());
+    }
+
     let mut conn = match get_connection(&pool) {
         Ok(conn) => conn,
         Err(e) => return Ok(e),
@@ -134,6 +149,7 @@ pub async fn update_materiel(
 
     match result {
         Ok(materiel) => Ok(HttpResponse::Ok().json(materiel)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => Ok(etat_materiel_inconnu()),
         Err(e) => {
             error!("Erreur lors de la mise à jour du matériel: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
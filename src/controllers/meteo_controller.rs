@@ -0,0 +1,171 @@
+use actix_web::{web, HttpResponse, Result, get, post, delete};
+use crate::db::Pool;
+use crate::models::meteo_models::{NewObservationMeteo, ConfigMeteo};
+use crate::services::meteo_service;
+use chrono::NaiveDate;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::Deserialize;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Période des observations, jours inclus
+#[derive(Deserialize)]
+pub struct PeriodeQuery {
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+}
+
+// Période à importer, jours inclus
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    debut: NaiveDate,
+    fin: NaiveDate,
+}
+
+// Récupérer les observations météo d'un rucher
+#[get("/ruchers/{id}/meteo")]
+pub async fn get_observations_by_rucher_id(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    query: web::Query<PeriodeQuery>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        meteo_service::get_observations_by_rucher_id(&mut conn, id.into_inner(), query.debut, query.fin)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(observations) => Ok(HttpResponse::Ok().json(observations)),
+        Err(e) => {
+            error!("Erreur lors de la récupération des observations météo: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Saisir l'observation météo d'un jour au rucher
+#[post("/ruchers/{id}/meteo")]
+pub async fn enregistrer_observation(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    new_observation: web::Json<NewObservationMeteo>
+) -> Result<HttpResponse> {
+    let mut new_observation = new_observation.into_inner();
+    new_observation.id_rucher = id.into_inner();
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        meteo_service::enregistrer_observation(&mut conn, new_observation)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(observation) => Ok(HttpResponse::Created().json(observation)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Précipitations ou vent négatifs, ou température minimale supérieure à la maximale"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::NotFound().json("Rucher introuvable"))
+        }
+        Err(e) => {
+            error!("Erreur lors de l'enregistrement de l'observation météo: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Importer les observations météo d'un rucher depuis le fournisseur configuré
+#[post("/ruchers/{id}/meteo/import")]
+pub async fn importer_observations(
+    pool: web::Data<Pool>,
+    config: web::Data<Option<ConfigMeteo>>,
+    id: web::Path<i32>,
+    query: web::Query<ImportQuery>
+) -> Result<HttpResponse> {
+    let Some(config) = config.get_ref().clone() else {
+        return Ok(HttpResponse::ServiceUnavailable().json("Aucun fournisseur météo configuré"));
+    };
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        meteo_service::importer_observations(&mut conn, &config, id.into_inner(), query.debut, query.fin)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(observations) => Ok(HttpResponse::Ok().json(observations)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Rucher introuvable")),
+        Err(DieselError::DeserializationError(e)) => {
+            error!("Erreur du fournisseur météo: {}", e);
+            Ok(HttpResponse::BadGateway().json(format!("Erreur du fournisseur météo: {}", e)))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadGateway().json("Observation invalide transmise par le fournisseur météo"))
+        }
+        Err(e) => {
+            error!("Erreur lors de l'import des observations météo: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Supprimer une observation météo
+#[delete("/meteo/{id}")]
+pub async fn delete_observation(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        meteo_service::delete_observation(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            error!("Erreur lors de la suppression de l'observation météo: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,65 @@
+use actix_web::{web, HttpResponse, Result, get};
+use crate::db::Pool;
+use crate::services::miellee_service;
+use chrono::{Days, NaiveDate, Utc};
+use serde::Deserialize;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Fenêtre d'ajustement maximale, en jours
+const JOURS_MAX: u64 = 365;
+
+// Date de la prévision (date du jour par défaut) et fenêtre d'ajustement de la tendance, en jours (14 par défaut)
+#[derive(Deserialize)]
+pub struct PrevisionQuery {
+    date: Option<NaiveDate>,
+    jours: Option<u64>,
+}
+
+// Prévoir la miellée d'une ruche
+#[get("/ruches/{id}/prevision-miellee")]
+pub async fn get_prevision_miellee(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    query: web::Query<PrevisionQuery>
+) -> Result<HttpResponse> {
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    let jours = query.jours.unwrap_or(14);
+    // La fenêtre, même étendue, doit rester dans le calendrier
+    if !(1..=JOURS_MAX).contains(&jours) || date.checked_sub_days(Days::new(JOURS_MAX)).is_none() {
+        return Ok(HttpResponse::BadRequest().json(format!(
+            "La fenêtre doit être comprise entre 1 et {} jours avant une date valide",
+            JOURS_MAX
+        )));
+    }
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        miellee_service::prevoir_miellee(&mut conn, id.into_inner(), date, jours)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(prevision) => Ok(HttpResponse::Ok().json(prevision)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
@@ -3,4 +3,23 @@ pub mod utilisateur_controller;
 pub mod ruche_controller;
 pub mod production_controller;
 pub mod poids_controller;
-pub mod materiel_controller;
\ No newline at end of file
+pub mod materiel_controller;
+pub mod configuration_ruche_controller;
+pub mod hausse_controller;
+pub mod recolte_controller;
+pub mod rucher_controller;
+pub mod lot_controller;
+pub mod traitement_controller;
+pub mod alerte_controller;
+pub mod varroa_controller;
+pub mod nourrissement_controller;
+pub mod appareil_controller;
+pub mod lorawan_controller;
+pub mod releve_capteur_controller;
+pub mod miellee_controller;
+pub mod anomalie_poids_controller;
+pub mod meteo_controller;
+pub mod floraison_controller;
+pub mod sante_controller;
+pub mod tableau_de_bord_controller;
+pub mod chronologie_controller;
\ No newline at end of file
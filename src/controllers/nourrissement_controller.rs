@@ -0,0 +1,294 @@
+use actix_web::{web, HttpResponse, Result, get, post, put, delete};
+use crate::db::Pool;
+use crate::models::nourrissement_models::{NewNourrissement, NewSeuilReserve};
+use crate::services::nourrissement_service;
+use chrono::{NaiveDate, Utc};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::Deserialize;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Obtenir les nourrissements d'une ruche
+#[get("/ruches/{id}/nourrissements")]
+pub async fn get_nourrissements_by_ruche_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        nourrissement_service::get_nourrissements_by_ruche_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(nourrissements) => Ok(HttpResponse::Ok().json(nourrissements)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Enregistrer un nourrissement
+#[post("/ruches/{id}/nourrissements")]
+pub async fn create_nourrissement(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    new_nourrissement: web::Json<NewNourrissement>
+) -> Result<HttpResponse> {
+    let mut new_nourrissement = new_nourrissement.into_inner();
+    new_nourrissement.id_ruche = id.into_inner();
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        nourrissement_service::create_nourrissement(&mut conn, new_nourrissement)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(nourrissement) => Ok(HttpResponse::Created().json(nourrissement)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("La quantité doit être positive"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Ruche ou type de nourriture inconnu"))
+        }
+        Err(e) => {
+            error!("Erreur lors de l'enregistrement du nourrissement: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Supprimer un nourrissement
+#[delete("/nourrissements/{id}")]
+pub async fn delete_nourrissement(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        nourrissement_service::delete_nourrissement(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            error!("Erreur lors de la suppression du nourrissement: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Obtenir les types de nourriture
+#[get("/types-nourriture")]
+pub async fn get_all_types_nourriture(pool: web::Data<Pool>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        nourrissement_service::get_all_types_nourriture(&mut conn)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(types) => Ok(HttpResponse::Ok().json(types)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Date de l'estimation des réserves (date du jour par défaut)
+#[derive(Deserialize)]
+pub struct ReservesQuery {
+    date: Option<NaiveDate>,
+}
+
+// Estimer les réserves hivernales d'une ruche
+#[get("/ruches/{id}/reserves")]
+pub async fn get_reserves_by_ruche_id(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    query: web::Query<ReservesQuery>
+) -> Result<HttpResponse> {
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        nourrissement_service::estimer_reserves(&mut conn, id.into_inner(), date)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(reserves) => Ok(HttpResponse::Ok().json(reserves)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Obtenir les seuils de réserves
+#[get("/seuils-reserve")]
+pub async fn get_all_seuils(pool: web::Data<Pool>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        nourrissement_service::get_all_seuils(&mut conn)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(seuils) => Ok(HttpResponse::Ok().json(seuils)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Créer un seuil de réserves
+#[post("/seuils-reserve")]
+pub async fn create_seuil(pool: web::Data<Pool>, new_seuil: web::Json<NewSeuilReserve>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        nourrissement_service::create_seuil(&mut conn, new_seuil.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(seuil) => Ok(HttpResponse::Created().json(seuil)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Mois hors de 1 à 12 ou seuil négatif"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la création du seuil: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Mettre à jour un seuil de réserves
+#[put("/seuils-reserve/{id}")]
+pub async fn update_seuil(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    seuil: web::Json<NewSeuilReserve>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        nourrissement_service::update_seuil(&mut conn, id.into_inner(), seuil.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(seuil) => Ok(HttpResponse::Ok().json(seuil)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Seuil introuvable")),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Mois hors de 1 à 12 ou seuil négatif"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la mise à jour du seuil: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Supprimer un seuil de réserves
+#[delete("/seuils-reserve/{id}")]
+pub async fn delete_seuil(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        nourrissement_service::delete_seuil(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            error!("Erreur lors de la suppression du seuil: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
@@ -1,8 +1,12 @@
 use actix_web::{web, HttpResponse, Result, get, post, put, delete};
 use crate::db::Pool;
-use crate::models::poids_models::{NewPoids, Poids, UpdatePoids};
+use crate::middleware::auth::Auth;
+use crate::models::poids_models::{NewPoids, Poids, UpdatePoidsMotif, TypePoids};
+use crate::models::releve_capteur_models::RetentionMesures;
+use crate::models::utilisateur_models::TokenClaims;
 use crate::services::poids_service;
-use chrono::NaiveDate;
+use chrono::{Days, NaiveDate, Utc};
+use diesel::result::Error as DieselError;
 use serde::Deserialize;
 use log::{error};
 use serde::Serialize;
@@ -134,6 +138,33 @@ pub async fn get_last_poids_by_ruche_id(pool: web::Data<Pool>, ruche_id: web::Pa
     }
 }
 
+// Pesées d'une ruche avec la tare à leur date et le poids net
+#[get("/poids/ruche/{ruche_id}/net")]
+pub async fn get_poids_net_by_ruche_id(pool: web::Data<Pool>, ruche_id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        poids_service::get_poids_net_by_ruche_id(&mut conn, ruche_id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(poids) => Ok(HttpResponse::Ok().json(poids)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
 #[get("/poids/date-range")]
 pub async fn get_poids_by_date_range(
     pool: web::Data<Pool>,
@@ -173,15 +204,21 @@ pub async fn get_poids_by_date_range(
     }
 }
 
-#[post("/poids")]
-pub async fn create_poids(pool: web::Data<Pool>, new_poids: web::Json<NewPoids>) -> Result<HttpResponse> {
+// Saisir une pesée sur une ruche de l'apiculteur authentifié ; les appareils passent par /devices/mesures
+#[post("/poids", wrap = "Auth")]
+pub async fn create_poids(
+    pool: web::Data<Pool>,
+    new_poids: web::Json<NewPoids>,
+    claims: web::ReqData<TokenClaims>
+) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
     let mut conn = match get_connection(&pool) {
         Ok(conn) => conn,
         Err(e) => return Ok(e),
     };
 
     let result = match web::block(move || {
-        poids_service::create_poids(&mut conn, new_poids.into_inner())
+        poids_service::create_poids_apiculteur(&mut conn, new_poids.into_inner(), apiculteur_id)
     }).await {
         Ok(result) => result,
         Err(e) => {
@@ -192,6 +229,7 @@ pub async fn create_poids(pool: web::Data<Pool>, new_poids: web::Json<NewPoids>)
 
     match result {
         Ok(poids) => Ok(HttpResponse::Created().json(poids)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Ruche introuvable")),
         Err(e) => {
             error!("Erreur lors de la création du poids: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
@@ -199,19 +237,24 @@ pub async fn create_poids(pool: web::Data<Pool>, new_poids: web::Json<NewPoids>)
     }
 }
 
-#[put("/poids/{id}")]
+// Corriger une pesée d'une ruche de l'apiculteur authentifié, éventuellement vers une autre de ses ruches
+#[put("/poids/{id}", wrap = "Auth")]
 pub async fn update_poids(
     pool: web::Data<Pool>,
     id: web::Path<i32>,
-    poids: web::Json<UpdatePoids>
+    poids: web::Json<UpdatePoidsMotif>,
+    retention: web::Data<RetentionMesures>,
+    claims: web::ReqData<TokenClaims>
 ) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
     let mut conn = match get_connection(&pool) {
         Ok(conn) => conn,
         Err(e) => return Ok(e),
     };
 
     let result = match web::block(move || {
-        poids_service::update_poids(&mut conn, id.into_inner(), poids.into_inner())
+        let UpdatePoidsMotif { poids, motif } = poids.into_inner();
+        poids_service::update_poids_apiculteur(&mut conn, id.into_inner(), poids, motif, &retention, apiculteur_id)
     }).await {
         Ok(result) => result,
         Err(e) => {
@@ -222,6 +265,7 @@ pub async fn update_poids(
 
     match result {
         Ok(poids) => Ok(HttpResponse::Ok().json(poids)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Pesée ou ruche introuvable")),
         Err(e) => {
             error!("Erreur lors de la mise à jour du poids: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
@@ -229,15 +273,47 @@ pub async fn update_poids(
     }
 }
 
-#[delete("/poids/{id}")]
-pub async fn delete_poids(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+#[get("/poids/{id}/corrections")]
+pub async fn get_corrections_by_poids_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        poids_service::get_corrections_by_poids_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(corrections) => Ok(HttpResponse::Ok().json(corrections)),
+        Err(e) => {
+            error!("Erreur lors de la récupération des corrections du poids: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Supprimer une pesée d'une ruche de l'apiculteur authentifié
+#[delete("/poids/{id}", wrap = "Auth")]
+pub async fn delete_poids(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    claims: web::ReqData<TokenClaims>
+) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
     let mut conn = match get_connection(&pool) {
         Ok(conn) => conn,
         Err(e) => return Ok(e),
     };
 
     let result = match web::block(move || {
-        poids_service::delete_poids(&mut conn, id.into_inner())
+        poids_service::delete_poids_apiculteur(&mut conn, id.into_inner(), apiculteur_id)
     }).await {
         Ok(result) => result,
         Err(e) => {
@@ -248,6 +324,7 @@ pub async fn delete_poids(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<H
 
     match result {
         Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Pesée introuvable")),
         Err(e) => {
             error!("Erreur lors de la suppression du poids: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
@@ -258,27 +335,33 @@ pub async fn delete_poids(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<H
 #[derive(Deserialize)]
 pub struct YearQuery {
     year: i32,
+    #[serde(default)]
+    poids: TypePoids, // brut (par défaut) ou net
 }
 
 #[derive(Deserialize)]
 pub struct YearsQuery {
     years: String, // Format: "2022,2023,2024"
+    #[serde(default)]
+    poids: TypePoids,
 }
 
 #[get("/poids/ruche/{ruche_id}/average")]
 pub async fn get_annual_average_weight(
     pool: web::Data<Pool>,
+    retention: web::Data<RetentionMesures>,
     path: web::Path<i32>,
     query: web::Query<YearQuery>,
 ) -> Result<HttpResponse> {
     let ruche_id = path.into_inner();
     let year = query.year;
+    let type_poids = query.poids;
     let mut conn = match get_connection(&pool) {
         Ok(conn) => conn,
         Err(e) => return Ok(e),
     };
 
-    match web::block(move || poids_service::get_annual_average_weight(&mut conn, ruche_id, year)).await {
+    match web::block(move || poids_service::get_annual_average_weight(&mut conn, ruche_id, year, type_poids, &retention)).await {
         Ok(Ok(average)) => Ok(HttpResponse::Ok().json(average)),
         Ok(Err(e)) => {
             error!("Erreur de base de données: {}", e);
@@ -296,17 +379,19 @@ pub async fn get_annual_average_weight(
 #[get("/poids/ruche/{ruche_id}/monthly-average")]
 pub async fn get_annual_average_weight_by_month(
     pool: web::Data<Pool>,
+    retention: web::Data<RetentionMesures>,
     path: web::Path<i32>,
     query: web::Query<YearQuery>,
 ) -> Result<HttpResponse> {
     let ruche_id = path.into_inner();
     let year = query.year;
+    let type_poids = query.poids;
     let mut conn = match get_connection(&pool) {
         Ok(conn) => conn,
         Err(e) => return Ok(e),
     };
 
-    match web::block(move || poids_service::get_annual_average_weight_by_month(&mut conn, ruche_id, year)).await {
+    match web::block(move || poids_service::get_annual_average_weight_by_month(&mut conn, ruche_id, year, type_poids, &retention)).await {
         Ok(Ok(averages)) => Ok(HttpResponse::Ok().json(averages)),
         Ok(Err(e)) => {
             error!("Erreur de base de données: {}", e);
@@ -324,6 +409,7 @@ pub async fn get_annual_average_weight_by_month(
 #[get("/poids/ruche/{ruche_id}/evolution")]
 pub async fn get_weight_evolution(
     pool: web::Data<Pool>,
+    retention: web::Data<RetentionMesures>,
     path: web::Path<i32>,
     query: web::Query<YearsQuery>,
 ) -> Result<HttpResponse> {
@@ -332,13 +418,14 @@ pub async fn get_weight_evolution(
         .split(',')
         .filter_map(|y| y.trim().parse::<i32>().ok())
         .collect();
+    let type_poids = query.poids;
 
     let mut conn = match get_connection(&pool) {
         Ok(conn) => conn,
         Err(e) => return Ok(e),
     };
 
-    match web::block(move || poids_service::get_weight_evolution(&mut conn, ruche_id, years)).await {
+    match web::block(move || poids_service::get_weight_evolution(&mut conn, ruche_id, years, type_poids, &retention)).await {
         Ok(Ok(evolution)) => Ok(HttpResponse::Ok().json(evolution)),
         Ok(Err(e)) => {
             error!("Erreur de base de données: {}", e);
@@ -351,4 +438,50 @@ pub async fn get_weight_evolution(
                 .json(format!("Erreur lors du calcul de l'évolution du poids: {}", e)))
         }
     }
+}
+
+// Période des variations, jours inclus : les 30 derniers jours par défaut
+#[derive(Deserialize)]
+pub struct VariationsQuery {
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+    #[serde(default)]
+    poids: TypePoids,
+}
+
+#[get("/poids/ruche/{ruche_id}/variations")]
+pub async fn get_variations_journalieres(
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+    query: web::Query<VariationsQuery>,
+) -> Result<HttpResponse> {
+    let ruche_id = path.into_inner();
+    let fin = query.fin.unwrap_or_else(|| Utc::now().date_naive());
+    let Some(debut) = query.debut.or_else(|| fin.checked_sub_days(Days::new(30))) else {
+        return Ok(HttpResponse::BadRequest().json("Date de fin hors calendrier"));
+    };
+    if debut > fin {
+        return Ok(HttpResponse::BadRequest().json("La date de début est postérieure à la date de fin"));
+    }
+    let type_poids = query.poids;
+
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    match web::block(move || poids_service::get_variations_journalieres(&mut conn, ruche_id, debut, fin, type_poids)).await {
+        Ok(Ok(variations)) => Ok(HttpResponse::Ok().json(variations)),
+        Ok(Err(DieselError::NotFound)) => Ok(HttpResponse::NotFound().json("Ruche introuvable")),
+        Ok(Err(e)) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError()
+                .json(format!("Erreur de base de données: {}", e)))
+        },
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            Ok(HttpResponse::InternalServerError()
+                .json(format!("Erreur lors du calcul des variations du poids: {}", e)))
+        }
+    }
 }
\ No newline at end of file
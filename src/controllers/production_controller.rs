@@ -1,8 +1,9 @@
 use actix_web::{web, HttpResponse, Result, get, post, put, delete};
 use crate::db::Pool;
-use crate::models::production_models::{Production, NewProduction, UpdateProduction};
+use crate::models::production_models::{Production, NewProduction, UpdateProduction, Perimetre};
 use crate::services::production_service;
 use chrono::NaiveDate;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use log::{error};
 use serde::Serialize;
 
@@ -129,6 +130,10 @@ pub async fn create_production(pool: web::Data<Pool>, new_production: web::Json<
 
     match result {
         Ok(production) => Ok(HttpResponse::Created().json(production)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _))
+        | Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Type de produit, unité ou ruche inconnu"))
+        }
         Err(e) => {
             error!("Erreur lors de la création de la production: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
@@ -160,6 +165,10 @@ pub async fn update_production(
 
     match result {
         Ok(production) => Ok(HttpResponse::Ok().json(production)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _))
+        | Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Type de produit, unité ou ruche inconnu"))
+        }
         Err(e) => {
             error!("Erreur lors de la mise à jour de la production: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
@@ -194,20 +203,94 @@ pub async fn delete_production(pool: web::Data<Pool>, id: web::Path<i32>) -> Res
     }
 }
 
-// Statistiques de production par ruche
+// Paramètres des statistiques de production
+#[derive(serde::Deserialize)]
+pub struct StatistiquesQuery {
+    // Unité dans laquelle exprimer les quantités
+    unite: Option<String>,
+    // Type de produit des statistiques annuelles, saisonnières et du classement (miel par défaut)
+    type_produit: Option<String>,
+    // Nombre de meilleures et de moins bonnes ruches par année
+    limite: Option<i64>,
+}
+
+// Fonction auxiliaire commune aux statistiques par ruche, par rucher et par apiculteur
+async fn production_statistics(
+    pool: web::Data<Pool>,
+    perimetre: Perimetre,
+    id: i32,
+    query: StatistiquesQuery
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    match web::block(move || production_service::get_production_statistics(
+        &mut conn,
+        perimetre,
+        id,
+        query.type_produit,
+        query.unite,
+        query.limite
+    )).await {
+        Ok(Ok(statistiques)) => Ok(HttpResponse::Ok().json(statistiques)),
+        Ok(Err(DieselError::NotFound)) => {
+            Ok(HttpResponse::BadRequest().json("Unité de mesure inconnue"))
+        },
+        Ok(Err(e)) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError()
+                .json(format!("Erreur de base de données: {}", e)))
+        },
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            Ok(HttpResponse::InternalServerError()
+                .json(format!("Erreur lors de la récupération des statistiques de production: {}", e)))
+        },
+    }
+}
+
+// Statistiques de production d'une ruche
 #[get("/productions/ruche/{ruche_id}/statistiques")]
 pub async fn get_production_statistics_by_ruche(
     pool: web::Data<Pool>,
-    ruche_id: web::Path<i32>
+    ruche_id: web::Path<i32>,
+    query: web::Query<StatistiquesQuery>
 ) -> Result<HttpResponse> {
-    let ruche_id = ruche_id.into_inner();
+    production_statistics(pool, Perimetre::Ruche, ruche_id.into_inner(), query.into_inner()).await
+}
+
+// Statistiques de production des ruches d'un rucher
+#[get("/productions/rucher/{rucher_id}/statistiques")]
+pub async fn get_production_statistics_by_rucher(
+    pool: web::Data<Pool>,
+    rucher_id: web::Path<i32>,
+    query: web::Query<StatistiquesQuery>
+) -> Result<HttpResponse> {
+    production_statistics(pool, Perimetre::Rucher, rucher_id.into_inner(), query.into_inner()).await
+}
+
+// Statistiques de production des ruches d'un apiculteur
+#[get("/productions/apiculteur/{apiculteur_id}/statistiques")]
+pub async fn get_production_statistics_by_apiculteur(
+    pool: web::Data<Pool>,
+    apiculteur_id: web::Path<i32>,
+    query: web::Query<StatistiquesQuery>
+) -> Result<HttpResponse> {
+    production_statistics(pool, Perimetre::Apiculteur, apiculteur_id.into_inner(), query.into_inner()).await
+}
+
+// Unités de mesure et facteurs de conversion
+#[get("/unites")]
+pub async fn get_all_unites(pool: web::Data<Pool>) -> Result<HttpResponse> {
     let mut conn = match get_connection(&pool) {
         Ok(conn) => conn,
         Err(e) => return Ok(e),
     };
 
-    match web::block(move || production_service::get_total_production_by_ruche(&mut conn, ruche_id)).await {
-        Ok(Ok(total_production)) => Ok(HttpResponse::Ok().json(total_production)),
+    match web::block(move || production_service::get_all_unites(&mut conn)).await {
+        Ok(Ok(unites)) => Ok(HttpResponse::Ok().json(unites)),
         Ok(Err(e)) => {
             error!("Erreur de base de données: {}", e);
             Ok(HttpResponse::InternalServerError()
@@ -216,7 +299,7 @@ pub async fn get_production_statistics_by_ruche(
         Err(e) => {
             error!("Erreur lors de l'exécution de la requête: {}", e);
             Ok(HttpResponse::InternalServerError()
-                .json(format!("Erreur lors de la récupération des statistiques de production: {}", e)))
+                .json(format!("Erreur lors de la récupération des unités: {}", e)))
         },
     }
 }
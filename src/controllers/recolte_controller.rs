@@ -0,0 +1,113 @@
+use actix_web::{web, HttpResponse, Result, get, post};
+use crate::db::Pool;
+use crate::models::hausse_models::ErreurHausse;
+use crate::models::recolte_models::NewRecolte;
+use crate::services::recolte_service;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Obtenir toutes les sessions de récolte
+#[get("/recoltes")]
+pub async fn get_all_recoltes(pool: web::Data<Pool>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || recolte_service::get_all_recoltes(&mut conn))
+        .await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(recoltes) => Ok(HttpResponse::Ok().json(recoltes)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Obtenir une session de récolte avec ses hausses, productions et lot
+#[get("/recoltes/{id}")]
+pub async fn get_recolte_by_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        recolte_service::get_recolte_by_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(recolte) => Ok(HttpResponse::Ok().json(recolte)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Session de récolte introuvable")),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Enregistrer une session de récolte
+#[post("/recoltes")]
+pub async fn create_recolte(pool: web::Data<Pool>, new_recolte: web::Json<NewRecolte>) -> Result<HttpResponse> {
+    if new_recolte.hausses.is_empty() {
+        return Ok(HttpResponse::BadRequest().json("Une session de récolte doit comporter au moins une hausse"));
+    }
+
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        recolte_service::create_recolte(&mut conn, new_recolte.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(recolte) => Ok(HttpResponse::Created().json(recolte)),
+        Err(ErreurHausse::Base(DieselError::NotFound)) => {
+            Ok(HttpResponse::NotFound().json("Une des hausses n'est posée sur aucune ruche"))
+        }
+        Err(ErreurHausse::Base(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _))) => {
+            Ok(HttpResponse::BadRequest().json("Pesées ou date de récolte incohérentes pour une des hausses"))
+        }
+        Err(ErreurHausse::Base(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _))) => {
+            Ok(HttpResponse::Conflict().json("Ce numéro de lot existe déjà"))
+        }
+        Err(e @ (ErreurHausse::ProductionHorsLimites { .. } | ErreurHausse::PeseesIncoherentes)) => {
+            Ok(HttpResponse::BadRequest().json(e.to_string()))
+        }
+        Err(e) => {
+            error!("Erreur lors de l'enregistrement de la récolte: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
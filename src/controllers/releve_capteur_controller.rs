@@ -0,0 +1,232 @@
+use actix_web::{web, HttpResponse, Result, get, post, delete};
+use crate::db::Pool;
+use crate::models::poids_models::TypePoids;
+use crate::models::releve_capteur_models::{NewReleveCapteur, ParametresSerie, RetentionMesures};
+use crate::services::releve_capteur_service;
+use chrono::NaiveDate;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::Deserialize;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Obtenir les types de mesure
+#[get("/types-mesure")]
+pub async fn get_all_types_mesure(pool: web::Data<Pool>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        releve_capteur_service::get_all_types_mesure(&mut conn)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(types) => Ok(HttpResponse::Ok().json(types)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Filtres des relevés, la période s'entendant du jour de début au jour de fin inclus
+#[derive(Deserialize)]
+pub struct RelevesQuery {
+    type_mesure: Option<String>,
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+}
+
+// Obtenir les relevés des capteurs d'une ruche
+#[get("/ruches/{id}/releves")]
+pub async fn get_releves_by_ruche_id(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    query: web::Query<RelevesQuery>
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        releve_capteur_service::get_releves_by_ruche_id(&mut conn, id.into_inner(), query.type_mesure, query.debut, query.fin)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(releves) => Ok(HttpResponse::Ok().json(releves)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Enregistrer le relevé d'un capteur
+#[post("/ruches/{id}/releves")]
+pub async fn create_releve(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    new_releve: web::Json<NewReleveCapteur>
+) -> Result<HttpResponse> {
+    let mut new_releve = new_releve.into_inner();
+    new_releve.id_ruche = id.into_inner();
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        releve_capteur_service::create_releve(&mut conn, new_releve)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(releve) => Ok(HttpResponse::Created().json(releve)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::BadRequest().json("Type de mesure inconnu")),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::NotFound().json("Ruche introuvable"))
+        }
+        Err(e) => {
+            error!("Erreur lors de l'enregistrement du relevé: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Supprimer un relevé
+#[delete("/releves/{id}")]
+pub async fn delete_releve(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        releve_capteur_service::delete_releve(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            error!("Erreur lors de la suppression du relevé: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Séries demandées (codes de types de mesure séparés par des virgules, `poids` pour les pesées), période
+// du jour de début au jour de fin inclus, et pesées brutes ou nettes
+#[derive(Deserialize)]
+pub struct SeriesQuery {
+    types: Option<String>,
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+    #[serde(default)]
+    poids: TypePoids,
+}
+
+// Obtenir les séries des capteurs et des pesées d'une ruche
+#[get("/ruches/{id}/series")]
+pub async fn get_series_by_ruche_id(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    query: web::Query<SeriesQuery>
+) -> Result<HttpResponse> {
+    let types = query.types.as_ref().map(|types| {
+        types.split(',').map(|code| code.trim().to_string()).filter(|code| !code.is_empty()).collect::<Vec<_>>()
+    });
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        releve_capteur_service::get_series(&mut conn, id.into_inner(), types, query.debut, query.fin, query.poids)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(series) => Ok(HttpResponse::Ok().json(series)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::BadRequest().json("Type de mesure inconnu")),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Obtenir une série d'une ruche agrégée par intervalles
+#[get("/ruches/{id}/series/{type_mesure}")]
+pub async fn get_serie_agregee(
+    pool: web::Data<Pool>,
+    retention: web::Data<RetentionMesures>,
+    path: web::Path<(i32, String)>,
+    query: web::Query<ParametresSerie>
+) -> Result<HttpResponse> {
+    let (id, type_mesure) = path.into_inner();
+    if query.fin.is_some_and(|fin| fin.fin_exclue().is_none()) {
+        return Ok(HttpResponse::BadRequest().json("Date de fin hors calendrier"));
+    }
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        releve_capteur_service::get_serie_agregee(&mut conn, &retention, id, type_mesure, query.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(serie) => Ok(HttpResponse::Ok().json(serie)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::BadRequest().json("Type de mesure inconnu")),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
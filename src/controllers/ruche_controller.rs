@@ -2,6 +2,8 @@ use actix_web::{web, HttpResponse, Result, get, post, put, delete};
 use crate::db::Pool;
 use crate::models::ruche_models::{NewRuche, UpdateRuche};
 use crate::services::ruche_service;
+use chrono::Utc;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use log::error;
 use serde::Serialize;
 
@@ -61,7 +63,7 @@ pub async fn get_ruche_by_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Resul
     };
 
     let result = match web::block(move || {
-        ruche_service::get_ruche_by_id(&mut conn, id.into_inner())
+        ruche_service::get_ruche_by_id(&mut conn, id.into_inner(), Utc::now().date_naive())
     }).await {
         Ok(result) => result,
         Err(e) => {
@@ -72,6 +74,7 @@ pub async fn get_ruche_by_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Resul
 
     match result {
         Ok(ruche) => Ok(HttpResponse::Ok().json(ruche)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Ruche introuvable")),
         Err(e) => {
             error!("Erreur de base de données: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
@@ -133,6 +136,9 @@ pub async fn create_ruche(pool: web::Data<Pool>, new_ruche: web::Json<NewRuche>)
 
     match result {
         Ok(ruche) => Ok(HttpResponse::Created().json(ruche)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Année de la reine invalide"))
+        }
         Err(e) => {
             error!("Erreur lors de la création de la ruche: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
@@ -163,6 +169,9 @@ pub async fn update_ruche(
 
     match result {
         Ok(ruche) => Ok(HttpResponse::Ok().json(ruche)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("Année de la reine invalide"))
+        }
         Err(e) => {
             error!("Erreur lors de la mise à jour de la ruche: {}", e);
             Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
@@ -0,0 +1,103 @@
+use actix_web::{web, HttpResponse, Result, get};
+use crate::db::Pool;
+use crate::models::sante_models::{TriSante, Ordre};
+use crate::services::sante_service;
+use chrono::{NaiveDate, Utc};
+use diesel::result::Error as DieselError;
+use serde::Deserialize;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Date de l'évaluation (date du jour par défaut)
+#[derive(Deserialize)]
+pub struct SanteQuery {
+    date: Option<NaiveDate>,
+}
+
+// Filtres et tri de la liste (score croissant par défaut, les ruches à visiter en premier)
+#[derive(Deserialize)]
+pub struct SantesQuery {
+    id_rucher: Option<i32>,
+    id_apiculteur: Option<i32>,
+    #[serde(default)]
+    tri: TriSante,
+    #[serde(default)]
+    ordre: Ordre,
+    date: Option<NaiveDate>,
+}
+
+// Récupérer le score de santé des ruches
+#[get("/ruches/sante")]
+pub async fn get_sante_ruches(pool: web::Data<Pool>, query: web::Query<SantesQuery>) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    if !sante_service::date_evaluable(date) {
+        return Ok(HttpResponse::BadRequest().json("Date d'évaluation hors calendrier"));
+    }
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        sante_service::get_sante_ruches(&mut conn, query.id_rucher, query.id_apiculteur, query.tri, query.ordre, date)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(santes) => Ok(HttpResponse::Ok().json(santes)),
+        Err(e) => {
+            error!("Erreur lors du calcul de la santé des ruches: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Récupérer le score de santé d'une ruche
+#[get("/ruches/{id}/sante")]
+pub async fn get_sante_ruche(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    query: web::Query<SanteQuery>
+) -> Result<HttpResponse> {
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    if !sante_service::date_evaluable(date) {
+        return Ok(HttpResponse::BadRequest().json("Date d'évaluation hors calendrier"));
+    }
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        sante_service::get_sante_ruche(&mut conn, id.into_inner(), date)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(sante) => Ok(HttpResponse::Ok().json(sante)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Ruche introuvable")),
+        Err(e) => {
+            error!("Erreur lors du calcul de la santé de la ruche: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use actix_web::{web, HttpResponse, Result, get};
+use crate::db::Pool;
+use crate::middleware::auth::Auth;
+use crate::models::utilisateur_models::TokenClaims;
+use crate::services::tableau_de_bord_service;
+use chrono::Utc;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Récupérer le tableau de bord de l'apiculteur authentifié
+#[get("/dashboard", wrap = "Auth")]
+pub async fn get_tableau_de_bord(pool: web::Data<Pool>, claims: web::ReqData<TokenClaims>) -> Result<HttpResponse> {
+    let apiculteur_id = claims.user_id;
+    let date = Utc::now().date_naive();
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        tableau_de_bord_service::get_tableau_de_bord(&mut conn, apiculteur_id, date)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(tableau) => Ok(HttpResponse::Ok().json(tableau)),
+        Err(e) => {
+            error!("Erreur lors de la construction du tableau de bord: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
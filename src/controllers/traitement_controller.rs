@@ -0,0 +1,257 @@
+use actix_web::{web, HttpResponse, Result, get, post, put, delete};
+use crate::db::Pool;
+use crate::models::traitement_models::{NewTraitement, UpdateTraitement};
+use crate::services::traitement_service;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::Deserialize;
+use log::error;
+
+// Fonction auxiliaire pour obtenir la connexion à la base de données
+fn get_connection(pool: &web::Data<Pool>) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>, HttpResponse> {
+    pool.get().map_err(|e| {
+        let msg = format!("Erreur de connexion à la base de données: {}", e);
+        error!("{}", msg);
+        HttpResponse::InternalServerError().json(msg)
+    })
+}
+
+// Obtenir les traitements d'une ruche
+#[get("/ruches/{id}/traitements")]
+pub async fn get_traitements_by_ruche_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        traitement_service::get_traitements_by_ruche_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(traitements) => Ok(HttpResponse::Ok().json(traitements)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Inscrire un traitement au registre
+#[post("/ruches/{id}/traitements")]
+pub async fn create_traitement(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    new_traitement: web::Json<NewTraitement>
+) -> Result<HttpResponse> {
+    let mut new_traitement = new_traitement.into_inner();
+    new_traitement.id_ruche = id.into_inner();
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        traitement_service::create_traitement(&mut conn, new_traitement)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(traitement) => Ok(HttpResponse::Created().json(traitement)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("La date de fin précède la date de début ou le délai d'attente n'est pas compris entre 0 et 365 jours"))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            Ok(HttpResponse::NotFound().json("Ruche introuvable"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la création du traitement: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Filtres des productions récoltées pendant un délai d'attente
+#[derive(Deserialize)]
+pub struct DelaiAttenteQuery {
+    id_ruche: Option<i32>,
+    id_apiculteur: Option<i32>,
+    annee: Option<i32>,
+}
+
+// Productions datées pendant un traitement ou son délai d'attente
+#[get("/traitements/conflits")]
+pub async fn get_productions_en_delai_attente(pool: web::Data<Pool>, query: web::Query<DelaiAttenteQuery>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        traitement_service::get_productions_en_delai_attente(&mut conn, query.id_ruche, query.id_apiculteur, query.annee)
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(productions) => Ok(HttpResponse::Ok().json(productions)),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Paramètres d'export du registre
+#[derive(Deserialize)]
+pub struct RegistreQuery {
+    id_apiculteur: Option<i32>,
+    // json (par défaut) ou csv
+    format: Option<String>,
+}
+
+// Registre d'élevage des traitements d'une année
+#[get("/traitements/registre/{annee}")]
+pub async fn get_registre_traitements(
+    pool: web::Data<Pool>,
+    annee: web::Path<i32>,
+    query: web::Query<RegistreQuery>
+) -> Result<HttpResponse> {
+    let annee = annee.into_inner();
+    let RegistreQuery { id_apiculteur, format } = query.into_inner();
+    let csv = match format.as_deref() {
+        None | Some("json") => false,
+        Some("csv") => true,
+        Some(_) => return Ok(HttpResponse::BadRequest().json("Format inconnu, valeurs possibles : json, csv")),
+    };
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    match web::block(move || traitement_service::get_registre_traitements(&mut conn, annee, id_apiculteur)).await {
+        Ok(Ok(registre)) if csv => Ok(HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"registre-traitements-{}.csv\"", annee),
+            ))
+            .body(registre.to_csv())),
+        Ok(Ok(registre)) => Ok(HttpResponse::Ok().json(registre)),
+        Ok(Err(DieselError::NotFound)) => Ok(HttpResponse::BadRequest().json("Année invalide")),
+        Ok(Err(e)) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError()
+                .json(format!("Erreur de base de données: {}", e)))
+        },
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            Ok(HttpResponse::InternalServerError()
+                .json(format!("Erreur lors de la récupération du registre des traitements: {}", e)))
+        },
+    }
+}
+
+// Obtenir un traitement par ID
+#[get("/traitements/{id}")]
+pub async fn get_traitement_by_id(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        traitement_service::get_traitement_by_id(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(traitement) => Ok(HttpResponse::Ok().json(traitement)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Traitement introuvable")),
+        Err(e) => {
+            error!("Erreur de base de données: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Mettre à jour un traitement
+#[put("/traitements/{id}")]
+pub async fn update_traitement(
+    pool: web::Data<Pool>,
+    id: web::Path<i32>,
+    traitement: web::Json<UpdateTraitement>
+) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        traitement_service::update_traitement(&mut conn, id.into_inner(), traitement.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(traitement) => Ok(HttpResponse::Ok().json(traitement)),
+        Err(DieselError::NotFound) => Ok(HttpResponse::NotFound().json("Traitement introuvable")),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _)) => {
+            Ok(HttpResponse::BadRequest().json("La date de fin précède la date de début ou le délai d'attente n'est pas compris entre 0 et 365 jours"))
+        }
+        Err(e) => {
+            error!("Erreur lors de la mise à jour du traitement: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
+
+// Supprimer un traitement
+#[delete("/traitements/{id}")]
+pub async fn delete_traitement(pool: web::Data<Pool>, id: web::Path<i32>) -> Result<HttpResponse> {
+    let mut conn = match get_connection(&pool) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(e),
+    };
+
+    let result = match web::block(move || {
+        traitement_service::delete_traitement(&mut conn, id.into_inner())
+    }).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Erreur lors de l'exécution de la requête: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(format!("Erreur de serveur: {}", e)));
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            error!("Erreur lors de la suppression du traitement: {}", e);
+            Ok(HttpResponse::InternalServerError().json(format!("Erreur de base de données: {}", e)))
+        }
+    }
+}
\ No newline at end of file
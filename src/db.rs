@@ -9,4 +9,14 @@ pub type Pool = r2d2::Pool<ConnectionManager<PgConnection>>;
 pub fn establish_connection_pool(database_url: &str) -> Result<Pool, PoolError> {
     let manager = ConnectionManager::<PgConnection>::new(database_url);
     Pool::builder().build(manager)
-}
\ No newline at end of file
+}
+
+/// Connexion à la base désignée par `DATABASE_URL`, pour les tests qui en ont besoin
+#[cfg(test)]
+pub fn connexion_de_test() -> DbConnection {
+    use diesel::Connection;
+
+    dotenv::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL doit être défini");
+    PgConnection::establish(&database_url).expect("Connexion à la base de test impossible")
+}
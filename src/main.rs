@@ -13,6 +13,11 @@ mod middleware;
 mod models;
 
 use crate::controllers::intervention_controller;
+use crate::models::appareil_models::SurveillanceAppareils;
+use crate::models::meteo_models::ConfigMeteo;
+use crate::models::mqtt_models::ConfigMqtt;
+use crate::models::releve_capteur_models::RetentionMesures;
+use crate::services::{appareil_service, meteo_service, mqtt_service, releve_capteur_service};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -53,6 +58,33 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    // Surveillance périodique des appareils connectés
+    let surveillance = SurveillanceAppareils::depuis_env();
+    actix_web::rt::spawn(appareil_service::surveiller_appareils(pool.clone(), surveillance.clone()));
+
+    // Agrégation et élagage périodiques des mesures
+    let retention = RetentionMesures::depuis_env();
+    actix_web::rt::spawn(releve_capteur_service::agreger_mesures(pool.clone(), retention.clone()));
+
+    // Abonnement facultatif aux pesées publiées sur MQTT
+    match ConfigMqtt::depuis_env() {
+        Some(config) => {
+            actix_web::rt::spawn(mqtt_service::souscrire(pool.clone(), config));
+        }
+        None => info!("Abonnement MQTT désactivé, MQTT_BROKER_HOST n'est pas définie"),
+    }
+
+    // Fournisseur facultatif des observations météo importées
+    let meteo = ConfigMeteo::depuis_env();
+    match &meteo {
+        Some(config) => {
+            if meteo_service::fournisseur_pour(&config.fournisseur).is_none() {
+                panic!("METEO_FOURNISSEUR inconnu : {}", config.fournisseur);
+            }
+        }
+        None => info!("Import météo désactivé, METEO_FOURNISSEUR n'est pas définie"),
+    }
+
     info!("Démarrage du serveur sur {}:{}", host, port);
 
     // Configuration et démarrage du serveur HTTP
@@ -68,6 +100,9 @@ async fn main() -> std::io::Result<()> {
             .wrap(Logger::default())
             .wrap(cors)
             .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(surveillance.clone()))
+            .app_data(web::Data::new(retention.clone()))
+            .app_data(web::Data::new(meteo.clone()))
             .service(web::scope("/api")
                 .service(intervention_controller::get_all_interventions)
                 .service(intervention_controller::get_intervention_by_id)
@@ -82,6 +117,10 @@ async fn main() -> std::io::Result<()> {
                 .service(controllers::utilisateur_controller::delete_utilisateur)
                 .service(controllers::utilisateur_controller::login)
                 
+                // Routes de santé des ruches, déclarées avant /ruches/{id} qui capturerait « sante »
+                .service(controllers::sante_controller::get_sante_ruches)
+                .service(controllers::sante_controller::get_sante_ruche)
+
                 // Routes de ruche
                 .service(controllers::ruche_controller::get_all_ruches)
                 .service(controllers::ruche_controller::get_ruche_by_id)
@@ -89,6 +128,76 @@ async fn main() -> std::io::Result<()> {
                 .service(controllers::ruche_controller::create_ruche)
                 .service(controllers::ruche_controller::update_ruche)
                 .service(controllers::ruche_controller::delete_ruche)
+
+                // Routes de rucher
+                .service(controllers::rucher_controller::get_all_ruchers)
+                .service(controllers::rucher_controller::get_rucher_by_id)
+                .service(controllers::rucher_controller::get_ruches_by_rucher)
+                .service(controllers::rucher_controller::create_rucher)
+                .service(controllers::rucher_controller::update_rucher)
+                .service(controllers::rucher_controller::delete_rucher)
+
+                // Routes du registre des traitements
+                .service(controllers::traitement_controller::get_traitements_by_ruche_id)
+                .service(controllers::traitement_controller::create_traitement)
+                .service(controllers::traitement_controller::get_productions_en_delai_attente)
+                .service(controllers::traitement_controller::get_registre_traitements)
+                .service(controllers::traitement_controller::get_traitement_by_id)
+                .service(controllers::traitement_controller::update_traitement)
+                .service(controllers::traitement_controller::delete_traitement)
+
+                // Routes de suivi du varroa
+                .service(controllers::varroa_controller::get_comptages_by_ruche_id)
+                .service(controllers::varroa_controller::create_comptage)
+                .service(controllers::varroa_controller::delete_comptage)
+                .service(controllers::varroa_controller::get_all_seuils)
+                .service(controllers::varroa_controller::create_seuil)
+                .service(controllers::varroa_controller::update_seuil)
+                .service(controllers::varroa_controller::delete_seuil)
+                .service(controllers::varroa_controller::get_efficacite_traitement)
+
+                // Routes de nourrissement et de réserves hivernales
+                .service(controllers::nourrissement_controller::get_nourrissements_by_ruche_id)
+                .service(controllers::nourrissement_controller::create_nourrissement)
+                .service(controllers::nourrissement_controller::delete_nourrissement)
+                .service(controllers::nourrissement_controller::get_all_types_nourriture)
+                .service(controllers::nourrissement_controller::get_reserves_by_ruche_id)
+                .service(controllers::nourrissement_controller::get_all_seuils)
+                .service(controllers::nourrissement_controller::create_seuil)
+                .service(controllers::nourrissement_controller::update_seuil)
+                .service(controllers::nourrissement_controller::delete_seuil)
+
+                // Routes d'alerte
+                .service(controllers::alerte_controller::get_alertes)
+                .service(controllers::alerte_controller::get_alertes_by_ruche_id)
+                .service(controllers::alerte_controller::resoudre_alerte)
+
+                // Routes de configuration des ruches
+                .service(controllers::configuration_ruche_controller::get_configurations_by_ruche_id)
+                .service(controllers::configuration_ruche_controller::create_configuration)
+
+                // Routes de hausses
+                .service(controllers::hausse_controller::get_hausses_by_ruche_id)
+                .service(controllers::hausse_controller::get_poses_by_ruche_id)
+                .service(controllers::hausse_controller::add_hausse)
+                .service(controllers::hausse_controller::get_poses_by_materiel_id)
+                .service(controllers::hausse_controller::remove_hausse)
+                .service(controllers::hausse_controller::create_productions_from_hausses)
+
+                // Routes de récolte
+                .service(controllers::recolte_controller::get_all_recoltes)
+                .service(controllers::recolte_controller::get_recolte_by_id)
+                .service(controllers::recolte_controller::create_recolte)
+
+                // Routes de lot de miel
+                .service(controllers::lot_controller::get_all_lots)
+                .service(controllers::lot_controller::get_lot_by_id)
+                .service(controllers::lot_controller::get_tracabilite_lot)
+                .service(controllers::lot_controller::get_lots_by_ruche_id)
+                .service(controllers::lot_controller::create_lot)
+                .service(controllers::lot_controller::update_lot)
+                .service(controllers::lot_controller::replace_pots)
+                .service(controllers::lot_controller::delete_lot)
                 
                 // Routes de production
                 .service(controllers::production_controller::get_all_productions)
@@ -96,6 +205,30 @@ async fn main() -> std::io::Result<()> {
                 .service(controllers::production_controller::create_production)
                 .service(controllers::production_controller::update_production)
                 .service(controllers::production_controller::delete_production)
+                .service(controllers::production_controller::get_production_statistics_by_ruche)
+                .service(controllers::production_controller::get_production_statistics_by_rucher)
+                .service(controllers::production_controller::get_production_statistics_by_apiculteur)
+                .service(controllers::production_controller::get_all_unites)
+
+                // Routes des appareils connectés
+                .service(controllers::appareil_controller::create_mesure)
+                .service(controllers::appareil_controller::get_all_appareils)
+                .service(controllers::appareil_controller::get_appareil_by_id)
+                .service(controllers::appareil_controller::create_appareil)
+                .service(controllers::appareil_controller::update_appareil)
+                .service(controllers::appareil_controller::regenerer_cle)
+                .service(controllers::appareil_controller::delete_appareil)
+
+                // Routes des relevés de capteurs
+                .service(controllers::releve_capteur_controller::get_all_types_mesure)
+                .service(controllers::releve_capteur_controller::get_releves_by_ruche_id)
+                .service(controllers::releve_capteur_controller::create_releve)
+                .service(controllers::releve_capteur_controller::delete_releve)
+                .service(controllers::releve_capteur_controller::get_series_by_ruche_id)
+                .service(controllers::releve_capteur_controller::get_serie_agregee)
+
+                // Routes LoRaWAN
+                .service(controllers::lorawan_controller::receive_uplink)
 
                 // Routes de poids
                 .service(controllers::poids_controller::get_all_poids)
@@ -103,7 +236,42 @@ async fn main() -> std::io::Result<()> {
                 .service(controllers::poids_controller::create_poids)
                 .service(controllers::poids_controller::update_poids)
                 .service(controllers::poids_controller::delete_poids)
+                .service(controllers::poids_controller::get_poids_by_ruche_id)
+                .service(controllers::poids_controller::get_last_poids_by_ruche_id)
+                .service(controllers::poids_controller::get_poids_net_by_ruche_id)
                 .service(controllers::poids_controller::get_annual_average_weight)
+                .service(controllers::poids_controller::get_annual_average_weight_by_month)
+                .service(controllers::poids_controller::get_weight_evolution)
+                .service(controllers::poids_controller::get_corrections_by_poids_id)
+                .service(controllers::poids_controller::get_variations_journalieres)
+
+                // Routes de météo
+                .service(controllers::meteo_controller::get_observations_by_rucher_id)
+                .service(controllers::meteo_controller::enregistrer_observation)
+                .service(controllers::meteo_controller::importer_observations)
+                .service(controllers::meteo_controller::delete_observation)
+
+                // Routes de floraisons
+                .service(controllers::floraison_controller::get_ressources_by_rucher_id)
+                .service(controllers::floraison_controller::create_ressource)
+                .service(controllers::floraison_controller::update_ressource)
+                .service(controllers::floraison_controller::delete_ressource)
+                .service(controllers::floraison_controller::get_floraisons_du_moment)
+                .service(controllers::floraison_controller::get_productions_floraisons)
+
+                // Routes d'anomalies des pesées
+                .service(controllers::anomalie_poids_controller::get_anomalies_by_ruche_id)
+                .service(controllers::anomalie_poids_controller::detecter_anomalies)
+                .service(controllers::anomalie_poids_controller::decider_anomalie)
+
+                // Routes de prévision de miellée
+                .service(controllers::miellee_controller::get_prevision_miellee)
+
+                // Routes de chronologie des ruches
+                .service(controllers::chronologie_controller::get_chronologie)
+
+                // Routes du tableau de bord
+                .service(controllers::tableau_de_bord_controller::get_tableau_de_bord)
 
                 // Routes de matériel
                 .service(controllers::materiel_controller::get_all_materiels)
@@ -5,15 +5,7 @@ use actix_web::{
 use futures::future::{ready, LocalBoxFuture, Ready};
 use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
 use std::env;
-use serde::{Deserialize, Serialize};
-
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TokenClaims {
-    pub sub: String,
-    pub exp: usize,
-    pub iat: usize,
-}
+use crate::models::utilisateur_models::TokenClaims;
 
 pub struct Auth;
 
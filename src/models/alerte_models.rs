@@ -0,0 +1,40 @@
+use crate::schema::*;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Types d'alerte
+pub const ALERTE_VARROA: &str = "varroa";
+pub const ALERTE_RESERVES: &str = "reserves";
+pub const ALERTE_APPAREIL_HORS_LIGNE: &str = "appareil_hors_ligne";
+pub const ALERTE_BATTERIE_FAIBLE: &str = "batterie_faible";
+
+/// Alerte levée sur une ruche ou un appareil, ouverte tant qu'elle n'est pas résolue
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = alerte)]
+pub struct Alerte {
+    pub id: i32,
+    pub id_ruche: Option<i32>,
+    pub type_alerte: String,
+    pub message: String,
+    // Valeur mesurée et seuil dépassé
+    pub valeur: Option<f64>,
+    pub seuil: Option<f64>,
+    pub date_alerte: NaiveDateTime,
+    pub date_resolution: Option<NaiveDateTime>,
+    pub id_appareil: Option<i32>,
+    // Propriétaire de l'appareil, destinataire de l'alerte
+    pub id_apiculteur: Option<i32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = alerte)]
+pub struct NewAlerte {
+    pub id_ruche: Option<i32>,
+    pub type_alerte: String,
+    pub message: String,
+    pub valeur: Option<f64>,
+    pub seuil: Option<f64>,
+    pub id_appareil: Option<i32>,
+    pub id_apiculteur: Option<i32>,
+}
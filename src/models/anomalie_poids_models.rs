@@ -0,0 +1,39 @@
+use crate::schema::*;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Statuts d'une anomalie (a_verifier, confirmee ou rejetee) : tant qu'elle n'est pas rejetée, la pesée est
+// écartée des agrégations
+pub const STATUT_A_VERIFIER: &str = "a_verifier";
+pub const STATUT_REJETEE: &str = "rejetee";
+
+/// Anomalie détectée sur une pesée : saut annulé par la pesée suivante (`saut`), poids négatif (`negatif`)
+/// ou valeur répétée à l'identique par une balance bloquée (`bloque`)
+#[derive(Queryable, QueryableByName, Selectable, Serialize)]
+#[diesel(table_name = anomalie_poids)]
+pub struct AnomaliePoids {
+    pub id: i32,
+    pub id_poids: i32,
+    pub type_anomalie: String,
+    pub detail: String,
+    pub statut: String,
+    pub date_detection: NaiveDateTime,
+    pub date_decision: Option<NaiveDateTime>,
+}
+
+/// Anomalie accompagnée de la pesée concernée
+#[derive(Serialize)]
+pub struct AnomaliePesee {
+    #[serde(flatten)]
+    pub anomalie: AnomaliePoids,
+    pub poids_ruche: Option<i32>,
+    pub horodatage: Option<NaiveDateTime>,
+}
+
+/// Décision de l'apiculteur sur une anomalie
+#[derive(Deserialize)]
+pub struct DecisionAnomalie {
+    // confirmee, rejetee, ou a_verifier pour revenir sur une décision
+    pub statut: String,
+}
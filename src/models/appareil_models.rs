@@ -0,0 +1,126 @@
+use crate::schema::*;
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::time::Duration;
+
+/// Appareil connecté (balance, capteur) envoyant ses mesures pour la ruche associée
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = appareil)]
+pub struct Appareil {
+    pub id: i32,
+    pub numero_serie: String,
+    pub modele: String,
+    pub version_firmware: Option<String>,
+    pub id_ruche: Option<i32>,
+    pub id_apiculteur: Option<i32>,
+    // Pourcentage de charge
+    pub niveau_batterie: Option<i32>,
+    pub derniere_connexion: Option<NaiveDateTime>,
+    // Empreinte de la clé d'API, jamais renvoyée
+    #[serde(skip)]
+    pub cle_api: Option<String>,
+    // Identifiant LoRaWAN (DevEUI)
+    pub dev_eui: Option<String>,
+    // Puissance du dernier signal LoRaWAN reçu, en dBm
+    pub dernier_rssi: Option<i32>,
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = appareil)]
+pub struct NewAppareil {
+    pub numero_serie: String,
+    pub modele: String,
+    pub version_firmware: Option<String>,
+    pub id_ruche: Option<i32>,
+    // Apiculteur authentifié, jamais lu dans le corps de la requête
+    #[serde(skip_deserializing)]
+    pub id_apiculteur: Option<i32>,
+    pub dev_eui: Option<String>,
+}
+
+#[derive(AsChangeset, Deserialize)]
+#[diesel(table_name = appareil)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateAppareil {
+    pub numero_serie: String,
+    pub modele: String,
+    pub version_firmware: Option<String>,
+    pub id_ruche: Option<i32>,
+    // Apiculteur authentifié, jamais lu dans le corps de la requête
+    #[serde(skip_deserializing)]
+    pub id_apiculteur: Option<i32>,
+    pub dev_eui: Option<String>,
+}
+
+/// Appareil accompagné de sa clé d'API en clair, renvoyée uniquement à sa création
+#[derive(Serialize)]
+pub struct AppareilAvecCle {
+    #[serde(flatten)]
+    pub appareil: Appareil,
+    pub cle_api: String,
+}
+
+/// Mesure envoyée par un appareil
+#[derive(Deserialize)]
+pub struct MesureAppareil {
+    // En grammes
+    pub poids: i32,
+    // Instant de réception si absente
+    pub date_mesure: Option<NaiveDate>,
+    pub niveau_batterie: Option<i32>,
+    pub version_firmware: Option<String>,
+}
+
+/// Paramètres de la surveillance des appareils, lus dans l'environnement
+#[derive(Clone)]
+pub struct SurveillanceAppareils {
+    // Durée sans mesure au-delà de laquelle un appareil est considéré hors ligne
+    pub delai_hors_ligne: TimeDelta,
+    // Pourcentage de charge en dessous duquel la batterie est considérée faible
+    pub seuil_batterie: i32,
+    // Intervalle entre deux vérifications des appareils
+    pub intervalle_verification: Duration,
+}
+
+impl SurveillanceAppareils {
+    /// Lit `APPAREIL_DELAI_HORS_LIGNE_MINUTES` (180 par défaut), `APPAREIL_SEUIL_BATTERIE` (20 par défaut)
+    /// et `APPAREIL_INTERVALLE_VERIFICATION_SECONDES` (300 par défaut, non nul)
+    pub fn depuis_env() -> Self {
+        let delai_hors_ligne = env::var("APPAREIL_DELAI_HORS_LIGNE_MINUTES").unwrap_or_else(|_| "180".to_string())
+            .parse::<i64>().expect("APPAREIL_DELAI_HORS_LIGNE_MINUTES doit être un nombre");
+        let seuil_batterie = env::var("APPAREIL_SEUIL_BATTERIE").unwrap_or_else(|_| "20".to_string())
+            .parse::<i32>().expect("APPAREIL_SEUIL_BATTERIE doit être un nombre");
+        let intervalle_verification = env::var("APPAREIL_INTERVALLE_VERIFICATION_SECONDES").unwrap_or_else(|_| "300".to_string())
+            .parse::<u64>().ok().filter(|secondes| *secondes > 0)
+            .expect("APPAREIL_INTERVALLE_VERIFICATION_SECONDES doit être un nombre strictement positif");
+
+        SurveillanceAppareils {
+            delai_hors_ligne: TimeDelta::minutes(delai_hors_ligne),
+            seuil_batterie,
+            intervalle_verification: Duration::from_secs(intervalle_verification),
+        }
+    }
+}
+
+/// État de connexion d'un appareil
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EtatAppareil {
+    EnLigne,
+    HorsLigne,
+    // Aucune mesure reçue depuis son enregistrement
+    JamaisConnecte,
+}
+
+/// Appareil accompagné de son état de santé
+#[derive(Serialize)]
+pub struct SanteAppareil {
+    #[serde(flatten)]
+    pub appareil: Appareil,
+    pub etat: EtatAppareil,
+    pub batterie_faible: bool,
+    // Minutes écoulées depuis la dernière mesure
+    pub minutes_sans_mesure: Option<i64>,
+}
\ No newline at end of file
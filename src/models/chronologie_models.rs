@@ -0,0 +1,92 @@
+use crate::models::configuration_ruche_models::ConfigurationRuche;
+use crate::models::hausse_models::PoseHausse;
+use crate::models::intervention_models::Intervention;
+use crate::models::materiel_models::{AffectationMateriel, Materiel};
+use crate::models::poids_models::Poids;
+use crate::models::production_models::Production;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Timestamp, Varchar};
+use serde::Serialize;
+
+// Types d'événement de la chronologie d'une ruche
+pub const EVENEMENT_INTERVENTION: &str = "intervention";
+pub const EVENEMENT_POIDS: &str = "poids";
+pub const EVENEMENT_PRODUCTION: &str = "production";
+pub const EVENEMENT_POSE_HAUSSE: &str = "pose_hausse";
+pub const EVENEMENT_RETRAIT_HAUSSE: &str = "retrait_hausse";
+pub const EVENEMENT_CONFIGURATION: &str = "configuration";
+pub const EVENEMENT_AFFECTATION_MATERIEL: &str = "affectation_materiel";
+pub const EVENEMENT_RETRAIT_MATERIEL: &str = "retrait_materiel";
+pub const TYPES_EVENEMENT: [&str; 8] = [
+    EVENEMENT_INTERVENTION,
+    EVENEMENT_POIDS,
+    EVENEMENT_PRODUCTION,
+    EVENEMENT_POSE_HAUSSE,
+    EVENEMENT_RETRAIT_HAUSSE,
+    EVENEMENT_CONFIGURATION,
+    EVENEMENT_AFFECTATION_MATERIEL,
+    EVENEMENT_RETRAIT_MATERIEL,
+];
+
+/// Référence d'un événement de la chronologie, avant chargement de son contenu
+#[derive(QueryableByName)]
+pub struct ReferenceEvenement {
+    #[diesel(sql_type = Varchar)]
+    pub type_evenement: String,
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+    #[diesel(sql_type = Timestamp)]
+    pub date_evenement: NaiveDateTime,
+}
+
+/// Nombre d'événements de la chronologie
+#[derive(QueryableByName)]
+pub struct TotalEvenements {
+    #[diesel(sql_type = BigInt)]
+    pub total: i64,
+}
+
+/// Contenu d'un événement, étiqueté par son type
+#[derive(Serialize)]
+#[serde(tag = "type", content = "donnees", rename_all = "snake_case")]
+pub enum EvenementRuche {
+    Intervention(Intervention),
+    Poids(Poids),
+    Production(Production),
+    // Une pose et un retrait de hausse portent la même période de pose
+    PoseHausse(PoseHausse),
+    RetraitHausse(PoseHausse),
+    // Relevé de cadres enregistré à la création de la ruche ou lorsque ses compteurs ont changé
+    Configuration(ConfigurationRuche),
+    // Matériel arrivé sur la ruche ou l'ayant quittée, hors hausses suivies par leurs poses
+    AffectationMateriel(MouvementMateriel),
+    RetraitMateriel(MouvementMateriel),
+}
+
+/// Affectation d'un matériel, avec son état actuel
+#[derive(Serialize)]
+pub struct MouvementMateriel {
+    #[serde(flatten)]
+    pub affectation: AffectationMateriel,
+    pub materiel: Materiel,
+}
+
+/// Entrée de la chronologie ; les événements datés d'un jour sont placés à minuit
+#[derive(Serialize)]
+pub struct EntreeChronologie {
+    pub date: NaiveDateTime,
+    #[serde(flatten)]
+    pub evenement: EvenementRuche,
+}
+
+/// Page de la chronologie d'une ruche, du plus récent au plus ancien
+#[derive(Serialize)]
+pub struct ChronologieRuche {
+    pub id_ruche: i32,
+    pub page: i64,
+    pub par_page: i64,
+    // Nombre total d'événements des types demandés
+    pub total: i64,
+    pub entrees: Vec<EntreeChronologie>,
+}
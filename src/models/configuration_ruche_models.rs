@@ -0,0 +1,44 @@
+use crate::schema::*;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDateTime;
+
+/// Relevé daté de la configuration des cadres d'une ruche
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = configuration_ruche)]
+pub struct ConfigurationRuche {
+    pub id: i32,
+    pub id_ruche: i32,
+    pub nombre_cadres_corp: Option<i32>,
+    pub nombre_hausses: Option<i32>,
+    pub nombre_cadre_couvain: Option<i32>,
+    pub nombre_cadre_nourriture: Option<i32>,
+    pub nombre_cadre_libre: Option<i32>,
+    pub date_releve: NaiveDateTime,
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = configuration_ruche)]
+pub struct NewConfigurationRuche {
+    // Renseigné depuis le chemin de la requête
+    #[serde(default)]
+    pub id_ruche: i32,
+    pub nombre_cadres_corp: Option<i32>,
+    pub nombre_hausses: Option<i32>,
+    pub nombre_cadre_couvain: Option<i32>,
+    pub nombre_cadre_nourriture: Option<i32>,
+    pub nombre_cadre_libre: Option<i32>,
+    // Date du jour si absente
+    pub date_releve: Option<NaiveDateTime>,
+}
+
+impl NewConfigurationRuche {
+    /// Indique si le relevé reprend exactement les compteurs d'une configuration existante
+    pub fn same_counts(&self, configuration: &ConfigurationRuche) -> bool {
+        self.nombre_cadres_corp == configuration.nombre_cadres_corp
+            && self.nombre_hausses == configuration.nombre_hausses
+            && self.nombre_cadre_couvain == configuration.nombre_cadre_couvain
+            && self.nombre_cadre_nourriture == configuration.nombre_cadre_nourriture
+            && self.nombre_cadre_libre == configuration.nombre_cadre_libre
+    }
+}
@@ -0,0 +1,94 @@
+use crate::models::production_models::Production;
+use crate::schema::*;
+use chrono::{Datelike, NaiveDate};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Ressource mellifère proche d'un rucher : espèce, période de floraison attendue chaque année et surface (ha).
+/// Seuls le jour et le mois des dates de floraison comptent, une fin antérieure au début s'entendant l'année suivante
+#[derive(Queryable, Selectable, Serialize)]
+#[diesel(table_name = ressource_mellifere)]
+pub struct RessourceMellifere {
+    pub id: i32,
+    pub id_rucher: i32,
+    pub espece: String,
+    pub debut_floraison: NaiveDate,
+    pub fin_floraison: NaiveDate,
+    pub surface: Option<f64>,
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = ressource_mellifere)]
+pub struct NewRessourceMellifere {
+    // Renseigné à partir du chemin de la requête
+    #[serde(default)]
+    pub id_rucher: i32,
+    pub espece: String,
+    pub debut_floraison: NaiveDate,
+    pub fin_floraison: NaiveDate,
+    pub surface: Option<f64>,
+}
+
+#[derive(AsChangeset, Deserialize)]
+#[diesel(table_name = ressource_mellifere)]
+pub struct UpdateRessourceMellifere {
+    pub espece: Option<String>,
+    pub debut_floraison: Option<NaiveDate>,
+    pub fin_floraison: Option<NaiveDate>,
+    pub surface: Option<f64>,
+}
+
+/// Floraison attendue d'une ressource une année donnée
+#[derive(Serialize)]
+pub struct FenetreFloraison {
+    pub id_ressource: i32,
+    pub espece: String,
+    pub surface: Option<f64>,
+    pub debut: NaiveDate,
+    pub fin: NaiveDate,
+}
+
+// Jour et mois d'une date reportés sur une année, le 29 février devenant le 28 hors année bissextile.
+// `None` si l'année sort du calendrier
+fn date_de_l_annee(date: NaiveDate, annee: i32) -> Option<NaiveDate> {
+    match (date.month(), date.day()) {
+        (2, 29) => date.with_year(annee).or_else(|| NaiveDate::from_ymd_opt(annee, 2, 28)),
+        _ => date.with_year(annee),
+    }
+}
+
+impl RessourceMellifere {
+    /// Floraisons attendues de la ressource chevauchant une période, jours inclus, hors années sortant du calendrier
+    pub fn fenetres(&self, debut: NaiveDate, fin: NaiveDate) -> Vec<FenetreFloraison> {
+        let chevauche_annee = (self.fin_floraison.month(), self.fin_floraison.day())
+            < (self.debut_floraison.month(), self.debut_floraison.day());
+
+        (debut.year() - 1..=fin.year())
+            .filter_map(|annee| Some(FenetreFloraison {
+                id_ressource: self.id,
+                espece: self.espece.clone(),
+                surface: self.surface,
+                debut: date_de_l_annee(self.debut_floraison, annee)?,
+                fin: date_de_l_annee(self.fin_floraison, annee + chevauche_annee as i32)?,
+            }))
+            .filter(|fenetre| fenetre.debut <= fin && fenetre.fin >= debut)
+            .collect()
+    }
+}
+
+/// Floraisons d'un rucher à une date : ressources en fleur et floraisons débutant dans les jours suivants
+#[derive(Serialize)]
+pub struct FloraisonsDuMoment {
+    pub id_rucher: i32,
+    pub date: NaiveDate,
+    pub en_fleur: Vec<FenetreFloraison>,
+    pub a_venir: Vec<FenetreFloraison>,
+}
+
+/// Production d'une ruche avec les floraisons de son rucher depuis la production précédente du même type
+#[derive(Serialize)]
+pub struct ProductionFloraisons {
+    #[serde(flatten)]
+    pub production: Production,
+    pub floraisons: Vec<FenetreFloraison>,
+}
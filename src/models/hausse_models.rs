@@ -0,0 +1,98 @@
+use crate::schema::*;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+use diesel::result::Error;
+use std::fmt;
+
+/// Période pendant laquelle une hausse (matériel de type "hausse") est posée sur une ruche
+#[derive(Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = pose_hausse)]
+pub struct PoseHausse {
+    pub id: i32,
+    pub id_materiel: i32,
+    pub id_ruche: i32,
+    pub date_pose: NaiveDate,
+    pub date_retrait: Option<NaiveDate>,
+    // Poids de miel extrait de la hausse lors de la récolte, en grammes
+    pub poids_miel_extrait: Option<i32>,
+    // Production générée à partir de cette hausse
+    pub id_production: Option<i32>,
+    // Session de récolte au cours de laquelle la hausse a été retirée
+    pub id_recolte: Option<i32>,
+    // Pesées de la hausse pleine et vide (après extraction), en grammes
+    pub poids_plein: Option<i32>,
+    pub poids_vide: Option<i32>,
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = pose_hausse)]
+pub struct NewPoseHausse {
+    pub id_materiel: i32,
+    // Renseigné depuis le chemin de la requête
+    #[serde(default)]
+    pub id_ruche: i32,
+    // Date du jour si absente
+    pub date_pose: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+pub struct RetraitHausse {
+    // Date du jour si absente
+    pub date_retrait: Option<NaiveDate>,
+    // Déduit des pesées pleine et vide s'il n'est pas renseigné
+    pub poids_miel_extrait: Option<i32>,
+    pub poids_plein: Option<i32>,
+    pub poids_vide: Option<i32>,
+}
+
+impl RetraitHausse {
+    /// Poids de miel extrait, renseigné ou calculé à partir des pesées, `PeseesIncoherentes` si la hausse vide
+    /// pèse plus que pleine
+    pub fn poids_miel(&self) -> Result<Option<i32>, ErreurHausse> {
+        match (self.poids_miel_extrait, self.poids_plein, self.poids_vide) {
+            (Some(poids), _, _) => Ok(Some(poids)),
+            (None, Some(plein), Some(vide)) => match plein.checked_sub(vide) {
+                Some(poids) if poids >= 0 => Ok(Some(poids)),
+                _ => Err(ErreurHausse::PeseesIncoherentes),
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RecolteHausses {
+    // Date de retrait des hausses récoltées
+    pub date_recolte: NaiveDate,
+}
+/// Erreur de la gestion des hausses et des récoltes, distinguant les erreurs de la base des données refusées
+#[derive(Debug)]
+pub enum ErreurHausse {
+    Base(Error),
+    // Le matériel à poser n'est pas une hausse
+    PasUneHausse,
+    // Hausse vide plus lourde que pleine, ou écart hors limites
+    PeseesIncoherentes,
+    // Miel extrait des hausses d'une ruche dépassant la capacité d'une production, en grammes
+    ProductionHorsLimites { id_ruche: i32, total: i64 },
+}
+
+impl From<Error> for ErreurHausse {
+    fn from(e: Error) -> Self {
+        ErreurHausse::Base(e)
+    }
+}
+
+impl fmt::Display for ErreurHausse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurHausse::Base(e) => write!(f, "{}", e),
+            ErreurHausse::PasUneHausse => write!(f, "Ce matériel n'est pas une hausse"),
+            ErreurHausse::PeseesIncoherentes => write!(f, "La hausse vide pèse plus que la hausse pleine"),
+            ErreurHausse::ProductionHorsLimites { id_ruche, total } => {
+                write!(f, "Miel extrait hors limites pour la ruche {} : {} g", id_ruche, total)
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::models::poids_models::Poids;
+use crate::models::releve_capteur_models::ReleveCapteur;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Uplink transmis par un serveur de réseau LoRaWAN, au format de The Things Stack (v3) ou de ChirpStack (v4)
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum UplinkLoRaWan {
+    TheThingsStack(UplinkTheThingsStack),
+    ChirpStack(UplinkChirpStack),
+}
+
+#[derive(Deserialize)]
+pub struct UplinkTheThingsStack {
+    pub end_device_ids: IdentifiantsTheThingsStack,
+    pub received_at: DateTime<Utc>,
+    pub uplink_message: MessageTheThingsStack,
+}
+
+#[derive(Deserialize)]
+pub struct IdentifiantsTheThingsStack {
+    pub dev_eui: String,
+}
+
+#[derive(Deserialize)]
+pub struct MessageTheThingsStack {
+    pub f_port: Option<u8>,
+    // Charge utile encodée en base64, absente des trames sans données
+    pub frm_payload: Option<String>,
+    #[serde(default)]
+    pub rx_metadata: Vec<ReceptionPasserelle>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UplinkChirpStack {
+    pub device_info: IdentifiantsChirpStack,
+    pub time: DateTime<Utc>,
+    pub f_port: Option<u8>,
+    pub data: Option<String>,
+    #[serde(default)]
+    pub rx_info: Vec<ReceptionPasserelle>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentifiantsChirpStack {
+    pub dev_eui: String,
+}
+
+/// Réception de la trame par une passerelle
+#[derive(Deserialize)]
+pub struct ReceptionPasserelle {
+    pub rssi: Option<f64>,
+}
+
+/// Uplink ramené à ce qui est commun aux serveurs de réseau
+pub struct Uplink {
+    // En majuscules
+    pub dev_eui: String,
+    pub port: Option<u8>,
+    pub charge: Vec<u8>,
+    pub date_reception: NaiveDateTime,
+    // Meilleur signal parmi les passerelles ayant reçu la trame, en dBm
+    pub rssi: Option<i32>,
+}
+
+impl UplinkLoRaWan {
+    /// Décode la charge utile base64 et retient le meilleur signal reçu
+    pub fn normaliser(self) -> Result<Uplink, base64::DecodeError> {
+        let (dev_eui, port, charge, date, receptions) = match self {
+            UplinkLoRaWan::TheThingsStack(u) => (
+                u.end_device_ids.dev_eui,
+                u.uplink_message.f_port,
+                u.uplink_message.frm_payload,
+                u.received_at,
+                u.uplink_message.rx_metadata,
+            ),
+            UplinkLoRaWan::ChirpStack(u) => (u.device_info.dev_eui, u.f_port, u.data, u.time, u.rx_info),
+        };
+
+        Ok(Uplink {
+            dev_eui: dev_eui.to_uppercase(),
+            port,
+            charge: STANDARD.decode(charge.unwrap_or_default())?,
+            date_reception: date.naive_utc(),
+            rssi: receptions
+                .iter()
+                .filter_map(|r| r.rssi)
+                .max_by(f64::total_cmp)
+                .map(|rssi| rssi.round() as i32),
+        })
+    }
+}
+
+/// Mesures extraites de la charge utile par le décodeur du modèle de l'appareil
+#[derive(Default)]
+pub struct MesuresDecodees {
+    // En grammes
+    pub poids: Option<i32>,
+    // En degrés Celsius
+    pub temperature: Option<f64>,
+    // Humidité relative, en pourcentage
+    pub humidite: Option<f64>,
+    pub niveau_batterie: Option<i32>,
+}
+
+/// Données enregistrées pour la ruche associée à l'appareil à la réception d'un uplink
+#[derive(Serialize)]
+pub struct ResultatUplink {
+    pub id_appareil: i32,
+    pub poids: Option<Poids>,
+    pub releves: Vec<ReleveCapteur>,
+}
\ No newline at end of file
@@ -0,0 +1,93 @@
+use crate::schema::*;
+use crate::models::production_models::Production;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+
+/// Lot de miel, constitué d'une ou plusieurs productions
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = lot_miel)]
+pub struct LotMiel {
+    pub id: i32,
+    pub numero_lot: String,
+    pub type_miel: Option<String>,
+    pub date_extraction: Option<NaiveDate>,
+    pub taux_humidite: Option<f64>,
+    // Date limite d'utilisation optimale
+    pub dluo: Option<NaiveDate>,
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = lot_miel)]
+pub struct NewLotMiel {
+    pub numero_lot: String,
+    pub type_miel: Option<String>,
+    pub date_extraction: Option<NaiveDate>,
+    pub taux_humidite: Option<f64>,
+    pub dluo: Option<NaiveDate>,
+    // Productions composant le lot
+    #[diesel(skip_insertion)]
+    #[serde(default)]
+    pub productions: Vec<i32>,
+    #[diesel(skip_insertion)]
+    #[serde(default)]
+    pub pots: Vec<LotPot>,
+}
+
+#[derive(AsChangeset, Deserialize)]
+#[diesel(table_name = lot_miel)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateLotMiel {
+    pub numero_lot: String,
+    pub type_miel: Option<String>,
+    pub date_extraction: Option<NaiveDate>,
+    pub taux_humidite: Option<f64>,
+    pub dluo: Option<NaiveDate>,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = lot_production)]
+pub struct LotProduction {
+    pub id_lot: i32,
+    pub id_production: i32,
+}
+
+/// Nombre de pots d'une contenance donnée (en grammes) conditionnés pour un lot
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = lot_pot)]
+pub struct LotPot {
+    // Renseigné à partir du lot concerné
+    #[serde(default)]
+    pub id_lot: i32,
+    pub contenance: i32,
+    pub nombre_pots: i32,
+}
+
+/// Lot avec ses productions et son conditionnement
+#[derive(Serialize)]
+pub struct LotDetail {
+    #[serde(flatten)]
+    pub lot: LotMiel,
+    pub productions: Vec<Production>,
+    pub pots: Vec<LotPot>,
+}
+
+/// Production ayant contribué à un lot, avec sa ruche et son rucher d'origine
+#[derive(Queryable, Serialize)]
+pub struct ProvenanceLot {
+    pub id_production: i32,
+    pub date_production: Option<NaiveDate>,
+    pub quantite_production: Option<i32>,
+    pub id_ruche: i32,
+    pub nom_ruche: Option<String>,
+    pub numero_ruche: Option<i32>,
+    pub id_rucher: Option<i32>,
+    pub nom_rucher: Option<String>,
+}
+
+/// Traçabilité d'un lot jusqu'aux ruches et ruchers
+#[derive(Serialize)]
+pub struct TracabiliteLot {
+    pub lot: LotMiel,
+    pub provenances: Vec<ProvenanceLot>,
+}
\ No newline at end of file
@@ -1,6 +1,18 @@
 use crate::schema::*;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use chrono::NaiveDateTime;
+
+// États possibles d'un matériel, repris par la contrainte materiel_etat_materiel_check
+pub const ETATS_MATERIEL: [&str; 6] = ["disponible", "en_service", "a_reparer", "endommage", "mauvais", "hors_service"];
+
+// États d'un matériel à réparer ou à remplacer
+pub const ETATS_A_REPARER: [&str; 4] = ["a_reparer", "endommage", "mauvais", "hors_service"];
+
+/// Vérifie qu'un état de matériel fait partie du vocabulaire, un état absent étant accepté
+pub fn etat_materiel_valide(etat: Option<&str>) -> bool {
+    etat.is_none_or(|etat| ETATS_MATERIEL.contains(&etat))
+}
 
 #[derive(Queryable, Selectable, Serialize, Deserialize)]
 #[diesel(table_name = materiel)]
@@ -10,6 +22,7 @@ pub struct Materiel {
     pub nom_materiel: Option<String>,
     pub type_materiel: Option<String>,
     pub etat_materiel: Option<String>,
+    pub poids_tare: Option<i32>,
 }
 
 #[derive(Insertable, AsChangeset, Deserialize)]
@@ -19,6 +32,7 @@ pub struct NewMateriel {
     pub nom_materiel: Option<String>,
     pub type_materiel: Option<String>,
     pub etat_materiel: Option<String>,
+    pub poids_tare: Option<i32>,
 }
 
 #[derive(AsChangeset, Deserialize)]
@@ -29,6 +43,7 @@ pub struct UpdateMateriel {
     pub nom_materiel: Option<String>,
     pub type_materiel: Option<String>,
     pub etat_materiel: Option<String>,
+    pub poids_tare: Option<i32>,
 }
 #[derive(Insertable, Deserialize)]
 #[diesel(table_name = materiel)]
@@ -38,6 +53,7 @@ pub struct NewMaterielWithId {
     pub nom_materiel: Option<String>,
     pub type_materiel: Option<String>,
     pub etat_materiel: Option<String>,
+    pub poids_tare: Option<i32>,
 }
 
 #[derive(AsChangeset, Deserialize)]
@@ -48,4 +64,18 @@ pub struct UpdateMaterielWithId {
     pub nom_materiel: Option<String>,
     pub type_materiel: Option<String>,
     pub etat_materiel: Option<String>,
+    pub poids_tare: Option<i32>,
+}
+
+/// Changement de ruche d'un matériel, enregistré par la base à chaque affectation
+#[derive(Queryable, Selectable, Serialize)]
+#[diesel(table_name = affectation_materiel)]
+pub struct AffectationMateriel {
+    pub id: i32,
+    pub id_materiel: i32,
+    // Ruche quittée, nulle à la création du matériel ou s'il était en stock
+    pub id_ruche_precedente: Option<i32>,
+    // Ruche d'arrivée, nulle si le matériel est remis en stock
+    pub id_ruche: Option<i32>,
+    pub date_affectation: NaiveDateTime,
 }
@@ -0,0 +1,63 @@
+use crate::schema::*;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::PathBuf;
+
+// Source d'une observation saisie par l'apiculteur, qu'un import ne remplace pas
+pub const SOURCE_MANUELLE: &str = "manuelle";
+
+/// Observation météo d'un jour au rucher : températures (°C), précipitations (mm) et vent moyen (km/h)
+#[derive(Queryable, Selectable, Serialize)]
+#[diesel(table_name = observation_meteo)]
+pub struct ObservationMeteo {
+    pub id: i32,
+    pub id_rucher: i32,
+    pub date_observation: NaiveDate,
+    pub temperature_min: Option<f64>,
+    pub temperature_max: Option<f64>,
+    pub precipitations: Option<f64>,
+    pub vent: Option<f64>,
+    pub source: String,
+}
+
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = observation_meteo)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewObservationMeteo {
+    // Renseigné à partir du chemin de la requête ou du rucher importé
+    #[serde(default)]
+    pub id_rucher: i32,
+    pub date_observation: NaiveDate,
+    pub temperature_min: Option<f64>,
+    pub temperature_max: Option<f64>,
+    pub precipitations: Option<f64>,
+    pub vent: Option<f64>,
+    // `manuelle` pour une saisie, le nom du fournisseur pour un import
+    #[serde(default = "source_manuelle")]
+    pub source: String,
+}
+
+fn source_manuelle() -> String {
+    SOURCE_MANUELLE.to_string()
+}
+
+/// Fournisseur des observations météo importées, lu dans l'environnement
+#[derive(Clone)]
+pub struct ConfigMeteo {
+    pub fournisseur: String,
+    // Répertoire des fichiers d'observations du fournisseur `fichier`
+    pub repertoire: PathBuf,
+}
+
+impl ConfigMeteo {
+    /// Lit `METEO_FOURNISSEUR` (l'import est désactivé en son absence) et `METEO_REPERTOIRE`
+    /// (`fixtures/meteo` par défaut)
+    pub fn depuis_env() -> Option<Self> {
+        Some(ConfigMeteo {
+            fournisseur: env::var("METEO_FOURNISSEUR").ok()?,
+            repertoire: env::var("METEO_REPERTOIRE").unwrap_or_else(|_| "fixtures/meteo".to_string()).into(),
+        })
+    }
+}
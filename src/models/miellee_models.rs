@@ -0,0 +1,58 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Poids net d'une ruche à la dernière pesée d'un jour
+#[derive(Serialize)]
+pub struct PoidsNetJour {
+    pub date: NaiveDate,
+    pub poids_net: i32,
+}
+
+/// Fiabilité d'une prévision, selon les pesées disponibles
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FiabilitePrevision {
+    // Au moins une pesée par jour ou presque, typiquement une balance connectée
+    Elevee,
+    // Pesées espacées, bornes larges
+    Moyenne,
+    // Deux pesées seulement : tendance sans bornes de confiance
+    Faible,
+    // Moins de deux pesées, aucune tendance
+    Insuffisante,
+}
+
+/// Date projetée, avec ses bornes à 95 % (absentes si la tendance basse n'atteint pas le seuil)
+#[derive(Serialize)]
+pub struct DateProjetee {
+    pub date: NaiveDate,
+    pub au_plus_tot: Option<NaiveDate>,
+    pub au_plus_tard: Option<NaiveDate>,
+}
+
+/// Prévision de miellée d'une ruche à partir de l'évolution récente de son poids net
+#[derive(Serialize)]
+pub struct PrevisionMiellee {
+    pub id_ruche: i32,
+    // Pesées retenues, depuis le début de la fenêtre ou la dernière récolte
+    pub debut_fenetre: NaiveDate,
+    pub points: Vec<PoidsNetJour>,
+    pub fiabilite: FiabilitePrevision,
+    // Prise de poids nette par jour (pente de la droite ajustée) et son intervalle de confiance à 95 %, en grammes
+    pub gain_journalier: Option<f64>,
+    pub gain_journalier_min: Option<f64>,
+    pub gain_journalier_max: Option<f64>,
+    pub hausses_posees: i64,
+    // Miel que contient une hausse pleine, en grammes
+    pub capacite_hausse: i32,
+    // Vrai si la capacité est la moyenne du miel extrait des hausses déjà récoltées de la ruche
+    pub capacite_mesuree: bool,
+    // Prise de poids nette depuis la pose de la plus ancienne hausse en place, en grammes
+    pub miel_en_hausses: Option<i32>,
+    // Part de la capacité des hausses posées déjà remplie
+    pub remplissage: Option<f64>,
+    // Date à laquelle les hausses posées seront pleines
+    pub hausses_pleines: Option<DateProjetee>,
+    // Date à laquelle poser une hausse supplémentaire
+    pub nouvelle_hausse: Option<DateProjetee>,
+}
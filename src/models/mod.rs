@@ -3,4 +3,24 @@ pub mod utilisateur_models;
 pub mod ruche_models;
 pub mod production_models;
 pub mod poids_models;
-pub mod materiel_models;
\ No newline at end of file
+pub mod materiel_models;
+pub mod configuration_ruche_models;
+pub mod hausse_models;
+pub mod lot_models;
+pub mod recolte_models;
+pub mod rucher_models;
+pub mod traitement_models;
+pub mod alerte_models;
+pub mod varroa_models;
+pub mod nourrissement_models;
+pub mod appareil_models;
+pub mod releve_capteur_models;
+pub mod lorawan_models;
+pub mod mqtt_models;
+pub mod miellee_models;
+pub mod anomalie_poids_models;
+pub mod meteo_models;
+pub mod floraison_models;
+pub mod sante_models;
+pub mod tableau_de_bord_models;
+pub mod chronologie_models;
\ No newline at end of file
@@ -0,0 +1,83 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::env;
+
+/// Paramètres de l'abonnement MQTT, lus dans l'environnement
+#[derive(Clone)]
+pub struct ConfigMqtt {
+    pub hote: String,
+    pub port: u16,
+    pub identifiant_client: String,
+    pub utilisateur: Option<String>,
+    pub mot_de_passe: Option<String>,
+    // Filtres de sujets auxquels s'abonner
+    pub sujets: Vec<String>,
+    // Nombre de messages en attente de traitement au-delà duquel la lecture du broker est suspendue
+    pub capacite: usize,
+}
+
+impl ConfigMqtt {
+    /// Lit `MQTT_BROKER_HOST` (l'abonnement est désactivé en son absence), `MQTT_BROKER_PORT` (1883 par défaut),
+    /// `MQTT_CLIENT_ID`, `MQTT_USERNAME`, `MQTT_PASSWORD`, `MQTT_TOPICS` (filtres séparés par des virgules,
+    /// `apiary/+/hive/+/weight` par défaut) et `MQTT_CAPACITE` (10 par défaut)
+    pub fn depuis_env() -> Option<Self> {
+        let hote = env::var("MQTT_BROKER_HOST").ok()?;
+        let port = env::var("MQTT_BROKER_PORT").unwrap_or_else(|_| "1883".to_string())
+            .parse::<u16>().expect("MQTT_BROKER_PORT doit être un nombre");
+        let capacite = env::var("MQTT_CAPACITE").unwrap_or_else(|_| "10".to_string())
+            .parse::<usize>().expect("MQTT_CAPACITE doit être un nombre");
+
+        Some(ConfigMqtt {
+            hote,
+            port,
+            identifiant_client: env::var("MQTT_CLIENT_ID").unwrap_or_else(|_| "happytobees-backend".to_string()),
+            utilisateur: env::var("MQTT_USERNAME").ok(),
+            mot_de_passe: env::var("MQTT_PASSWORD").ok(),
+            sujets: env::var("MQTT_TOPICS")
+                .unwrap_or_else(|_| "apiary/+/hive/+/weight".to_string())
+                .split(',')
+                .map(|sujet| sujet.trim().to_string())
+                .filter(|sujet| !sujet.is_empty())
+                .collect(),
+            capacite,
+        })
+    }
+}
+
+/// Ruche désignée par un sujet de la forme `apiary/<id_rucher>/hive/<id_ruche>/...`
+#[derive(Clone, Copy)]
+pub struct SujetRuche {
+    pub id_rucher: Option<i32>,
+    pub id_ruche: i32,
+}
+
+impl SujetRuche {
+    /// Extrait les identifiants suivant les segments `apiary` (facultatif) et `hive`
+    pub fn depuis_sujet(sujet: &str) -> Option<Self> {
+        let segments: Vec<&str> = sujet.split('/').collect();
+        let identifiant = |nom: &str| {
+            segments
+                .iter()
+                .position(|segment| *segment == nom)
+                .and_then(|i| segments.get(i + 1))
+                .map(|valeur| valeur.parse::<i32>())
+        };
+
+        Some(SujetRuche {
+            id_rucher: identifiant("apiary").transpose().ok()?,
+            id_ruche: identifiant("hive")?.ok()?,
+        })
+    }
+}
+
+/// Pesée publiée sur MQTT : un nombre de grammes, ou un objet précisant la date de la mesure
+#[derive(Clone, Copy, Deserialize)]
+#[serde(untagged)]
+pub enum MesureMqtt {
+    Poids(i32),
+    Detaillee {
+        poids: i32,
+        // Instant de réception si absente
+        date_mesure: Option<NaiveDate>,
+    },
+}
\ No newline at end of file
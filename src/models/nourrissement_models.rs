@@ -0,0 +1,81 @@
+use crate::schema::*;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Nourrissement d'une ruche
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = nourrissement)]
+pub struct Nourrissement {
+    pub id: i32,
+    pub id_ruche: i32,
+    pub date_nourrissement: NaiveDate,
+    // Code d'un type de nourriture (sirop_1_1, sirop_2_1, candi, galette_proteinee...)
+    pub type_nourriture: String,
+    // En grammes
+    pub quantite: i32,
+    pub commentaire: Option<String>,
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = nourrissement)]
+pub struct NewNourrissement {
+    // Renseigné à partir du chemin de la requête
+    #[serde(default)]
+    pub id_ruche: i32,
+    // Date du jour si absente
+    pub date_nourrissement: Option<NaiveDate>,
+    pub type_nourriture: String,
+    pub quantite: i32,
+    pub commentaire: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = type_nourriture)]
+pub struct TypeNourriture {
+    pub code: String,
+    pub libelle: String,
+    // Part de la quantité apportée stockée en réserves
+    pub facteur_reserve: f64,
+}
+
+/// Seuil de réserves hivernales sur une saison (mois_debut à mois_fin inclus)
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = seuil_reserve)]
+pub struct SeuilReserve {
+    pub id: i32,
+    pub libelle: Option<String>,
+    pub mois_debut: i32,
+    pub mois_fin: i32,
+    // En grammes
+    pub seuil: i32,
+}
+
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = seuil_reserve)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewSeuilReserve {
+    pub libelle: Option<String>,
+    pub mois_debut: i32,
+    pub mois_fin: i32,
+    pub seuil: i32,
+}
+
+/// Estimation des réserves d'une ruche à partir de sa dernière pesée et des nourrissements suivants.
+/// Toutes les masses sont en grammes
+#[derive(Serialize)]
+pub struct ReserveHivernale {
+    pub id_ruche: i32,
+    pub date_pesee: Option<NaiveDate>,
+    pub poids_ruche: Option<i32>,
+    // Tare de la ruche (matériel fixe et hausses) à la date de la pesée
+    pub tare_materiel: i32,
+    // Abeilles, couvain et cires
+    pub poids_colonie: i32,
+    // Réserves apportées par les nourrissements postérieurs à la pesée
+    pub apports_nourrissement: i32,
+    // Absentes sans pesée
+    pub reserves_estimees: Option<i32>,
+    pub seuil: Option<SeuilReserve>,
+    pub sous_le_seuil: bool,
+}
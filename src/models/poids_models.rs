@@ -1,7 +1,9 @@
 use crate::schema::*;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::sql_types::{Date, Double, Integer, Nullable};
+use crate::models::meteo_models::ObservationMeteo;
 
 #[derive(Queryable, Selectable, Serialize, Deserialize)]
 #[diesel(table_name = poids)]
@@ -10,6 +12,12 @@ pub struct Poids {
     pub id_ruche: Option<i32>,
     pub poids_ruche: Option<i32>,
     pub date_creation: Option<NaiveDate>,
+    // Instant de la pesée, le début du jour de `date_creation` si absent
+    pub horodatage: Option<NaiveDateTime>,
+    // Déjà comptée dans les agrégats horaires et journaliers
+    pub agrege: bool,
+    // Écartée des agrégations tant qu'une anomalie non rejetée la concerne
+    pub exclu: bool,
 }
 
 #[derive(Insertable, AsChangeset, Deserialize)]
@@ -18,6 +26,8 @@ pub struct NewPoids {
     pub id_ruche: Option<i32>,
     pub poids_ruche: Option<i32>,
     pub date_creation: Option<NaiveDate>,
+    // Instant de la pesée, le début du jour de `date_creation` si absent
+    pub horodatage: Option<NaiveDateTime>,
 }
 
 #[derive(AsChangeset, Deserialize)]
@@ -27,6 +37,46 @@ pub struct UpdatePoids {
     pub id_ruche: Option<i32>,
     pub poids_ruche: Option<i32>,
     pub date_creation: Option<NaiveDate>,
+    // Instant de la pesée, le début du jour de `date_creation` si absent
+    pub horodatage: Option<NaiveDateTime>,
+}
+
+/// Correction d'une pesée, avec son motif facultatif
+#[derive(Deserialize)]
+pub struct UpdatePoidsMotif {
+    #[serde(flatten)]
+    pub poids: UpdatePoids,
+    pub motif: Option<String>,
+}
+
+/// Correction apportée à une pesée, avec ses valeurs avant et après
+#[derive(Queryable, Selectable, Serialize)]
+#[diesel(table_name = correction_poids)]
+pub struct CorrectionPoids {
+    pub id: i32,
+    // Absente si la pesée a été supprimée depuis
+    pub id_poids: Option<i32>,
+    pub ancienne_ruche: Option<i32>,
+    pub nouvelle_ruche: Option<i32>,
+    pub ancien_poids: Option<i32>,
+    pub nouveau_poids: Option<i32>,
+    pub ancien_horodatage: Option<NaiveDateTime>,
+    pub nouvel_horodatage: Option<NaiveDateTime>,
+    pub motif: Option<String>,
+    pub date_correction: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = correction_poids)]
+pub struct NewCorrectionPoids {
+    pub id_poids: i32,
+    pub ancienne_ruche: Option<i32>,
+    pub nouvelle_ruche: Option<i32>,
+    pub ancien_poids: Option<i32>,
+    pub nouveau_poids: Option<i32>,
+    pub ancien_horodatage: Option<NaiveDateTime>,
+    pub nouvel_horodatage: Option<NaiveDateTime>,
+    pub motif: Option<String>,
 }
 
 #[derive(Insertable, Deserialize)]
@@ -45,4 +95,52 @@ pub struct UpdatePoidsWithId {
     pub id_ruche: Option<i32>,
     pub poids_ruche: Option<i32>,
     pub date_creation: Option<NaiveDate>,
+}
+
+/// Poids utilisé par les agrégations : lecture brute de la balance ou poids net de la tare
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TypePoids {
+    #[default]
+    Brut,
+    Net,
+}
+
+/// Pesée avec la tare de la ruche à sa date et le poids net qui en découle
+#[derive(Queryable, Serialize)]
+pub struct PoidsNet {
+    pub id: i32,
+    pub id_ruche: Option<i32>,
+    pub date_creation: Option<NaiveDate>,
+    pub poids_brut: Option<i32>,
+    pub tare: Option<i32>,
+    pub poids_net: Option<i32>,
+}
+
+/// Moyenne des pesées d'une ruche sur une période (année ou mois)
+#[derive(QueryableByName)]
+pub struct MoyennePeriode {
+    #[diesel(sql_type = Integer)]
+    pub periode: i32,
+    #[diesel(sql_type = Double)]
+    pub moyenne: f64,
+}
+
+/// Dernière pesée d'un jour et variation depuis celle de la veille, absente sans pesée la veille
+#[derive(QueryableByName, Serialize)]
+pub struct PoidsJour {
+    #[diesel(sql_type = Date)]
+    pub date: NaiveDate,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub poids: Option<i32>,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub variation: Option<i32>,
+}
+
+/// Variation du poids d'un jour, avec la météo observée ce jour-là au rucher de la ruche
+#[derive(Serialize)]
+pub struct VariationJour {
+    #[serde(flatten)]
+    pub poids: PoidsJour,
+    pub meteo: Option<ObservationMeteo>,
 }
\ No newline at end of file
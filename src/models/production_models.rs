@@ -1,7 +1,9 @@
 use crate::schema::*;
 use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Double, Integer, Nullable, Varchar};
 use serde::{Deserialize, Serialize};
 use chrono::NaiveDate;
+use crate::models::traitement_models::ProductionEnDelaiAttente;
 
 #[derive(Queryable, Selectable, Serialize, Deserialize)]
 #[diesel(table_name = production)]
@@ -10,6 +12,21 @@ pub struct Production {
     pub id_ruche: Option<i32>,
     pub quantite_production: Option<i32>,
     pub date_creation: Option<NaiveDate>,
+    // miel, pollen, propolis, cire ou gelee_royale
+    pub type_produit: String,
+    // Code d'une unité de mesure (g, kg, ml...)
+    pub unite: String,
+    pub variete_miel: Option<String>,
+}
+
+/// Production créée ou modifiée, signalée si elle est datée pendant le délai d'attente d'un traitement de sa ruche
+#[derive(Serialize)]
+pub struct ProductionEnregistree {
+    #[serde(flatten)]
+    pub production: Production,
+    pub en_delai_attente: bool,
+    // Traitements concernés, avec la fin de leur délai d'attente
+    pub delais_attente: Vec<ProductionEnDelaiAttente>,
 }
 
 #[derive(Insertable, Deserialize)]
@@ -18,6 +35,10 @@ pub struct NewProduction {
     pub id_ruche: Option<i32>,
     pub quantite_production: Option<i32>,
     pub date_creation: Option<NaiveDate>,
+    // Miel en grammes si absents
+    pub type_produit: Option<String>,
+    pub unite: Option<String>,
+    pub variete_miel: Option<String>,
 }
 
 #[derive(AsChangeset, Deserialize)]
@@ -26,4 +47,130 @@ pub struct UpdateProduction {
     pub id_ruche: Option<i32>,
     pub quantite_production: Option<i32>,
     pub date_creation: Option<NaiveDate>,
+    pub type_produit: Option<String>,
+    pub unite: Option<String>,
+    pub variete_miel: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = unite_mesure)]
+pub struct UniteMesure {
+    pub code: String,
+    // Unité de référence de la grandeur mesurée (g pour les masses, ml pour les volumes)
+    pub unite_base: String,
+    // Nombre d'unités de base dans une unité
+    pub facteur: f64,
+}
+
+/// Quantité totale produite pour un type de produit (et une variété de miel le cas échéant)
+#[derive(QueryableByName, Serialize)]
+pub struct TotalProduction {
+    #[diesel(sql_type = Varchar)]
+    pub type_produit: String,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub variete_miel: Option<String>,
+    #[diesel(sql_type = Double)]
+    pub quantite: f64,
+    #[diesel(sql_type = Varchar)]
+    pub unite: String,
+}
+
+/// Périmètre sur lequel sont agrégées les statistiques de production
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Perimetre {
+    Ruche,
+    Rucher,
+    Apiculteur,
+}
+
+impl Perimetre {
+    // Condition SQL sélectionnant les ruches du périmètre (ruche aliasée en r, identifiant en $1)
+    pub fn condition(&self) -> &'static str {
+        match self {
+            Perimetre::Ruche => "r.id = $1",
+            Perimetre::Rucher => "r.id_rucher = $1",
+            Perimetre::Apiculteur => "r.id_apiculteur = $1",
+        }
+    }
+}
+
+/// Production d'une année sur le périmètre
+#[derive(QueryableByName, Serialize)]
+pub struct StatistiqueAnnuelle {
+    #[diesel(sql_type = Integer)]
+    pub annee: i32,
+    #[diesel(sql_type = Double)]
+    pub quantite: f64,
+    // Nombre de ruches ayant produit dans l'année
+    #[diesel(sql_type = BigInt)]
+    pub nombre_ruches: i64,
+    #[diesel(sql_type = Double)]
+    pub moyenne_par_ruche: f64,
+    #[diesel(sql_type = Double)]
+    pub mediane_par_ruche: f64,
+    // Rapportée aux cadres de couvain relevés sur les ruches de l'année
+    #[diesel(sql_type = Nullable<Double>)]
+    pub quantite_par_cadre_couvain: Option<f64>,
+    #[diesel(sql_type = Nullable<Double>)]
+    pub quantite_annee_precedente: Option<f64>,
+    // Évolution par rapport à l'année précédente, en pourcentage
+    #[diesel(sql_type = Nullable<Double>)]
+    pub evolution_pourcentage: Option<f64>,
+}
+
+/// Production d'une saison (printemps, ete, automne, hiver) d'une année civile
+#[derive(QueryableByName, Serialize)]
+pub struct StatistiqueSaisonniere {
+    #[diesel(sql_type = Integer)]
+    pub annee: i32,
+    #[diesel(sql_type = Varchar)]
+    pub saison: String,
+    #[diesel(sql_type = Double)]
+    pub quantite: f64,
+}
+
+/// Production annuelle d'une ruche et son rang parmi les ruches du périmètre
+#[derive(QueryableByName, Serialize, Clone)]
+pub struct ProductionRuche {
+    #[diesel(sql_type = Integer)]
+    pub id_ruche: i32,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub nom_ruche: Option<String>,
+    #[diesel(sql_type = Integer)]
+    pub annee: i32,
+    #[diesel(sql_type = Double)]
+    pub quantite: f64,
+    // Moyenne des relevés de l'année, ou dernier relevé connu
+    #[diesel(sql_type = Nullable<Double>)]
+    pub cadres_couvain: Option<f64>,
+    #[diesel(sql_type = Nullable<Double>)]
+    pub quantite_par_cadre_couvain: Option<f64>,
+    #[diesel(sql_type = BigInt)]
+    pub rang: i64,
+    #[serde(skip)]
+    #[diesel(sql_type = BigInt)]
+    pub nombre_ruches: i64,
+}
+
+/// Meilleures et moins bonnes ruches d'une année
+#[derive(Serialize)]
+pub struct ClassementAnnuel {
+    pub annee: i32,
+    pub meilleures: Vec<ProductionRuche>,
+    pub moins_bonnes: Vec<ProductionRuche>,
+}
+
+#[derive(Serialize)]
+pub struct StatistiquesProduction {
+    pub perimetre: Perimetre,
+    pub id: i32,
+    pub par_type: Vec<TotalProduction>,
+    pub par_variete: Vec<TotalProduction>,
+    // Les statistiques suivantes portent sur un seul type de produit, exprimé dans une seule unité
+    pub type_produit: String,
+    pub unite: String,
+    pub par_annee: Vec<StatistiqueAnnuelle>,
+    pub par_saison: Vec<StatistiqueSaisonniere>,
+    pub classement: Vec<ClassementAnnuel>,
 }
\ No newline at end of file
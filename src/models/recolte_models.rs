@@ -0,0 +1,52 @@
+use crate::schema::*;
+use crate::models::hausse_models::PoseHausse;
+use crate::models::lot_models::LotMiel;
+use crate::models::production_models::Production;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+
+/// Session de récolte regroupant les ruches récoltées le même jour
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = recolte)]
+pub struct Recolte {
+    pub id: i32,
+    pub id_apiculteur: Option<i32>,
+    pub date_recolte: NaiveDate,
+    pub type_miel: Option<String>,
+    // Taux d'humidité du miel mesuré au réfractomètre, en pourcentage
+    pub taux_humidite: Option<f64>,
+    pub id_lot: Option<i32>,
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = recolte)]
+pub struct NewRecolte {
+    pub id_apiculteur: Option<i32>,
+    pub date_recolte: NaiveDate,
+    pub type_miel: Option<String>,
+    pub taux_humidite: Option<f64>,
+    // Généré à partir de la date et de la session s'il n'est pas renseigné
+    #[diesel(skip_insertion)]
+    pub numero_lot: Option<String>,
+    #[diesel(skip_insertion)]
+    pub hausses: Vec<HausseRecoltee>,
+}
+
+/// Pesées d'une hausse retirée au cours de la récolte, en grammes
+#[derive(Deserialize)]
+pub struct HausseRecoltee {
+    pub id_materiel: i32,
+    pub poids_plein: i32,
+    pub poids_vide: i32,
+}
+
+/// Session de récolte avec les hausses récoltées, les productions et le lot créés
+#[derive(Serialize)]
+pub struct RecolteDetail {
+    #[serde(flatten)]
+    pub recolte: Recolte,
+    pub lot: Option<LotMiel>,
+    pub hausses: Vec<PoseHausse>,
+    pub productions: Vec<Production>,
+}
\ No newline at end of file
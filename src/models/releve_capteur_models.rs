@@ -0,0 +1,257 @@
+use crate::models::floraison_models::FenetreFloraison;
+use crate::models::poids_models::TypePoids;
+use crate::schema::*;
+use chrono::{Days, NaiveDate, NaiveDateTime, NaiveTime};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Double, Nullable, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::time::Duration;
+
+// Types de mesure relevés par les balances
+pub const MESURE_TEMPERATURE: &str = "temperature";
+pub const MESURE_HUMIDITE: &str = "humidite";
+
+// Série des pesées, servie avec celles des capteurs
+pub const SERIE_POIDS: &str = "poids";
+pub const UNITE_POIDS: &str = "g";
+
+/// Relevé d'un capteur de la ruche autre que le poids
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = releve_capteur)]
+pub struct ReleveCapteur {
+    pub id: i32,
+    pub id_ruche: i32,
+    pub id_appareil: Option<i32>,
+    // Code d'un type de mesure (temperature, humidite, frequence_son, entrees, sorties...)
+    pub type_mesure: String,
+    pub valeur: f64,
+    // Unité du type de mesure au moment du relevé
+    pub unite: String,
+    pub date_releve: NaiveDateTime,
+    // Déjà compté dans les agrégats horaires et journaliers
+    pub agrege: bool,
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = releve_capteur)]
+pub struct NewReleveCapteur {
+    // Renseigné à partir du chemin de la requête
+    #[serde(default)]
+    pub id_ruche: i32,
+    // Renseigné lorsque le relevé est transmis par un appareil
+    #[serde(skip)]
+    pub id_appareil: Option<i32>,
+    pub type_mesure: String,
+    pub valeur: f64,
+    // Maintenant si absente
+    pub date_releve: Option<NaiveDateTime>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = type_mesure)]
+pub struct TypeMesure {
+    pub code: String,
+    pub libelle: String,
+    pub unite: String,
+}
+
+/// Point d'une série
+#[derive(Serialize)]
+pub struct PointSerie {
+    pub date: NaiveDateTime,
+    pub valeur: f64,
+}
+
+/// Série d'un type de mesure, ou des pesées
+#[derive(Serialize)]
+pub struct Serie {
+    pub type_mesure: String,
+    pub unite: String,
+    pub points: Vec<PointSerie>,
+}
+
+/// Séries d'une ruche sur une période, pour les mettre en regard
+#[derive(Serialize)]
+pub struct SeriesRuche {
+    pub id_ruche: i32,
+    pub debut: Option<NaiveDate>,
+    pub fin: Option<NaiveDate>,
+    pub series: Vec<Serie>,
+    // Floraisons attendues autour du rucher durant la période, ou entre la première et la dernière mesure
+    pub floraisons: Vec<FenetreFloraison>,
+}
+
+/// Politique de conservation des mesures, lue au démarrage du serveur
+#[derive(Clone)]
+pub struct RetentionMesures {
+    // Jours durant lesquels toutes les mesures brutes sont conservées
+    pub jours_bruts: u64,
+    // Jours durant lesquels les agrégats horaires sont conservés, les agrégats journaliers l'étant toujours
+    pub jours_horaires: u64,
+    // Intervalle entre deux agrégations des mesures
+    pub intervalle_agregation: Duration,
+}
+
+impl RetentionMesures {
+    /// Lit `MESURES_RETENTION_BRUTE_JOURS` (90 par défaut), `MESURES_RETENTION_HORAIRE_JOURS` (730 par défaut,
+    /// au moins la rétention brute) et `MESURES_INTERVALLE_AGREGATION_SECONDES` (3600 par défaut, non nul)
+    pub fn depuis_env() -> Self {
+        let jours_bruts = env::var("MESURES_RETENTION_BRUTE_JOURS").unwrap_or_else(|_| "90".to_string())
+            .parse::<u64>().expect("MESURES_RETENTION_BRUTE_JOURS doit être un nombre");
+        let jours_horaires = env::var("MESURES_RETENTION_HORAIRE_JOURS").unwrap_or_else(|_| "730".to_string())
+            .parse::<u64>().expect("MESURES_RETENTION_HORAIRE_JOURS doit être un nombre");
+        let intervalle_agregation = env::var("MESURES_INTERVALLE_AGREGATION_SECONDES").unwrap_or_else(|_| "3600".to_string())
+            .parse::<u64>().ok().filter(|secondes| *secondes > 0)
+            .expect("MESURES_INTERVALLE_AGREGATION_SECONDES doit être un nombre strictement positif");
+
+        RetentionMesures {
+            jours_bruts,
+            jours_horaires: jours_horaires.max(jours_bruts),
+            intervalle_agregation: Duration::from_secs(intervalle_agregation),
+        }
+    }
+
+    /// Début du plus ancien jour dont les mesures brutes sont complètes
+    pub fn limite_brute(&self, aujourd_hui: NaiveDate) -> NaiveDateTime {
+        (aujourd_hui - Days::new(self.jours_bruts)).and_time(NaiveTime::MIN)
+    }
+
+    /// Début du plus ancien jour dont les agrégats horaires sont conservés
+    pub fn limite_horaire(&self, aujourd_hui: NaiveDate) -> NaiveDateTime {
+        (aujourd_hui - Days::new(self.jours_horaires)).and_time(NaiveTime::MIN)
+    }
+}
+
+/// Bilan d'un passage de la tâche d'agrégation
+pub struct BilanRetention {
+    pub agregats_horaires: usize,
+    pub agregats_journaliers: usize,
+    pub pesees_elaguees: usize,
+    pub releves_elagues: usize,
+    pub agregats_horaires_supprimes: usize,
+}
+
+// Agrégats horaires et journaliers créés ou complétés par une agrégation
+#[derive(QueryableByName)]
+pub struct NombreAgregats {
+    #[diesel(sql_type = BigInt)]
+    pub agregats_horaires: i64,
+    #[diesel(sql_type = BigInt)]
+    pub agregats_journaliers: i64,
+}
+
+/// Largeur des intervalles d'une série agrégée
+#[derive(Deserialize, Serialize, Clone, Copy, Default)]
+pub enum Intervalle {
+    #[serde(rename = "15m")]
+    QuinzeMinutes,
+    #[serde(rename = "1h")]
+    Heure,
+    #[default]
+    #[serde(rename = "1d")]
+    Jour,
+    #[serde(rename = "1w")]
+    Semaine,
+}
+
+impl Intervalle {
+    /// Durée de l'intervalle, au format des intervalles PostgreSQL
+    pub fn duree_sql(self) -> &'static str {
+        match self {
+            Intervalle::QuinzeMinutes => "15 minutes",
+            Intervalle::Heure => "1 hour",
+            Intervalle::Jour => "1 day",
+            Intervalle::Semaine => "7 days",
+        }
+    }
+}
+
+/// Agrégation des mesures de chaque intervalle
+#[derive(Deserialize, Serialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Agregat {
+    #[default]
+    Avg,
+    Min,
+    Max,
+    // Dernière mesure de l'intervalle
+    Last,
+    // Variation de la dernière mesure depuis celle de l'intervalle précédent
+    Delta,
+}
+
+/// Borne d'une période : un instant, ou un jour entier
+#[derive(Deserialize, Serialize, Clone, Copy)]
+#[serde(untagged)]
+pub enum Borne {
+    Instant(NaiveDateTime),
+    Jour(NaiveDate),
+}
+
+impl Borne {
+    /// Premier instant compris dans la borne
+    pub fn debut(self) -> NaiveDateTime {
+        match self {
+            Borne::Instant(instant) => instant,
+            Borne::Jour(jour) => jour.and_time(NaiveTime::MIN),
+        }
+    }
+
+    /// Jour de la borne
+    pub fn jour(self) -> NaiveDate {
+        match self {
+            Borne::Instant(instant) => instant.date(),
+            Borne::Jour(jour) => jour,
+        }
+    }
+
+    /// Premier instant suivant la borne : un instant de fin est exclu, un jour de fin inclus. Absent pour le
+    /// dernier jour du calendrier
+    pub fn fin_exclue(self) -> Option<NaiveDateTime> {
+        match self {
+            Borne::Instant(instant) => Some(instant),
+            Borne::Jour(jour) => jour.succ_opt().map(|lendemain| lendemain.and_time(NaiveTime::MIN)),
+        }
+    }
+}
+
+/// Paramètres d'une série agrégée
+#[derive(Deserialize)]
+pub struct ParametresSerie {
+    #[serde(default)]
+    pub bucket: Intervalle,
+    #[serde(default)]
+    pub agg: Agregat,
+    pub debut: Option<Borne>,
+    pub fin: Option<Borne>,
+    // Pesées brutes ou nettes, pour la série `poids`
+    #[serde(default)]
+    pub poids: TypePoids,
+}
+
+/// Agrégat des mesures d'un intervalle, daté de son début
+#[derive(QueryableByName, Serialize)]
+pub struct PointAgrege {
+    #[diesel(sql_type = Timestamp)]
+    pub date: NaiveDateTime,
+    #[diesel(sql_type = Nullable<Double>)]
+    pub valeur: Option<f64>,
+    #[diesel(sql_type = BigInt)]
+    pub nombre_mesures: i64,
+}
+
+/// Série d'un type de mesure, ou des pesées, agrégée par intervalle
+#[derive(Serialize)]
+pub struct SerieAgregee {
+    pub id_ruche: i32,
+    pub type_mesure: String,
+    pub unite: String,
+    pub bucket: Intervalle,
+    pub agg: Agregat,
+    pub debut: Option<Borne>,
+    pub fin: Option<Borne>,
+    pub points: Vec<PointAgrege>,
+    // Floraisons attendues autour du rucher durant la période, ou entre le premier et le dernier point
+    pub floraisons: Vec<FenetreFloraison>,
+}
\ No newline at end of file
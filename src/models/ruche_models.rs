@@ -1,17 +1,21 @@
 // src/models.rs
 use crate::schema::*;
+use crate::models::configuration_ruche_models::{ConfigurationRuche, NewConfigurationRuche};
+use crate::models::sante_models::SanteRuche;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
-use chrono::NaiveDate;
 
-#[derive(Queryable, Selectable, Serialize, Deserialize)]
-#[diesel(table_name = ruche)]
+/// Ruche exposée par l'API, les compteurs de cadres provenant du dernier relevé
+#[derive(Serialize, Deserialize)]
 pub struct Ruche {
     pub id: i32,
     pub id_apiculteur: Option<i32>,
+    pub id_rucher: Option<i32>,
     pub photo_ruche: Option<String>,
     pub numero_ruche: Option<i32>,
     pub nom_ruche: Option<String>,
+    // Année de naissance de la reine
+    pub annee_reine: Option<i32>,
     pub nombre_cadres_corp: Option<i32>,
     pub nombre_hausses: Option<i32>,
     pub nombre_cadres_hausse: Option<i32>,
@@ -20,31 +24,97 @@ pub struct Ruche {
     pub nombre_cadre_libre: Option<i32>,
 }
 
-#[derive(Insertable, AsChangeset, Deserialize)]
+impl Ruche {
+    /// Assemble une ruche à partir de sa ligne en base et de son dernier relevé de cadres
+    pub fn from_row(row: RucheRow, configuration: Option<&ConfigurationRuche>) -> Self {
+        Ruche {
+            id: row.id,
+            id_apiculteur: row.id_apiculteur,
+            id_rucher: row.id_rucher,
+            photo_ruche: row.photo_ruche,
+            numero_ruche: row.numero_ruche,
+            nom_ruche: row.nom_ruche,
+            annee_reine: row.annee_reine,
+            nombre_cadres_corp: configuration.and_then(|c| c.nombre_cadres_corp),
+            nombre_hausses: configuration.and_then(|c| c.nombre_hausses),
+            nombre_cadres_hausse: row.nombre_cadres_hausse,
+            nombre_cadre_couvain: configuration.and_then(|c| c.nombre_cadre_couvain),
+            nombre_cadre_nourriture: configuration.and_then(|c| c.nombre_cadre_nourriture),
+            nombre_cadre_libre: configuration.and_then(|c| c.nombre_cadre_libre),
+        }
+    }
+}
+
+/// Ruche accompagnée de son score de santé
+#[derive(Serialize)]
+pub struct RucheAvecSante {
+    #[serde(flatten)]
+    pub ruche: Ruche,
+    pub sante: SanteRuche,
+}
+
+/// Ligne de la table ruche, sans les compteurs historisés dans configuration_ruche
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = ruche)]
+pub struct RucheRow {
+    pub id: i32,
+    pub id_apiculteur: Option<i32>,
+    pub id_rucher: Option<i32>,
+    pub photo_ruche: Option<String>,
+    pub numero_ruche: Option<i32>,
+    pub nom_ruche: Option<String>,
+    pub annee_reine: Option<i32>,
+    pub nombre_cadres_hausse: Option<i32>,
+}
+
+#[derive(Insertable, Deserialize)]
 #[diesel(table_name = ruche)]
 #[diesel(treat_none_as_null = true)]
 pub struct NewRuche {
     pub id_apiculteur: Option<i32>,
+    pub id_rucher: Option<i32>,
     pub photo_ruche: Option<String>,
     pub numero_ruche: Option<i32>,
     pub nom_ruche: Option<String>,
+    pub annee_reine: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_cadres_corp: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_hausses: Option<i32>,
     pub nombre_cadres_hausse: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_cadre_couvain: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_cadre_nourriture: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_cadre_libre: Option<i32>,
 }
 
-#[derive(AsChangeset, Deserialize, Serialize)]
-#[diesel(table_name = ruche)]
-#[diesel(treat_none_as_null = true)]
+impl NewRuche {
+    /// Relevé de cadres initial de la ruche
+    pub fn configuration(&self, ruche_id: i32) -> NewConfigurationRuche {
+        NewConfigurationRuche {
+            id_ruche: ruche_id,
+            nombre_cadres_corp: self.nombre_cadres_corp,
+            nombre_hausses: self.nombre_hausses,
+            nombre_cadre_couvain: self.nombre_cadre_couvain,
+            nombre_cadre_nourriture: self.nombre_cadre_nourriture,
+            nombre_cadre_libre: self.nombre_cadre_libre,
+            date_releve: None,
+        }
+    }
+}
+
+// Les compteurs de cadres ne sont pas mis à jour sur la ruche mais historisés
+#[derive(Deserialize, Serialize)]
 pub struct UpdateRuche {
     pub id: i32,
     pub id_apiculteur: Option<i32>,
+    pub id_rucher: Option<i32>,
     pub photo_ruche: Option<String>,
     pub numero_ruche: Option<i32>,
     pub nom_ruche: Option<String>,
+    pub annee_reine: Option<i32>,
     pub nombre_cadres_corp: Option<i32>,
     pub nombre_hausses: Option<i32>,
     pub nombre_cadres_hausse: Option<i32>,
@@ -52,19 +122,42 @@ pub struct UpdateRuche {
     pub nombre_cadre_nourriture: Option<i32>,
     pub nombre_cadre_libre: Option<i32>,
 }
+
+impl UpdateRuche {
+    /// Relevé de cadres correspondant à la mise à jour
+    pub fn configuration(&self, ruche_id: i32) -> NewConfigurationRuche {
+        NewConfigurationRuche {
+            id_ruche: ruche_id,
+            nombre_cadres_corp: self.nombre_cadres_corp,
+            nombre_hausses: self.nombre_hausses,
+            nombre_cadre_couvain: self.nombre_cadre_couvain,
+            nombre_cadre_nourriture: self.nombre_cadre_nourriture,
+            nombre_cadre_libre: self.nombre_cadre_libre,
+            date_releve: None,
+        }
+    }
+}
+
 #[derive(Insertable, Deserialize)]
 #[diesel(table_name = ruche)]
 pub struct NewRucheWithId {
     pub id: i32,
     pub id_apiculteur: Option<i32>,
+    pub id_rucher: Option<i32>,
     pub photo_ruche: Option<String>,
     pub numero_ruche: Option<i32>,
     pub nom_ruche: Option<String>,
+    pub annee_reine: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_cadres_corp: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_hausses: Option<i32>,
     pub nombre_cadres_hausse: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_cadre_couvain: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_cadre_nourriture: Option<i32>,
+    #[diesel(skip_insertion)]
     pub nombre_cadre_libre: Option<i32>,
 }
 
@@ -73,13 +166,10 @@ pub struct NewRucheWithId {
 pub struct UpdateRucheWithId {
     pub id: i32,
     pub id_apiculteur: Option<i32>,
+    pub id_rucher: Option<i32>,
     pub photo_ruche: Option<String>,
     pub numero_ruche: Option<i32>,
     pub nom_ruche: Option<String>,
-    pub nombre_cadres_corp: Option<i32>,
-    pub nombre_hausses: Option<i32>,
+    pub annee_reine: Option<i32>,
     pub nombre_cadres_hausse: Option<i32>,
-    pub nombre_cadre_couvain: Option<i32>,
-    pub nombre_cadre_nourriture: Option<i32>,
-    pub nombre_cadre_libre: Option<i32>,
 }
\ No newline at end of file
@@ -0,0 +1,36 @@
+use crate::schema::*;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Rucher : emplacement regroupant les ruches d'un apiculteur
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = rucher)]
+pub struct Rucher {
+    pub id: i32,
+    pub id_apiculteur: Option<i32>,
+    pub nom_rucher: Option<String>,
+    pub localisation: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = rucher)]
+pub struct NewRucher {
+    pub id_apiculteur: Option<i32>,
+    pub nom_rucher: Option<String>,
+    pub localisation: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+#[derive(AsChangeset, Deserialize)]
+#[diesel(table_name = rucher)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateRucher {
+    pub id_apiculteur: Option<i32>,
+    pub nom_rucher: Option<String>,
+    pub localisation: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
\ No newline at end of file
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// Note d'un critère de santé, entre 0 (mauvais) et 1 (bon)
+#[derive(Serialize)]
+pub struct CritereSante {
+    pub critere: String,
+    pub note: f64,
+    // Poids du critère dans le score
+    pub poids: f64,
+    pub detail: String,
+}
+
+/// Score de santé d'une ruche, pour prioriser les visites
+#[derive(Serialize)]
+pub struct SanteRuche {
+    pub id_ruche: i32,
+    pub nom_ruche: Option<String>,
+    pub id_rucher: Option<i32>,
+    // Moyenne pondérée des critères disponibles, de 0 à 100, nulle sans aucun critère sur la colonie
+    pub score: Option<f64>,
+    pub criteres: Vec<CritereSante>,
+    // Critères sans données, exclus du score
+    pub donnees_manquantes: Vec<String>,
+}
+
+/// Clé de tri de la liste des scores de santé
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TriSante {
+    #[default]
+    Score,
+    Ruche,
+}
+
+/// Sens du tri
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Ordre {
+    #[default]
+    Asc,
+    Desc,
+}
@@ -0,0 +1,28 @@
+use crate::models::alerte_models::Alerte;
+use crate::models::intervention_models::Intervention;
+use crate::models::materiel_models::Materiel;
+use crate::models::poids_models::Poids;
+use crate::models::production_models::TotalProduction;
+use serde::Serialize;
+
+/// Dernière pesée retenue d'une ruche
+#[derive(Serialize)]
+pub struct DernierPoidsRuche {
+    pub nom_ruche: Option<String>,
+    #[serde(flatten)]
+    pub poids: Poids,
+}
+
+/// Synthèse de la page d'accueil d'un apiculteur
+#[derive(Serialize)]
+pub struct TableauDeBord {
+    pub id_apiculteur: i32,
+    pub nombre_ruches: i64,
+    pub derniers_poids: Vec<DernierPoidsRuche>,
+    // Production depuis le 1er janvier de l'année de la saison, par type de produit
+    pub saison: i32,
+    pub production_saison: Vec<TotalProduction>,
+    pub materiels_a_reparer: Vec<Materiel>,
+    pub interventions_a_venir: Vec<Intervention>,
+    pub alertes_ouvertes: Vec<Alerte>,
+}
@@ -0,0 +1,154 @@
+use crate::schema::*;
+use chrono::{Days, NaiveDate};
+use diesel::prelude::*;
+use diesel::sql_types::{Date, Integer, Nullable, Varchar};
+use serde::{Deserialize, Serialize};
+
+/// Traitement médicamenteux inscrit au registre d'élevage
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = traitement)]
+pub struct Traitement {
+    pub id: i32,
+    pub id_ruche: i32,
+    pub produit: String,
+    // Numéro d'autorisation de mise sur le marché
+    pub numero_amm: Option<String>,
+    pub numero_lot_produit: Option<String>,
+    pub dose: Option<String>,
+    pub date_debut: NaiveDate,
+    pub date_fin: Option<NaiveDate>,
+    // Délai d'attente en jours après la fin du traitement
+    pub delai_attente: i32,
+    pub veterinaire: Option<String>,
+    pub numero_ordonnance: Option<String>,
+}
+
+impl Traitement {
+    /// Dernier jour du délai d'attente, inconnu tant que le traitement est en cours ou s'il sort du calendrier
+    pub fn fin_delai_attente(&self) -> Option<NaiveDate> {
+        let delai = u64::try_from(self.delai_attente).ok()?;
+        self.date_fin?.checked_add_days(Days::new(delai))
+    }
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = traitement)]
+pub struct NewTraitement {
+    // Renseigné à partir du chemin de la requête
+    #[serde(default)]
+    pub id_ruche: i32,
+    pub produit: String,
+    pub numero_amm: Option<String>,
+    pub numero_lot_produit: Option<String>,
+    pub dose: Option<String>,
+    pub date_debut: NaiveDate,
+    pub date_fin: Option<NaiveDate>,
+    // Aucun délai d'attente si absent
+    pub delai_attente: Option<i32>,
+    pub veterinaire: Option<String>,
+    pub numero_ordonnance: Option<String>,
+}
+
+#[derive(AsChangeset, Deserialize)]
+#[diesel(table_name = traitement)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateTraitement {
+    pub produit: String,
+    pub numero_amm: Option<String>,
+    pub numero_lot_produit: Option<String>,
+    pub dose: Option<String>,
+    pub date_debut: NaiveDate,
+    pub date_fin: Option<NaiveDate>,
+    #[serde(default)]
+    pub delai_attente: i32,
+    pub veterinaire: Option<String>,
+    pub numero_ordonnance: Option<String>,
+}
+
+/// Production datée entre le début d'un traitement et la fin de son délai d'attente
+#[derive(QueryableByName, Serialize)]
+pub struct ProductionEnDelaiAttente {
+    #[diesel(sql_type = Integer)]
+    pub id_production: i32,
+    #[diesel(sql_type = Integer)]
+    pub id_ruche: i32,
+    #[diesel(sql_type = Date)]
+    pub date_production: NaiveDate,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub quantite_production: Option<i32>,
+    #[diesel(sql_type = Varchar)]
+    pub unite: String,
+    #[diesel(sql_type = Varchar)]
+    pub type_produit: String,
+    #[diesel(sql_type = Integer)]
+    pub id_traitement: i32,
+    #[diesel(sql_type = Varchar)]
+    pub produit: String,
+    #[diesel(sql_type = Date)]
+    pub date_debut: NaiveDate,
+    #[diesel(sql_type = Nullable<Date>)]
+    pub date_fin: Option<NaiveDate>,
+    #[diesel(sql_type = Nullable<Date>)]
+    pub fin_delai_attente: Option<NaiveDate>,
+}
+
+/// Ligne du registre : traitement et ruche concernée
+#[derive(Serialize)]
+pub struct LigneRegistre {
+    #[serde(flatten)]
+    pub traitement: Traitement,
+    pub numero_ruche: Option<i32>,
+    pub nom_ruche: Option<String>,
+    pub nom_rucher: Option<String>,
+    pub fin_delai_attente: Option<NaiveDate>,
+}
+
+/// Registre des traitements d'une année
+#[derive(Serialize)]
+pub struct RegistreTraitements {
+    pub annee: i32,
+    pub id_apiculteur: Option<i32>,
+    pub traitements: Vec<LigneRegistre>,
+    pub productions_en_delai_attente: Vec<ProductionEnDelaiAttente>,
+}
+
+impl RegistreTraitements {
+    /// Export du registre au format CSV (séparateur point-virgule)
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "Rucher;Ruche;Numéro de ruche;Produit;Numéro AMM;Numéro de lot;Dose;Date de début;Date de fin;\
+             Délai d'attente (jours);Fin du délai d'attente;Vétérinaire prescripteur;Numéro d'ordonnance\n",
+        );
+        for ligne in &self.traitements {
+            let t = &ligne.traitement;
+            let champs = [
+                ligne.nom_rucher.clone().unwrap_or_default(),
+                ligne.nom_ruche.clone().unwrap_or_default(),
+                ligne.numero_ruche.map(|n| n.to_string()).unwrap_or_default(),
+                t.produit.clone(),
+                t.numero_amm.clone().unwrap_or_default(),
+                t.numero_lot_produit.clone().unwrap_or_default(),
+                t.dose.clone().unwrap_or_default(),
+                t.date_debut.to_string(),
+                t.date_fin.map(|d| d.to_string()).unwrap_or_default(),
+                t.delai_attente.to_string(),
+                ligne.fin_delai_attente.map(|d| d.to_string()).unwrap_or_default(),
+                t.veterinaire.clone().unwrap_or_default(),
+                t.numero_ordonnance.clone().unwrap_or_default(),
+            ];
+            let champs: Vec<String> = champs.iter().map(|c| champ_csv(c)).collect();
+            csv.push_str(&champs.join(";"));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+// Entoure de guillemets les valeurs contenant un séparateur, un guillemet ou un retour à la ligne
+fn champ_csv(valeur: &str) -> String {
+    if valeur.contains([';', '"', '\n', '\r']) {
+        format!("\"{}\"", valeur.replace('"', "\"\""))
+    } else {
+        valeur.to_string()
+    }
+}
\ No newline at end of file
@@ -42,7 +42,7 @@ pub struct UpdateUtilisateur {
     pub date_naissance: Option<NaiveDate>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct TokenClaims {
     pub sub: String,
     pub user_id: i32,
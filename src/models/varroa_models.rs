@@ -0,0 +1,86 @@
+use crate::models::alerte_models::Alerte;
+use crate::schema::*;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Comptage de varroas sur une ruche
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = comptage_varroa)]
+pub struct ComptageVarroa {
+    pub id: i32,
+    pub id_ruche: i32,
+    pub date_comptage: NaiveDate,
+    // lavage_alcool, sucre_glace ou chute_naturelle
+    pub methode: String,
+    pub nombre_varroas: i32,
+    // Taille de l'échantillon d'abeilles (prélèvements)
+    pub nombre_abeilles: Option<i32>,
+    // Durée de pose du lange en jours (chute naturelle)
+    pub duree_jours: Option<i32>,
+    pub commentaire: Option<String>,
+    // Varroas pour 100 abeilles, ou varroas tombés par jour pour la chute naturelle
+    pub taux_infestation: Option<f64>,
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = comptage_varroa)]
+pub struct NewComptageVarroa {
+    // Renseigné à partir du chemin de la requête
+    #[serde(default)]
+    pub id_ruche: i32,
+    // Date du jour si absente
+    pub date_comptage: Option<NaiveDate>,
+    pub methode: String,
+    pub nombre_varroas: i32,
+    pub nombre_abeilles: Option<i32>,
+    pub duree_jours: Option<i32>,
+    pub commentaire: Option<String>,
+}
+
+/// Seuil d'alerte d'une méthode de comptage sur une saison (mois_debut à mois_fin inclus)
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = seuil_varroa)]
+pub struct SeuilVarroa {
+    pub id: i32,
+    pub methode: String,
+    pub libelle: Option<String>,
+    pub mois_debut: i32,
+    pub mois_fin: i32,
+    // Dans l'unité du taux d'infestation de la méthode
+    pub seuil: f64,
+}
+
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = seuil_varroa)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewSeuilVarroa {
+    pub methode: String,
+    pub libelle: Option<String>,
+    pub mois_debut: i32,
+    pub mois_fin: i32,
+    pub seuil: f64,
+}
+
+/// Comptage enregistré, avec le seuil de la saison et l'alerte éventuellement levée
+#[derive(Serialize)]
+pub struct ComptageVarroaResultat {
+    #[serde(flatten)]
+    pub comptage: ComptageVarroa,
+    pub seuil: Option<SeuilVarroa>,
+    pub alerte: Option<Alerte>,
+}
+
+/// Comparaison des comptages précédant et suivant un traitement
+#[derive(Serialize)]
+pub struct EfficaciteTraitement {
+    pub id_traitement: i32,
+    pub id_ruche: i32,
+    pub avant: Option<ComptageVarroa>,
+    pub apres: Option<ComptageVarroa>,
+    // Comptages réalisés avec la même méthode ; à défaut, aucune réduction n'est calculée, les taux des
+    // prélèvements et de la chute naturelle n'ayant pas la même unité. Nul sans comptage avant et après
+    pub meme_methode: Option<bool>,
+    // Baisse du taux d'infestation entre les deux comptages de même méthode, en pourcentage
+    pub reduction_pourcentage: Option<f64>,
+}
@@ -1,5 +1,109 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    affectation_materiel (id) {
+        id -> Int4,
+        id_materiel -> Int4,
+        id_ruche_precedente -> Nullable<Int4>,
+        id_ruche -> Nullable<Int4>,
+        date_affectation -> Timestamp,
+    }
+}
+
+diesel::table! {
+    alerte (id) {
+        id -> Int4,
+        id_ruche -> Nullable<Int4>,
+        #[max_length = 30]
+        type_alerte -> Varchar,
+        message -> Text,
+        valeur -> Nullable<Float8>,
+        seuil -> Nullable<Float8>,
+        date_alerte -> Timestamp,
+        date_resolution -> Nullable<Timestamp>,
+        id_appareil -> Nullable<Int4>,
+        id_apiculteur -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    anomalie_poids (id) {
+        id -> Int4,
+        id_poids -> Int4,
+        #[max_length = 20]
+        type_anomalie -> Varchar,
+        detail -> Text,
+        #[max_length = 20]
+        statut -> Varchar,
+        date_detection -> Timestamp,
+        date_decision -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    appareil (id) {
+        id -> Int4,
+        #[max_length = 64]
+        numero_serie -> Varchar,
+        #[max_length = 50]
+        modele -> Varchar,
+        #[max_length = 30]
+        version_firmware -> Nullable<Varchar>,
+        id_ruche -> Nullable<Int4>,
+        id_apiculteur -> Nullable<Int4>,
+        niveau_batterie -> Nullable<Int4>,
+        derniere_connexion -> Nullable<Timestamp>,
+        #[max_length = 255]
+        cle_api -> Nullable<Varchar>,
+        #[max_length = 16]
+        dev_eui -> Nullable<Varchar>,
+        dernier_rssi -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    comptage_varroa (id) {
+        id -> Int4,
+        id_ruche -> Int4,
+        date_comptage -> Date,
+        #[max_length = 20]
+        methode -> Varchar,
+        nombre_varroas -> Int4,
+        nombre_abeilles -> Nullable<Int4>,
+        duree_jours -> Nullable<Int4>,
+        commentaire -> Nullable<Text>,
+        taux_infestation -> Nullable<Float8>,
+    }
+}
+
+diesel::table! {
+    configuration_ruche (id) {
+        id -> Int4,
+        id_ruche -> Int4,
+        nombre_cadres_corp -> Nullable<Int4>,
+        nombre_hausses -> Nullable<Int4>,
+        nombre_cadre_couvain -> Nullable<Int4>,
+        nombre_cadre_nourriture -> Nullable<Int4>,
+        nombre_cadre_libre -> Nullable<Int4>,
+        date_releve -> Timestamp,
+    }
+}
+
+diesel::table! {
+    correction_poids (id) {
+        id -> Int4,
+        id_poids -> Nullable<Int4>,
+        ancienne_ruche -> Nullable<Int4>,
+        nouvelle_ruche -> Nullable<Int4>,
+        ancien_poids -> Nullable<Int4>,
+        nouveau_poids -> Nullable<Int4>,
+        ancien_horodatage -> Nullable<Timestamp>,
+        nouvel_horodatage -> Nullable<Timestamp>,
+        motif -> Nullable<Text>,
+        date_correction -> Timestamp,
+    }
+}
+
 diesel::table! {
     interventions (id) {
         id -> Int4,
@@ -11,6 +115,34 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    lot_miel (id) {
+        id -> Int4,
+        #[max_length = 50]
+        numero_lot -> Varchar,
+        #[max_length = 50]
+        type_miel -> Nullable<Varchar>,
+        date_extraction -> Nullable<Date>,
+        taux_humidite -> Nullable<Float8>,
+        dluo -> Nullable<Date>,
+    }
+}
+
+diesel::table! {
+    lot_pot (id_lot, contenance) {
+        id_lot -> Int4,
+        contenance -> Int4,
+        nombre_pots -> Int4,
+    }
+}
+
+diesel::table! {
+    lot_production (id_lot, id_production) {
+        id_lot -> Int4,
+        id_production -> Int4,
+    }
+}
+
 diesel::table! {
     materiel (id) {
         id -> Int4,
@@ -21,6 +153,67 @@ diesel::table! {
         type_materiel -> Nullable<Varchar>,
         #[max_length = 20]
         etat_materiel -> Nullable<Varchar>,
+        poids_tare -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    mesure_horaire (id_ruche, type_mesure, debut) {
+        id_ruche -> Int4,
+        #[max_length = 30]
+        type_mesure -> Varchar,
+        debut -> Timestamp,
+        nombre_mesures -> Int4,
+        somme -> Float8,
+        minimum -> Float8,
+        maximum -> Float8,
+        premiere -> Float8,
+        derniere -> Float8,
+        instant_premiere -> Nullable<Timestamp>,
+        instant_derniere -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    mesure_journaliere (id_ruche, type_mesure, debut) {
+        id_ruche -> Int4,
+        #[max_length = 30]
+        type_mesure -> Varchar,
+        debut -> Timestamp,
+        nombre_mesures -> Int4,
+        somme -> Float8,
+        minimum -> Float8,
+        maximum -> Float8,
+        premiere -> Float8,
+        derniere -> Float8,
+        instant_premiere -> Nullable<Timestamp>,
+        instant_derniere -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    nourrissement (id) {
+        id -> Int4,
+        id_ruche -> Int4,
+        date_nourrissement -> Date,
+        #[max_length = 20]
+        type_nourriture -> Varchar,
+        quantite -> Int4,
+        commentaire -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    observation_meteo (id) {
+        id -> Int4,
+        id_rucher -> Int4,
+        date_observation -> Date,
+        temperature_min -> Nullable<Float8>,
+        temperature_max -> Nullable<Float8>,
+        precipitations -> Nullable<Float8>,
+        vent -> Nullable<Float8>,
+        #[max_length = 30]
+        source -> Varchar,
     }
 }
 
@@ -30,6 +223,24 @@ diesel::table! {
         id_ruche -> Nullable<Int4>,
         poids_ruche -> Nullable<Int4>,
         date_creation -> Nullable<Date>,
+        horodatage -> Nullable<Timestamp>,
+        agrege -> Bool,
+        exclu -> Bool,
+    }
+}
+
+diesel::table! {
+    pose_hausse (id) {
+        id -> Int4,
+        id_materiel -> Int4,
+        id_ruche -> Int4,
+        date_pose -> Date,
+        date_retrait -> Nullable<Date>,
+        poids_miel_extrait -> Nullable<Int4>,
+        id_production -> Nullable<Int4>,
+        id_recolte -> Nullable<Int4>,
+        poids_plein -> Nullable<Int4>,
+        poids_vide -> Nullable<Int4>,
     }
 }
 
@@ -39,6 +250,51 @@ diesel::table! {
         id_ruche -> Nullable<Int4>,
         quantite_production -> Nullable<Int4>,
         date_creation -> Nullable<Date>,
+        #[max_length = 20]
+        type_produit -> Varchar,
+        #[max_length = 10]
+        unite -> Varchar,
+        #[max_length = 50]
+        variete_miel -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    recolte (id) {
+        id -> Int4,
+        id_apiculteur -> Nullable<Int4>,
+        date_recolte -> Date,
+        #[max_length = 50]
+        type_miel -> Nullable<Varchar>,
+        taux_humidite -> Nullable<Float8>,
+        id_lot -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    releve_capteur (id) {
+        id -> Int4,
+        id_ruche -> Int4,
+        id_appareil -> Nullable<Int4>,
+        #[max_length = 30]
+        type_mesure -> Varchar,
+        valeur -> Float8,
+        #[max_length = 10]
+        unite -> Varchar,
+        date_releve -> Timestamp,
+        agrege -> Bool,
+    }
+}
+
+diesel::table! {
+    ressource_mellifere (id) {
+        id -> Int4,
+        id_rucher -> Int4,
+        #[max_length = 100]
+        espece -> Varchar,
+        debut_floraison -> Date,
+        fin_floraison -> Date,
+        surface -> Nullable<Float8>,
     }
 }
 
@@ -51,12 +307,22 @@ diesel::table! {
         numero_ruche -> Nullable<Int4>,
         #[max_length = 50]
         nom_ruche -> Nullable<Varchar>,
-        nombre_cadres_corp -> Nullable<Int4>,
-        nombre_hausses -> Nullable<Int4>,
         nombre_cadres_hausse -> Nullable<Int4>,
-        nombre_cadre_couvain -> Nullable<Int4>,
-        nombre_cadre_nourriture -> Nullable<Int4>,
-        nombre_cadre_libre -> Nullable<Int4>,
+        id_rucher -> Nullable<Int4>,
+        annee_reine -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    rucher (id) {
+        id -> Int4,
+        id_apiculteur -> Nullable<Int4>,
+        #[max_length = 50]
+        nom_rucher -> Nullable<Varchar>,
+        #[max_length = 255]
+        localisation -> Nullable<Varchar>,
+        latitude -> Nullable<Float8>,
+        longitude -> Nullable<Float8>,
     }
 }
 
@@ -73,6 +339,83 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    seuil_reserve (id) {
+        id -> Int4,
+        #[max_length = 50]
+        libelle -> Nullable<Varchar>,
+        mois_debut -> Int4,
+        mois_fin -> Int4,
+        seuil -> Int4,
+    }
+}
+
+diesel::table! {
+    seuil_varroa (id) {
+        id -> Int4,
+        #[max_length = 20]
+        methode -> Varchar,
+        #[max_length = 50]
+        libelle -> Nullable<Varchar>,
+        mois_debut -> Int4,
+        mois_fin -> Int4,
+        seuil -> Float8,
+    }
+}
+
+diesel::table! {
+    traitement (id) {
+        id -> Int4,
+        id_ruche -> Int4,
+        #[max_length = 100]
+        produit -> Varchar,
+        #[max_length = 50]
+        numero_amm -> Nullable<Varchar>,
+        #[max_length = 50]
+        numero_lot_produit -> Nullable<Varchar>,
+        #[max_length = 100]
+        dose -> Nullable<Varchar>,
+        date_debut -> Date,
+        date_fin -> Nullable<Date>,
+        delai_attente -> Int4,
+        #[max_length = 100]
+        veterinaire -> Nullable<Varchar>,
+        #[max_length = 50]
+        numero_ordonnance -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    type_mesure (code) {
+        #[max_length = 30]
+        code -> Varchar,
+        #[max_length = 50]
+        libelle -> Varchar,
+        #[max_length = 10]
+        unite -> Varchar,
+    }
+}
+
+diesel::table! {
+    type_nourriture (code) {
+        #[max_length = 20]
+        code -> Varchar,
+        #[max_length = 50]
+        libelle -> Varchar,
+        facteur_reserve -> Float8,
+    }
+}
+
+diesel::table! {
+    unite_mesure (code) {
+        #[max_length = 10]
+        code -> Varchar,
+        #[max_length = 10]
+        unite_base -> Varchar,
+        facteur -> Float8,
+    }
+}
+
 diesel::table! {
     utilisateur (id) {
         id -> Int4,
@@ -91,19 +434,76 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(affectation_materiel -> materiel (id_materiel));
+diesel::joinable!(alerte -> appareil (id_appareil));
+diesel::joinable!(alerte -> ruche (id_ruche));
+diesel::joinable!(alerte -> utilisateur (id_apiculteur));
+diesel::joinable!(anomalie_poids -> poids (id_poids));
+diesel::joinable!(appareil -> ruche (id_ruche));
+diesel::joinable!(appareil -> utilisateur (id_apiculteur));
+diesel::joinable!(comptage_varroa -> ruche (id_ruche));
+diesel::joinable!(configuration_ruche -> ruche (id_ruche));
+diesel::joinable!(correction_poids -> poids (id_poids));
 diesel::joinable!(interventions -> ruche (id_ruche));
+diesel::joinable!(lot_pot -> lot_miel (id_lot));
+diesel::joinable!(lot_production -> lot_miel (id_lot));
+diesel::joinable!(lot_production -> production (id_production));
 diesel::joinable!(materiel -> ruche (id_ruche));
+diesel::joinable!(mesure_horaire -> ruche (id_ruche));
+diesel::joinable!(mesure_journaliere -> ruche (id_ruche));
+diesel::joinable!(nourrissement -> ruche (id_ruche));
+diesel::joinable!(nourrissement -> type_nourriture (type_nourriture));
+diesel::joinable!(observation_meteo -> rucher (id_rucher));
 diesel::joinable!(poids -> ruche (id_ruche));
+diesel::joinable!(pose_hausse -> materiel (id_materiel));
+diesel::joinable!(pose_hausse -> production (id_production));
+diesel::joinable!(pose_hausse -> recolte (id_recolte));
+diesel::joinable!(pose_hausse -> ruche (id_ruche));
 diesel::joinable!(production -> ruche (id_ruche));
+diesel::joinable!(production -> unite_mesure (unite));
+diesel::joinable!(recolte -> lot_miel (id_lot));
+diesel::joinable!(recolte -> utilisateur (id_apiculteur));
+diesel::joinable!(releve_capteur -> appareil (id_appareil));
+diesel::joinable!(releve_capteur -> ruche (id_ruche));
+diesel::joinable!(releve_capteur -> type_mesure (type_mesure));
+diesel::joinable!(ressource_mellifere -> rucher (id_rucher));
+diesel::joinable!(ruche -> rucher (id_rucher));
 diesel::joinable!(ruche -> utilisateur (id_apiculteur));
+diesel::joinable!(rucher -> utilisateur (id_apiculteur));
 diesel::joinable!(sessions -> utilisateur (id_utilisateur));
+diesel::joinable!(traitement -> ruche (id_ruche));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    affectation_materiel,
+    alerte,
+    anomalie_poids,
+    appareil,
+    comptage_varroa,
+    configuration_ruche,
+    correction_poids,
     interventions,
+    lot_miel,
+    lot_pot,
+    lot_production,
     materiel,
+    mesure_horaire,
+    mesure_journaliere,
+    nourrissement,
+    observation_meteo,
     poids,
+    pose_hausse,
     production,
+    recolte,
+    releve_capteur,
+    ressource_mellifere,
     ruche,
+    rucher,
     sessions,
+    seuil_reserve,
+    seuil_varroa,
+    traitement,
+    type_mesure,
+    type_nourriture,
+    unite_mesure,
     utilisateur,
 );
\ No newline at end of file
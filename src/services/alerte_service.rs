@@ -0,0 +1,115 @@
+use crate::db::DbConnection;
+use crate::models::alerte_models::{Alerte, NewAlerte};
+use crate::schema::{alerte, ruche};
+use diesel::dsl::now;
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Récupère les alertes, éventuellement limitées à une ruche, à un apiculteur (alertes de ses ruches
+/// et de ses appareils) ou aux alertes ouvertes
+pub fn get_alertes(
+    conn: &mut DbConnection,
+    ruche_id: Option<i32>,
+    apiculteur_id: Option<i32>,
+    ouvertes: bool,
+) -> Result<Vec<Alerte>, Error> {
+    let mut query = alerte::table
+        .order((alerte::date_alerte.desc(), alerte::id.desc()))
+        .into_boxed();
+
+    if let Some(ruche_id) = ruche_id {
+        query = query.filter(alerte::id_ruche.eq(ruche_id));
+    }
+    if let Some(apiculteur_id) = apiculteur_id {
+        let ruches = ruche::table
+            .filter(ruche::id_apiculteur.eq(apiculteur_id))
+            .select(ruche::id.nullable());
+        query = query.filter(alerte::id_apiculteur.eq(apiculteur_id).or(alerte::id_ruche.eq_any(ruches)));
+    }
+    if ouvertes {
+        query = query.filter(alerte::date_resolution.is_null());
+    }
+
+    query.load::<Alerte>(conn)
+}
+
+/// Lève une alerte, ou met à jour l'alerte ouverte du même type sur le même appareil pour une alerte d'appareil,
+/// sur la même ruche sinon. L'alerte d'un appareil suit la ruche à laquelle il est associé
+pub fn lever_alerte(conn: &mut DbConnection, new_alerte: NewAlerte) -> Result<Alerte, Error> {
+    let mut query = alerte::table
+        .filter(alerte::type_alerte.eq(&new_alerte.type_alerte))
+        .filter(alerte::date_resolution.is_null())
+        .select(alerte::id)
+        .into_boxed();
+    query = match new_alerte.id_appareil {
+        Some(appareil_id) => query.filter(alerte::id_appareil.eq(appareil_id)),
+        None => query
+            .filter(alerte::id_appareil.is_null())
+            .filter(alerte::id_ruche.is_not_distinct_from(new_alerte.id_ruche)),
+    };
+    let ouverte = query.first::<i32>(conn).optional()?;
+
+    match ouverte {
+        Some(id) => diesel::update(alerte::table.find(id))
+            .set((
+                alerte::id_ruche.eq(new_alerte.id_ruche),
+                alerte::message.eq(&new_alerte.message),
+                alerte::valeur.eq(new_alerte.valeur),
+                alerte::seuil.eq(new_alerte.seuil),
+            ))
+            .get_result(conn),
+        None => diesel::insert_into(alerte::table)
+            .values(&new_alerte)
+            .get_result(conn),
+    }
+}
+
+/// Rattache les alertes ouvertes d'un appareil à la ruche à laquelle il est désormais associé
+pub fn deplacer_alertes_appareil(conn: &mut DbConnection, appareil_id: i32, ruche_id: Option<i32>) -> Result<usize, Error> {
+    diesel::update(
+        alerte::table
+            .filter(alerte::id_appareil.eq(appareil_id))
+            .filter(alerte::id_ruche.is_distinct_from(ruche_id))
+            .filter(alerte::date_resolution.is_null()),
+    )
+    .set(alerte::id_ruche.eq(ruche_id))
+    .execute(conn)
+}
+
+/// Résout les alertes ouvertes d'un type sur une ruche
+pub fn resoudre_alertes(conn: &mut DbConnection, ruche_id: i32, type_alerte: &str) -> Result<usize, Error> {
+    diesel::update(
+        alerte::table
+            .filter(alerte::id_ruche.eq(ruche_id))
+            .filter(alerte::type_alerte.eq(type_alerte))
+            .filter(alerte::date_resolution.is_null()),
+    )
+    .set(alerte::date_resolution.eq(now))
+    .execute(conn)
+}
+
+/// Résout les alertes ouvertes d'un type sur un appareil
+pub fn resoudre_alertes_appareil(conn: &mut DbConnection, appareil_id: i32, type_alerte: &str) -> Result<usize, Error> {
+    diesel::update(
+        alerte::table
+            .filter(alerte::id_appareil.eq(appareil_id))
+            .filter(alerte::type_alerte.eq(type_alerte))
+            .filter(alerte::date_resolution.is_null()),
+    )
+    .set(alerte::date_resolution.eq(now))
+    .execute(conn)
+}
+
+/// Marque une alerte comme résolue (sans effet si elle l'est déjà)
+pub fn resoudre_alerte(conn: &mut DbConnection, id: i32) -> Result<Alerte, Error> {
+    conn.transaction(|conn| {
+        let existante = alerte::table.find(id).first::<Alerte>(conn)?;
+        if existante.date_resolution.is_some() {
+            return Ok(existante);
+        }
+
+        diesel::update(alerte::table.find(id))
+            .set(alerte::date_resolution.eq(now))
+            .get_result(conn)
+    })
+}
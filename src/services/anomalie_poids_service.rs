@@ -0,0 +1,132 @@
+use crate::db::DbConnection;
+use crate::models::anomalie_poids_models::{AnomaliePoids, AnomaliePesee, STATUT_A_VERIFIER, STATUT_REJETEE};
+use crate::schema::{anomalie_poids, poids};
+use chrono::{NaiveDateTime, Utc};
+use diesel::dsl::exists;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::{BigInt, Integer, Nullable, Text, Timestamp};
+
+// Écart minimal avec la pesée précédente pour qu'une pesée soit un saut, en grammes
+const SAUT_MIN: i32 = 2000;
+
+// Écart maximal entre les pesées encadrant un saut pour qu'il soit considéré annulé, en grammes
+const RETOUR_MAX: i32 = 500;
+
+// Durée maximale entre les pesées encadrant un saut
+const FENETRE_SAUT: &str = "30 minutes";
+
+// Nombre de pesées identiques consécutives à partir duquel la balance est considérée bloquée
+const REPETITIONS_BLOQUE: i64 = 12;
+
+// Détecte les anomalies des pesées d'une ruche à partir de la veille de $2 (toutes si $2 est nul), enregistre
+// les nouvelles et en écarte les pesées des agrégations. Une anomalie déjà connue, même rejetée, est conservée
+const DETECTION_SQL: &str = "
+    WITH pesees AS (
+        SELECT id, horodatage, poids_ruche,
+               LAG(poids_ruche) OVER w AS precedent,
+               LEAD(poids_ruche) OVER w AS suivant,
+               LEAD(horodatage) OVER w - LAG(horodatage) OVER w AS ecart,
+               poids_ruche IS DISTINCT FROM LAG(poids_ruche) OVER w AS changement
+        FROM poids
+        WHERE id_ruche = $1 AND poids_ruche IS NOT NULL AND horodatage IS NOT NULL
+          AND ($2::timestamp IS NULL OR horodatage >= $2 - INTERVAL '1 day')
+        WINDOW w AS (ORDER BY horodatage, id)
+    ),
+    series AS (
+        SELECT *, sum(changement::int4) OVER (ORDER BY horodatage, id) AS serie
+        FROM pesees
+    ),
+    repetitions AS (
+        SELECT *, row_number() OVER (PARTITION BY serie ORDER BY horodatage, id) AS rang,
+               count(*) OVER (PARTITION BY serie) AS longueur
+        FROM series
+    ),
+    anomalies AS (
+        SELECT id, 'negatif' AS type_anomalie, format('Poids négatif : %s g', poids_ruche) AS detail
+        FROM repetitions
+        WHERE poids_ruche < 0
+        UNION ALL
+        SELECT id, 'saut', format('Saut de %s g annulé par la pesée suivante', poids_ruche - precedent)
+        FROM repetitions
+        WHERE abs(poids_ruche - precedent) >= $3 AND abs(suivant - precedent) <= $4 AND ecart <= $5::interval
+        UNION ALL
+        SELECT id, 'bloque', format('Valeur de %s g répétée au moins %s fois de suite', poids_ruche, $6)
+        FROM repetitions
+        WHERE longueur >= $6 AND rang > 1
+    ),
+    nouvelles AS (
+        INSERT INTO anomalie_poids (id_poids, type_anomalie, detail)
+        SELECT id, type_anomalie, detail FROM anomalies
+        ON CONFLICT (id_poids, type_anomalie) DO NOTHING
+        RETURNING *
+    ),
+    exclusions AS (
+        UPDATE poids SET exclu = TRUE WHERE id IN (SELECT id_poids FROM nouvelles)
+    )
+    SELECT * FROM nouvelles ORDER BY id";
+
+/// Détecte les anomalies des pesées d'une ruche depuis la veille d'un instant, ou sur tout l'historique.
+/// Renvoie les anomalies nouvellement détectées, dont les pesées sont écartées des agrégations
+pub fn detecter_anomalies(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    depuis: Option<NaiveDateTime>,
+) -> Result<Vec<AnomaliePoids>, Error> {
+    diesel::sql_query(DETECTION_SQL)
+        .bind::<Integer, _>(ruche_id)
+        .bind::<Nullable<Timestamp>, _>(depuis)
+        .bind::<Integer, _>(SAUT_MIN)
+        .bind::<Integer, _>(RETOUR_MAX)
+        .bind::<Text, _>(FENETRE_SAUT)
+        .bind::<BigInt, _>(REPETITIONS_BLOQUE)
+        .load::<AnomaliePoids>(conn)
+}
+
+/// Récupère les anomalies des pesées d'une ruche, éventuellement d'un statut, des plus récentes aux plus anciennes
+pub fn get_anomalies_by_ruche_id(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    statut: Option<String>,
+) -> Result<Vec<AnomaliePesee>, Error> {
+    let mut query = anomalie_poids::table
+        .inner_join(poids::table)
+        .filter(poids::id_ruche.eq(ruche_id))
+        .order((poids::horodatage.desc(), anomalie_poids::id.desc()))
+        .select((AnomaliePoids::as_select(), poids::poids_ruche, poids::horodatage))
+        .into_boxed();
+
+    if let Some(statut) = statut {
+        query = query.filter(anomalie_poids::statut.eq(statut));
+    }
+
+    Ok(query
+        .load::<(AnomaliePoids, Option<i32>, Option<NaiveDateTime>)>(conn)?
+        .into_iter()
+        .map(|(anomalie, poids_ruche, horodatage)| AnomaliePesee { anomalie, poids_ruche, horodatage })
+        .collect())
+}
+
+/// Confirme ou rejette une anomalie (ou la remet à vérifier). La pesée n'est de nouveau prise en compte dans
+/// les agrégations qu'une fois toutes ses anomalies rejetées
+pub fn decider_anomalie(conn: &mut DbConnection, id: i32, statut: String) -> Result<AnomaliePoids, Error> {
+    let date_decision = (statut != STATUT_A_VERIFIER).then(|| Utc::now().naive_utc());
+
+    conn.transaction(|conn| {
+        let anomalie = diesel::update(anomalie_poids::table.find(id))
+            .set((anomalie_poids::statut.eq(statut), anomalie_poids::date_decision.eq(date_decision)))
+            .get_result::<AnomaliePoids>(conn)?;
+
+        let exclu = diesel::select(exists(
+            anomalie_poids::table
+                .filter(anomalie_poids::id_poids.eq(anomalie.id_poids))
+                .filter(anomalie_poids::statut.ne(STATUT_REJETEE)),
+        ))
+        .get_result::<bool>(conn)?;
+        diesel::update(poids::table.find(anomalie.id_poids))
+            .set(poids::exclu.eq(exclu))
+            .execute(conn)?;
+
+        Ok(anomalie)
+    })
+}
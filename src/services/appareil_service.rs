@@ -0,0 +1,299 @@
+use crate::db::{DbConnection, Pool};
+use crate::models::alerte_models::{Alerte, NewAlerte, ALERTE_APPAREIL_HORS_LIGNE, ALERTE_BATTERIE_FAIBLE};
+use crate::models::appareil_models::{
+    Appareil, NewAppareil, UpdateAppareil, AppareilAvecCle, MesureAppareil, SurveillanceAppareils, EtatAppareil,
+    SanteAppareil,
+};
+use crate::models::poids_models::{Poids, NewPoids};
+use crate::schema::appareil;
+use crate::services::{alerte_service, poids_service, ruche_service};
+use argon2::{
+    password_hash::{PasswordHasher, PasswordVerifier, SaltString},
+    Argon2, PasswordHash,
+};
+use actix_web::{rt, web};
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+use log::{error, info};
+use rand_core::{OsRng, RngCore};
+
+/// Évalue l'état d'un appareil à un instant donné
+fn sante_appareil(appareil: Appareil, surveillance: &SurveillanceAppareils, maintenant: NaiveDateTime) -> SanteAppareil {
+    let sans_mesure = appareil.derniere_connexion.map(|derniere| maintenant - derniere);
+    let etat = match sans_mesure {
+        None => EtatAppareil::JamaisConnecte,
+        Some(duree) if duree > surveillance.delai_hors_ligne => EtatAppareil::HorsLigne,
+        Some(_) => EtatAppareil::EnLigne,
+    };
+
+    SanteAppareil {
+        etat,
+        batterie_faible: appareil.niveau_batterie.is_some_and(|niveau| niveau < surveillance.seuil_batterie),
+        minutes_sans_mesure: sans_mesure.map(|duree| duree.num_minutes()),
+        appareil,
+    }
+}
+
+/// Récupère tous les appareils, éventuellement ceux d'un apiculteur, avec leur état de santé
+pub fn get_all_appareils(
+    conn: &mut DbConnection,
+    surveillance: &SurveillanceAppareils,
+    apiculteur_id: Option<i32>,
+) -> Result<Vec<SanteAppareil>, Error> {
+    let mut query = appareil::table
+        .order(appareil::id)
+        .into_boxed();
+
+    if let Some(apiculteur_id) = apiculteur_id {
+        query = query.filter(appareil::id_apiculteur.eq(apiculteur_id));
+    }
+
+    let maintenant = Utc::now().naive_utc();
+    Ok(query
+        .load::<Appareil>(conn)?
+        .into_iter()
+        .map(|appareil| sante_appareil(appareil, surveillance, maintenant))
+        .collect())
+}
+
+/// Récupère un appareil d'un apiculteur par son ID
+pub fn get_appareil_by_id(conn: &mut DbConnection, id: i32, apiculteur_id: i32) -> Result<Appareil, Error> {
+    appareil::table
+        .find(id)
+        .filter(appareil::id_apiculteur.eq(apiculteur_id))
+        .first::<Appareil>(conn)
+}
+
+/// Enregistre un appareil d'un apiculteur, associé le cas échéant à l'une de ses ruches, et lui attribue une clé d'API
+pub fn create_appareil(conn: &mut DbConnection, mut new_appareil: NewAppareil, apiculteur_id: i32) -> Result<AppareilAvecCle, Error> {
+    new_appareil.dev_eui = new_appareil.dev_eui.map(|dev_eui| dev_eui.to_uppercase());
+    new_appareil.id_apiculteur = Some(apiculteur_id);
+
+    conn.transaction(|conn| {
+        if let Some(ruche_id) = new_appareil.id_ruche {
+            ruche_service::verifier_proprietaire(conn, ruche_id, apiculteur_id)?;
+        }
+        let appareil = diesel::insert_into(appareil::table)
+            .values(&new_appareil)
+            .get_result::<Appareil>(conn)?;
+
+        regenerer_cle(conn, appareil.id, apiculteur_id)
+    })
+}
+
+/// Met à jour un appareil d'un apiculteur (l'associer à une autre de ses ruches notamment)
+pub fn update_appareil(
+    conn: &mut DbConnection,
+    id: i32,
+    mut updated_appareil: UpdateAppareil,
+    apiculteur_id: i32,
+) -> Result<Appareil, Error> {
+    updated_appareil.dev_eui = updated_appareil.dev_eui.map(|dev_eui| dev_eui.to_uppercase());
+    updated_appareil.id_apiculteur = Some(apiculteur_id);
+
+    conn.transaction(|conn| {
+        if let Some(ruche_id) = updated_appareil.id_ruche {
+            ruche_service::verifier_proprietaire(conn, ruche_id, apiculteur_id)?;
+        }
+        let appareil = diesel::update(appareil::table.find(id).filter(appareil::id_apiculteur.eq(apiculteur_id)))
+            .set(&updated_appareil)
+            .get_result::<Appareil>(conn)?;
+        // Un appareil associé à une autre ruche y emporte ses alertes ouvertes
+        alerte_service::deplacer_alertes_appareil(conn, appareil.id, appareil.id_ruche)?;
+        Ok(appareil)
+    })
+}
+
+/// Supprime un appareil d'un apiculteur
+pub fn delete_appareil(conn: &mut DbConnection, id: i32, apiculteur_id: i32) -> Result<usize, Error> {
+    diesel::delete(appareil::table.find(id).filter(appareil::id_apiculteur.eq(apiculteur_id))).execute(conn)
+}
+
+/// Attribue une nouvelle clé d'API à un appareil d'un apiculteur, l'ancienne cessant d'être valide.
+/// La clé est de la forme `<id>.<secret>` et seule son empreinte est conservée
+pub fn regenerer_cle(conn: &mut DbConnection, id: i32, apiculteur_id: i32) -> Result<AppareilAvecCle, Error> {
+    let mut octets = [0u8; 32];
+    OsRng.fill_bytes(&mut octets);
+    let secret: String = octets.iter().map(|o| format!("{:02x}", o)).collect();
+
+    let salt = SaltString::generate(&mut OsRng);
+    let empreinte = match Argon2::default().hash_password(secret.as_bytes(), &salt) {
+        Ok(hash) => hash.to_string(),
+        Err(e) => {
+            error!("Erreur lors du hachage de la clé d'API : {}", e);
+            return Err(Error::RollbackTransaction);
+        }
+    };
+
+    let appareil = diesel::update(appareil::table.find(id).filter(appareil::id_apiculteur.eq(apiculteur_id)))
+        .set(appareil::cle_api.eq(empreinte))
+        .get_result::<Appareil>(conn)?;
+
+    Ok(AppareilAvecCle {
+        cle_api: format!("{}.{}", appareil.id, secret),
+        appareil,
+    })
+}
+
+/// Retrouve l'appareil correspondant à une clé d'API (`NotFound` si la clé est invalide)
+pub fn authentifier_appareil(conn: &mut DbConnection, cle: &str) -> Result<Appareil, Error> {
+    let (id, secret) = cle.split_once('.').ok_or(Error::NotFound)?;
+    let id = id.parse::<i32>().map_err(|_| Error::NotFound)?;
+
+    let appareil = appareil::table.find(id).first::<Appareil>(conn)?;
+    let empreinte = appareil.cle_api.as_deref().ok_or(Error::NotFound)?;
+    let empreinte = PasswordHash::new(empreinte).map_err(|e| {
+        error!("Erreur lors de l'analyse de l'empreinte de la clé d'API: {}", e);
+        Error::NotFound
+    })?;
+
+    match Argon2::default().verify_password(secret.as_bytes(), &empreinte) {
+        Ok(()) => Ok(appareil),
+        Err(_) => Err(Error::NotFound),
+    }
+}
+
+/// Met à jour l'état d'un appareil qui vient de transmettre (les valeurs absentes sont conservées, tout comme
+/// une dernière connexion plus récente en cas de retransmission) et résout son alerte hors ligne
+pub fn marquer_connexion(
+    conn: &mut DbConnection,
+    appareil: &Appareil,
+    date_connexion: NaiveDateTime,
+    niveau_batterie: Option<i32>,
+    version_firmware: Option<String>,
+    rssi: Option<i32>,
+) -> Result<(), Error> {
+    diesel::update(appareil::table.find(appareil.id))
+        .set((
+            appareil::derniere_connexion.eq(appareil.derniere_connexion.max(Some(date_connexion))),
+            appareil::niveau_batterie.eq(niveau_batterie.or(appareil.niveau_batterie)),
+            appareil::version_firmware.eq(version_firmware.or(appareil.version_firmware.clone())),
+            appareil::dernier_rssi.eq(rssi.or(appareil.dernier_rssi)),
+        ))
+        .execute(conn)?;
+    alerte_service::resoudre_alertes_appareil(conn, appareil.id, ALERTE_APPAREIL_HORS_LIGNE)?;
+    Ok(())
+}
+
+/// Enregistre la mesure d'un appareil authentifié par sa clé d'API comme poids de la ruche associée.
+/// L'état de l'appareil (dernière connexion, batterie, firmware) est mis à jour et son alerte hors ligne
+/// résolue même s'il n'est associé à aucune ruche, auquel cas aucun poids n'est enregistré
+pub fn enregistrer_mesure(conn: &mut DbConnection, cle: &str, mesure: MesureAppareil) -> Result<Option<Poids>, Error> {
+    let appareil = authentifier_appareil(conn, cle)?;
+
+    conn.transaction(|conn| {
+        marquer_connexion(
+            conn,
+            &appareil,
+            Utc::now().naive_utc(),
+            mesure.niveau_batterie,
+            mesure.version_firmware,
+            None,
+        )?;
+
+        match appareil.id_ruche {
+            Some(ruche_id) => poids_service::create_poids(conn, NewPoids {
+                id_ruche: Some(ruche_id),
+                poids_ruche: Some(mesure.poids),
+                date_creation: mesure.date_mesure,
+                // Une mesure sans date est horodatée à sa réception
+                horodatage: mesure.date_mesure.is_none().then(|| Utc::now().naive_utc()),
+            })
+            .map(Some),
+            None => Ok(None),
+        }
+    })
+}
+
+/// Lève ou résout l'alerte d'un type sur un appareil selon que la condition est remplie
+fn maj_alerte_appareil(
+    conn: &mut DbConnection,
+    sante: &SanteAppareil,
+    type_alerte: &str,
+    condition: bool,
+    message: impl FnOnce() -> String,
+    valeur: Option<f64>,
+    seuil: f64,
+) -> Result<Option<Alerte>, Error> {
+    if !condition {
+        alerte_service::resoudre_alertes_appareil(conn, sante.appareil.id, type_alerte)?;
+        return Ok(None);
+    }
+
+    alerte_service::lever_alerte(conn, NewAlerte {
+        id_ruche: sante.appareil.id_ruche,
+        type_alerte: type_alerte.to_string(),
+        message: message(),
+        valeur,
+        seuil: Some(seuil),
+        id_appareil: Some(sante.appareil.id),
+        id_apiculteur: sante.appareil.id_apiculteur,
+    })
+    .map(Some)
+}
+
+/// Vérifie tous les appareils : lève une alerte pour l'apiculteur propriétaire de chaque appareil resté
+/// sans mesure au-delà du délai ou dont la batterie est faible, et résout celles qui ne sont plus justifiées.
+/// Un appareil qui n'a jamais envoyé de mesure n'est pas considéré hors ligne
+pub fn verifier_appareils(conn: &mut DbConnection, surveillance: &SurveillanceAppareils) -> Result<Vec<Alerte>, Error> {
+    let appareils = get_all_appareils(conn, surveillance, None)?;
+
+    conn.transaction(|conn| {
+        let mut alertes = Vec::new();
+        for sante in &appareils {
+            let hors_ligne = maj_alerte_appareil(
+                conn,
+                sante,
+                ALERTE_APPAREIL_HORS_LIGNE,
+                sante.etat == EtatAppareil::HorsLigne,
+                || format!(
+                    "Appareil {} sans mesure depuis {} minutes",
+                    sante.appareil.numero_serie,
+                    sante.minutes_sans_mesure.unwrap_or_default()
+                ),
+                sante.minutes_sans_mesure.map(|minutes| minutes as f64),
+                surveillance.delai_hors_ligne.num_minutes() as f64,
+            )?;
+            let batterie_faible = maj_alerte_appareil(
+                conn,
+                sante,
+                ALERTE_BATTERIE_FAIBLE,
+                sante.batterie_faible,
+                || format!(
+                    "Batterie de l'appareil {} à {} %",
+                    sante.appareil.numero_serie,
+                    sante.appareil.niveau_batterie.unwrap_or_default()
+                ),
+                sante.appareil.niveau_batterie.map(|niveau| niveau as f64),
+                surveillance.seuil_batterie as f64,
+            )?;
+            alertes.extend(hors_ligne.into_iter().chain(batterie_faible));
+        }
+        Ok(alertes)
+    })
+}
+
+/// Tâche de fond vérifiant périodiquement les appareils, lancée au démarrage du serveur
+pub async fn surveiller_appareils(pool: Pool, surveillance: SurveillanceAppareils) {
+    let mut intervalle = rt::time::interval(surveillance.intervalle_verification);
+    loop {
+        intervalle.tick().await;
+
+        let pool = pool.clone();
+        let surveillance = surveillance.clone();
+        let result = web::block(move || {
+            let mut conn = pool.get().map_err(|e| e.to_string())?;
+            verifier_appareils(&mut conn, &surveillance).map_err(|e| e.to_string())
+        }).await;
+
+        match result {
+            Ok(Ok(alertes)) if !alertes.is_empty() => {
+                info!("Surveillance des appareils : {} alerte(s) ouverte(s)", alertes.len())
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => error!("Erreur lors de la vérification des appareils: {}", e),
+            Err(e) => error!("Erreur lors de l'exécution de la vérification des appareils: {}", e),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,166 @@
+use crate::db::DbConnection;
+use crate::models::chronologie_models::{
+    ChronologieRuche, EntreeChronologie, EvenementRuche, MouvementMateriel, ReferenceEvenement, TotalEvenements,
+    EVENEMENT_AFFECTATION_MATERIEL, EVENEMENT_CONFIGURATION, EVENEMENT_INTERVENTION, EVENEMENT_POIDS,
+    EVENEMENT_POSE_HAUSSE, EVENEMENT_PRODUCTION, EVENEMENT_RETRAIT_HAUSSE, EVENEMENT_RETRAIT_MATERIEL,
+};
+use crate::models::configuration_ruche_models::ConfigurationRuche;
+use crate::models::hausse_models::PoseHausse;
+use crate::models::intervention_models::Intervention;
+use crate::models::materiel_models::{AffectationMateriel, Materiel};
+use crate::models::poids_models::Poids;
+use crate::models::production_models::Production;
+use crate::schema::{affectation_materiel, configuration_ruche, interventions, materiel, poids, pose_hausse, production, ruche};
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::{Array, BigInt, Integer, Text};
+use std::collections::HashMap;
+
+// Événements de la ruche $1 parmi les types $2, les dates sans heure étant placées à minuit. Les hausses, dont
+// les poses et retraits sont suivis, sont écartées des affectations de matériel
+const EVENEMENTS_CTE: &str = "
+    WITH evenements AS (
+        SELECT 'intervention' AS type_evenement, i.id, i.date_intervention::timestamp AS date_evenement
+        FROM interventions i
+        WHERE i.id_ruche = $1 AND i.date_intervention IS NOT NULL
+        UNION ALL
+        SELECT 'poids', p.id, COALESCE(p.horodatage, p.date_creation::timestamp)
+        FROM poids p
+        WHERE p.id_ruche = $1 AND COALESCE(p.horodatage, p.date_creation::timestamp) IS NOT NULL
+        UNION ALL
+        SELECT 'production', p.id, p.date_creation::timestamp
+        FROM production p
+        WHERE p.id_ruche = $1 AND p.date_creation IS NOT NULL
+        UNION ALL
+        SELECT 'pose_hausse', h.id, h.date_pose::timestamp
+        FROM pose_hausse h
+        WHERE h.id_ruche = $1
+        UNION ALL
+        SELECT 'retrait_hausse', h.id, h.date_retrait::timestamp
+        FROM pose_hausse h
+        WHERE h.id_ruche = $1 AND h.date_retrait IS NOT NULL
+        UNION ALL
+        SELECT 'configuration', c.id, c.date_releve
+        FROM configuration_ruche c
+        WHERE c.id_ruche = $1
+        UNION ALL
+        SELECT 'affectation_materiel', a.id, a.date_affectation
+        FROM affectation_materiel a
+        JOIN materiel m ON m.id = a.id_materiel
+        WHERE a.id_ruche = $1 AND m.type_materiel IS DISTINCT FROM 'hausse'
+        UNION ALL
+        SELECT 'retrait_materiel', a.id, a.date_affectation
+        FROM affectation_materiel a
+        JOIN materiel m ON m.id = a.id_materiel
+        WHERE a.id_ruche_precedente = $1 AND m.type_materiel IS DISTINCT FROM 'hausse'
+    )";
+
+const PAGE_SQL: &str = "
+    SELECT type_evenement, id, date_evenement
+    FROM evenements
+    WHERE type_evenement = ANY($2)
+    ORDER BY date_evenement DESC, type_evenement, id DESC
+    LIMIT $3 OFFSET $4";
+
+const TOTAL_SQL: &str = "
+    SELECT COUNT(*) AS total
+    FROM evenements
+    WHERE type_evenement = ANY($2)";
+
+// Identifiants des références d'un type
+fn ids_du_type(references: &[ReferenceEvenement], types: &[&str]) -> Vec<i32> {
+    references
+        .iter()
+        .filter(|r| types.contains(&r.type_evenement.as_str()))
+        .map(|r| r.id)
+        .collect()
+}
+
+/// Récupère une page de la chronologie d'une ruche : interventions, pesées, productions, poses et retraits de
+/// hausses, relevés de cadres et affectations du reste du matériel, du plus récent au plus ancien
+pub fn get_chronologie(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    types: Vec<String>,
+    page: i64,
+    par_page: i64,
+) -> Result<ChronologieRuche, Error> {
+    // Une ruche inconnue est signalée plutôt que de renvoyer une chronologie vide
+    ruche::table.find(ruche_id).select(ruche::id).first::<i32>(conn)?;
+
+    let references = diesel::sql_query(EVENEMENTS_CTE.to_string() + PAGE_SQL)
+        .bind::<Integer, _>(ruche_id)
+        .bind::<Array<Text>, _>(&types)
+        .bind::<BigInt, _>(par_page)
+        .bind::<BigInt, _>((page - 1) * par_page)
+        .load::<ReferenceEvenement>(conn)?;
+    let total = diesel::sql_query(EVENEMENTS_CTE.to_string() + TOTAL_SQL)
+        .bind::<Integer, _>(ruche_id)
+        .bind::<Array<Text>, _>(&types)
+        .get_result::<TotalEvenements>(conn)?
+        .total;
+
+    // Contenu des événements de la page, une requête par type
+    let mut interventions: HashMap<i32, Intervention> = interventions::table
+        .filter(interventions::id.eq_any(ids_du_type(&references, &[EVENEMENT_INTERVENTION])))
+        .load::<Intervention>(conn)?
+        .into_iter()
+        .map(|i| (i.id, i))
+        .collect();
+    let mut pesees: HashMap<i32, Poids> = poids::table
+        .filter(poids::id.eq_any(ids_du_type(&references, &[EVENEMENT_POIDS])))
+        .load::<Poids>(conn)?
+        .into_iter()
+        .map(|p| (p.id, p))
+        .collect();
+    let mut productions: HashMap<i32, Production> = production::table
+        .filter(production::id.eq_any(ids_du_type(&references, &[EVENEMENT_PRODUCTION])))
+        .load::<Production>(conn)?
+        .into_iter()
+        .map(|p| (p.id, p))
+        .collect();
+    let poses: HashMap<i32, PoseHausse> = pose_hausse::table
+        .filter(pose_hausse::id.eq_any(ids_du_type(&references, &[EVENEMENT_POSE_HAUSSE, EVENEMENT_RETRAIT_HAUSSE])))
+        .load::<PoseHausse>(conn)?
+        .into_iter()
+        .map(|h| (h.id, h))
+        .collect();
+    let mut configurations: HashMap<i32, ConfigurationRuche> = configuration_ruche::table
+        .filter(configuration_ruche::id.eq_any(ids_du_type(&references, &[EVENEMENT_CONFIGURATION])))
+        .load::<ConfigurationRuche>(conn)?
+        .into_iter()
+        .map(|c| (c.id, c))
+        .collect();
+    let mut mouvements: HashMap<i32, MouvementMateriel> = affectation_materiel::table
+        .inner_join(materiel::table)
+        .filter(affectation_materiel::id.eq_any(ids_du_type(
+            &references,
+            &[EVENEMENT_AFFECTATION_MATERIEL, EVENEMENT_RETRAIT_MATERIEL],
+        )))
+        .select((AffectationMateriel::as_select(), Materiel::as_select()))
+        .load::<(AffectationMateriel, Materiel)>(conn)?
+        .into_iter()
+        .map(|(affectation, materiel)| (affectation.id, MouvementMateriel { affectation, materiel }))
+        .collect();
+
+    // Un événement supprimé entre les deux requêtes est ignoré
+    let entrees = references
+        .into_iter()
+        .filter_map(|reference| {
+            let evenement = match reference.type_evenement.as_str() {
+                EVENEMENT_INTERVENTION => EvenementRuche::Intervention(interventions.remove(&reference.id)?),
+                EVENEMENT_POIDS => EvenementRuche::Poids(pesees.remove(&reference.id)?),
+                EVENEMENT_PRODUCTION => EvenementRuche::Production(productions.remove(&reference.id)?),
+                EVENEMENT_POSE_HAUSSE => EvenementRuche::PoseHausse(poses.get(&reference.id)?.clone()),
+                EVENEMENT_RETRAIT_HAUSSE => EvenementRuche::RetraitHausse(poses.get(&reference.id)?.clone()),
+                EVENEMENT_CONFIGURATION => EvenementRuche::Configuration(configurations.remove(&reference.id)?),
+                EVENEMENT_AFFECTATION_MATERIEL => EvenementRuche::AffectationMateriel(mouvements.remove(&reference.id)?),
+                EVENEMENT_RETRAIT_MATERIEL => EvenementRuche::RetraitMateriel(mouvements.remove(&reference.id)?),
+                _ => return None,
+            };
+            Some(EntreeChronologie { date: reference.date_evenement, evenement })
+        })
+        .collect();
+
+    Ok(ChronologieRuche { id_ruche: ruche_id, page, par_page, total, entrees })
+}
@@ -0,0 +1,69 @@
+use crate::db::DbConnection;
+use crate::models::configuration_ruche_models::{ConfigurationRuche, NewConfigurationRuche};
+use crate::schema::configuration_ruche;
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Récupère l'historique des configurations d'une ruche, du plus ancien au plus récent
+pub fn get_configurations_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Vec<ConfigurationRuche>, Error> {
+    configuration_ruche::table
+        .filter(configuration_ruche::id_ruche.eq(ruche_id))
+        .order((configuration_ruche::date_releve.asc(), configuration_ruche::id.asc()))
+        .load::<ConfigurationRuche>(conn)
+}
+
+/// Récupère la configuration la plus récente d'une ruche
+pub fn get_last_configuration_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Option<ConfigurationRuche>, Error> {
+    configuration_ruche::table
+        .filter(configuration_ruche::id_ruche.eq(ruche_id))
+        .order((configuration_ruche::date_releve.desc(), configuration_ruche::id.desc()))
+        .first::<ConfigurationRuche>(conn)
+        .optional()
+}
+
+/// Récupère la configuration la plus récente de chacune des ruches demandées
+pub fn get_last_configurations_by_ruche_ids(conn: &mut DbConnection, ruche_ids: &[i32]) -> Result<Vec<ConfigurationRuche>, Error> {
+    configuration_ruche::table
+        .filter(configuration_ruche::id_ruche.eq_any(ruche_ids))
+        .distinct_on(configuration_ruche::id_ruche)
+        .order((
+            configuration_ruche::id_ruche,
+            configuration_ruche::date_releve.desc(),
+            configuration_ruche::id.desc(),
+        ))
+        .load::<ConfigurationRuche>(conn)
+}
+
+/// Enregistre un nouveau relevé de configuration
+pub fn create_configuration(conn: &mut DbConnection, new_configuration: NewConfigurationRuche) -> Result<ConfigurationRuche, Error> {
+    diesel::insert_into(configuration_ruche::table)
+        .values(&new_configuration)
+        .get_result(conn)
+}
+
+/// Enregistre un relevé uniquement si les compteurs diffèrent de la dernière configuration connue
+pub fn record_configuration_if_changed(
+    conn: &mut DbConnection,
+    new_configuration: NewConfigurationRuche,
+) -> Result<ConfigurationRuche, Error> {
+    match get_last_configuration_by_ruche_id(conn, new_configuration.id_ruche)? {
+        Some(last) if new_configuration.same_counts(&last) => Ok(last),
+        _ => create_configuration(conn, new_configuration),
+    }
+}
+
+/// Enregistre un relevé reprenant la dernière configuration avec un nouveau nombre de hausses
+pub fn record_nombre_hausses(conn: &mut DbConnection, ruche_id: i32, nombre_hausses: i32) -> Result<ConfigurationRuche, Error> {
+    let last = get_last_configuration_by_ruche_id(conn, ruche_id)?;
+    let new_configuration = NewConfigurationRuche {
+        id_ruche: ruche_id,
+        nombre_cadres_corp: last.as_ref().and_then(|c| c.nombre_cadres_corp),
+        nombre_hausses: Some(nombre_hausses),
+        nombre_cadre_couvain: last.as_ref().and_then(|c| c.nombre_cadre_couvain),
+        nombre_cadre_nourriture: last.as_ref().and_then(|c| c.nombre_cadre_nourriture),
+        nombre_cadre_libre: last.as_ref().and_then(|c| c.nombre_cadre_libre),
+        date_releve: None,
+    };
+
+    record_configuration_if_changed(conn, new_configuration)
+}
@@ -0,0 +1,152 @@
+use crate::db::DbConnection;
+use crate::models::floraison_models::{
+    RessourceMellifere, NewRessourceMellifere, UpdateRessourceMellifere, FenetreFloraison, FloraisonsDuMoment,
+    ProductionFloraisons,
+};
+use crate::models::production_models::Production;
+use crate::schema::{production, ressource_mellifere, ruche, rucher};
+use chrono::{Datelike, Days, NaiveDate};
+use diesel::prelude::*;
+use diesel::result::Error;
+use std::collections::HashMap;
+
+/// Récupère les ressources mellifères d'un rucher, dans l'ordre de leur floraison au fil de l'année
+pub fn get_ressources_by_rucher_id(conn: &mut DbConnection, rucher_id: i32) -> Result<Vec<RessourceMellifere>, Error> {
+    let mut ressources = ressource_mellifere::table
+        .filter(ressource_mellifere::id_rucher.eq(rucher_id))
+        .order(ressource_mellifere::id.asc())
+        .load::<RessourceMellifere>(conn)?;
+
+    ressources.sort_by_key(|ressource| (ressource.debut_floraison.month(), ressource.debut_floraison.day()));
+    Ok(ressources)
+}
+
+/// Crée une ressource mellifère
+pub fn create_ressource(
+    conn: &mut DbConnection,
+    new_ressource: NewRessourceMellifere,
+) -> Result<RessourceMellifere, Error> {
+    diesel::insert_into(ressource_mellifere::table)
+        .values(&new_ressource)
+        .get_result::<RessourceMellifere>(conn)
+}
+
+/// Met à jour une ressource mellifère existante
+pub fn update_ressource(
+    conn: &mut DbConnection,
+    id: i32,
+    updated_ressource: UpdateRessourceMellifere,
+) -> Result<RessourceMellifere, Error> {
+    diesel::update(ressource_mellifere::table.find(id))
+        .set(&updated_ressource)
+        .get_result::<RessourceMellifere>(conn)
+}
+
+/// Supprime une ressource mellifère
+pub fn delete_ressource(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(ressource_mellifere::table.find(id)).execute(conn)
+}
+
+// Floraisons attendues des ressources chevauchant une période, jours inclus, par date de début
+fn fenetres(ressources: &[RessourceMellifere], debut: NaiveDate, fin: NaiveDate) -> Vec<FenetreFloraison> {
+    let mut fenetres: Vec<FenetreFloraison> = ressources
+        .iter()
+        .flat_map(|ressource| ressource.fenetres(debut, fin))
+        .collect();
+
+    fenetres.sort_by(|a, b| (a.debut, &a.espece).cmp(&(b.debut, &b.espece)));
+    fenetres
+}
+
+// Ressources mellifères du rucher d'une ruche, aucune si la ruche n'existe pas ou n'est dans aucun rucher
+fn get_ressources_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Vec<RessourceMellifere>, Error> {
+    let rucher_id = ruche::table
+        .find(ruche_id)
+        .select(ruche::id_rucher)
+        .first::<Option<i32>>(conn)
+        .optional()?
+        .flatten();
+
+    match rucher_id {
+        Some(rucher_id) => get_ressources_by_rucher_id(conn, rucher_id),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Floraisons attendues autour du rucher d'une ruche durant une période, jours inclus
+pub fn get_floraisons_ruche(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    debut: NaiveDate,
+    fin: NaiveDate,
+) -> Result<Vec<FenetreFloraison>, Error> {
+    let ressources = get_ressources_by_ruche_id(conn, ruche_id)?;
+    Ok(fenetres(&ressources, debut, fin))
+}
+
+/// Ressources d'un rucher en fleur à une date, et floraisons débutant dans les `jours` suivants.
+/// `NotFound` si le rucher n'existe pas
+pub fn get_floraisons_du_moment(
+    conn: &mut DbConnection,
+    rucher_id: i32,
+    date: NaiveDate,
+    jours: u64,
+) -> Result<FloraisonsDuMoment, Error> {
+    rucher::table.find(rucher_id).select(rucher::id).first::<i32>(conn)?;
+    let ressources = get_ressources_by_rucher_id(conn, rucher_id)?;
+
+    let a_venir = fenetres(&ressources, date + Days::new(1), date + Days::new(jours))
+        .into_iter()
+        .filter(|fenetre| fenetre.debut > date)
+        .collect();
+
+    Ok(FloraisonsDuMoment {
+        id_rucher: rucher_id,
+        date,
+        en_fleur: fenetres(&ressources, date, date),
+        a_venir,
+    })
+}
+
+/// Productions datées d'une ruche entre deux jours inclus, chacune avec les floraisons de son rucher depuis la
+/// production précédente du même type, ou depuis le début de l'année pour la première. `NotFound` si la ruche
+/// n'existe pas
+pub fn get_productions_floraisons(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+) -> Result<Vec<ProductionFloraisons>, Error> {
+    ruche::table.find(ruche_id).select(ruche::id).first::<i32>(conn)?;
+    let ressources = get_ressources_by_ruche_id(conn, ruche_id)?;
+
+    // Toutes les productions sont lues afin de retrouver la précédente de celles de la période
+    let productions = production::table
+        .filter(production::id_ruche.eq(ruche_id))
+        .filter(production::date_creation.is_not_null())
+        .filter(production::date_creation.le(fin.unwrap_or(NaiveDate::MAX)))
+        .order((production::date_creation.asc(), production::id.asc()))
+        .load::<Production>(conn)?;
+
+    let mut precedentes: HashMap<String, NaiveDate> = HashMap::new();
+    let mut resultat = Vec::new();
+    for production in productions {
+        let Some(date) = production.date_creation else {
+            continue;
+        };
+        let depuis = match precedentes.insert(production.type_produit.clone(), date) {
+            Some(precedente) if precedente < date => precedente + Days::new(1),
+            Some(precedente) => precedente,
+            None => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+        };
+
+        if debut.is_none_or(|debut| date >= debut) {
+            resultat.push(ProductionFloraisons {
+                floraisons: fenetres(&ressources, depuis, date),
+                production,
+            });
+        }
+    }
+
+    Ok(resultat)
+}
@@ -0,0 +1,167 @@
+use crate::db::DbConnection;
+use crate::models::hausse_models::{PoseHausse, NewPoseHausse, RetraitHausse, ErreurHausse};
+use crate::models::materiel_models::Materiel;
+use crate::models::production_models::{NewProduction, Production};
+use crate::schema::{materiel, pose_hausse, production};
+use crate::services::configuration_ruche_service;
+use chrono::{NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+// Type de matériel des hausses
+const TYPE_HAUSSE: &str = "hausse";
+
+/// Récupère les hausses actuellement posées sur une ruche
+pub fn get_hausses_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Vec<Materiel>, Error> {
+    pose_hausse::table
+        .inner_join(materiel::table)
+        .filter(pose_hausse::id_ruche.eq(ruche_id))
+        .filter(pose_hausse::date_retrait.is_null())
+        .select(Materiel::as_select())
+        .load::<Materiel>(conn)
+}
+
+/// Récupère l'historique des poses de hausses sur une ruche
+pub fn get_poses_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Vec<PoseHausse>, Error> {
+    pose_hausse::table
+        .filter(pose_hausse::id_ruche.eq(ruche_id))
+        .order((pose_hausse::date_pose.asc(), pose_hausse::id.asc()))
+        .load::<PoseHausse>(conn)
+}
+
+/// Récupère l'historique des poses d'une hausse
+pub fn get_poses_by_materiel_id(conn: &mut DbConnection, materiel_id: i32) -> Result<Vec<PoseHausse>, Error> {
+    pose_hausse::table
+        .filter(pose_hausse::id_materiel.eq(materiel_id))
+        .order((pose_hausse::date_pose.asc(), pose_hausse::id.asc()))
+        .load::<PoseHausse>(conn)
+}
+
+/// Compte les hausses actuellement posées sur une ruche
+pub fn count_hausses_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<i64, Error> {
+    pose_hausse::table
+        .filter(pose_hausse::id_ruche.eq(ruche_id))
+        .filter(pose_hausse::date_retrait.is_null())
+        .count()
+        .get_result(conn)
+}
+
+// Répercute le nombre de hausses posées dans l'historique de configuration de la ruche
+fn sync_nombre_hausses(conn: &mut DbConnection, ruche_id: i32) -> Result<(), Error> {
+    let nombre_hausses = count_hausses_by_ruche_id(conn, ruche_id)? as i32;
+    configuration_ruche_service::record_nombre_hausses(conn, ruche_id, nombre_hausses)?;
+    Ok(())
+}
+
+/// Pose une hausse sur une ruche, `PasUneHausse` si le matériel est d'un autre type
+pub fn add_hausse(conn: &mut DbConnection, new_pose: NewPoseHausse) -> Result<PoseHausse, ErreurHausse> {
+    conn.transaction(|conn| {
+        let type_materiel = materiel::table
+            .find(new_pose.id_materiel)
+            .select(materiel::type_materiel)
+            .first::<Option<String>>(conn)?;
+        if type_materiel.as_deref() != Some(TYPE_HAUSSE) {
+            return Err(ErreurHausse::PasUneHausse);
+        }
+
+        let pose: PoseHausse = diesel::insert_into(pose_hausse::table)
+            .values(&new_pose)
+            .get_result(conn)?;
+
+        diesel::update(materiel::table.find(pose.id_materiel))
+            .set(materiel::id_ruche.eq(pose.id_ruche))
+            .execute(conn)?;
+
+        sync_nombre_hausses(conn, pose.id_ruche)?;
+        Ok(pose)
+    })
+}
+
+/// Retire une hausse de la ruche sur laquelle elle est posée
+pub fn remove_hausse(conn: &mut DbConnection, materiel_id: i32, retrait: RetraitHausse) -> Result<PoseHausse, ErreurHausse> {
+    let date_retrait = retrait.date_retrait.unwrap_or_else(|| Utc::now().date_naive());
+    let poids_miel = retrait.poids_miel()?;
+
+    conn.transaction(|conn| {
+        let pose: PoseHausse = diesel::update(
+            pose_hausse::table
+                .filter(pose_hausse::id_materiel.eq(materiel_id))
+                .filter(pose_hausse::date_retrait.is_null()),
+        )
+            .set((
+                pose_hausse::date_retrait.eq(date_retrait),
+                pose_hausse::poids_miel_extrait.eq(poids_miel),
+                pose_hausse::poids_plein.eq(retrait.poids_plein),
+                pose_hausse::poids_vide.eq(retrait.poids_vide),
+            ))
+            .get_result(conn)?;
+
+        diesel::update(materiel::table.find(materiel_id))
+            .set(materiel::id_ruche.eq(None::<i32>))
+            .execute(conn)?;
+
+        sync_nombre_hausses(conn, pose.id_ruche)?;
+        Ok(pose)
+    })
+}
+
+/// Crée une production par ruche à partir des hausses récoltées à une date donnée
+/// et non encore rattachées à une production
+pub fn create_productions_from_hausses(conn: &mut DbConnection, date_recolte: NaiveDate) -> Result<Vec<Production>, ErreurHausse> {
+    conn.transaction(|conn| {
+        let pose_ids = pose_hausse::table
+            .filter(pose_hausse::date_retrait.eq(date_recolte))
+            .select(pose_hausse::id)
+            .load::<i32>(conn)?;
+
+        create_productions_for_poses(conn, &pose_ids, date_recolte, None)
+    })
+}
+
+/// Crée une production par ruche à partir des poses de hausses données
+/// qui ont un poids de miel extrait et ne sont pas encore rattachées à une production
+pub fn create_productions_for_poses(
+    conn: &mut DbConnection,
+    pose_ids: &[i32],
+    date_production: NaiveDate,
+    variete_miel: Option<String>,
+) -> Result<Vec<Production>, ErreurHausse> {
+    conn.transaction(|conn| {
+        let recoltes_a_attribuer = pose_hausse::table
+            .filter(pose_hausse::id.eq_any(pose_ids))
+            .filter(pose_hausse::poids_miel_extrait.is_not_null())
+            .filter(pose_hausse::id_production.is_null());
+
+        let totaux = recoltes_a_attribuer.clone()
+            .group_by(pose_hausse::id_ruche)
+            .select((pose_hausse::id_ruche, diesel::dsl::sum(pose_hausse::poids_miel_extrait)))
+            .order(pose_hausse::id_ruche)
+            .load::<(i32, Option<i64>)>(conn)?;
+
+        let mut productions = Vec::with_capacity(totaux.len());
+        for (ruche_id, total) in totaux {
+            let total = total.unwrap_or(0);
+            let quantite = i32::try_from(total)
+                .map_err(|_| ErreurHausse::ProductionHorsLimites { id_ruche: ruche_id, total })?;
+            let new_production = NewProduction {
+                id_ruche: Some(ruche_id),
+                quantite_production: Some(quantite),
+                date_creation: Some(date_production),
+                type_produit: Some("miel".to_string()),
+                unite: Some("g".to_string()),
+                variete_miel: variete_miel.clone(),
+            };
+            let created: Production = diesel::insert_into(production::table)
+                .values(&new_production)
+                .get_result(conn)?;
+
+            diesel::update(recoltes_a_attribuer.clone().filter(pose_hausse::id_ruche.eq(ruche_id)))
+                .set(pose_hausse::id_production.eq(created.id))
+                .execute(conn)?;
+
+            productions.push(created);
+        }
+
+        Ok(productions)
+    })
+}
\ No newline at end of file
@@ -1,6 +1,7 @@
 use crate::db::DbConnection;
 use crate::models::intervention_models::{Intervention, NewIntervention, UpdateIntervention};
-use crate::schema::interventions;
+use crate::schema::{interventions, ruche};
+use chrono::NaiveDate;
 use diesel::prelude::*;
 use diesel::result::Error;
 
@@ -33,4 +34,20 @@ pub fn get_interventions_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) ->
     interventions::table
         .filter(interventions::id_ruche.eq(ruche_id))
         .load::<Intervention>(conn)
-}
\ No newline at end of file
+}
+
+/// Récupère les interventions prévues à partir d'une date sur les ruches d'un apiculteur, de la plus proche à la plus lointaine
+pub fn get_interventions_a_venir_by_apiculteur(
+    conn: &mut DbConnection,
+    apiculteur_id: i32,
+    depuis: NaiveDate,
+) -> Result<Vec<Intervention>, Error> {
+    let ruches = ruche::table
+        .filter(ruche::id_apiculteur.eq(apiculteur_id))
+        .select(ruche::id.nullable());
+    interventions::table
+        .filter(interventions::id_ruche.eq_any(ruches))
+        .filter(interventions::date_intervention.ge(depuis))
+        .order((interventions::date_intervention.asc(), interventions::id.asc()))
+        .load::<Intervention>(conn)
+}
@@ -0,0 +1,218 @@
+use crate::db::DbConnection;
+use crate::models::appareil_models::Appareil;
+use crate::models::lorawan_models::{Uplink, MesuresDecodees, ResultatUplink};
+use crate::models::poids_models::NewPoids;
+use crate::models::releve_capteur_models::{NewReleveCapteur, MESURE_TEMPERATURE, MESURE_HUMIDITE};
+use crate::schema::appareil;
+use crate::services::{appareil_service, poids_service, releve_capteur_service};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Décodeur de la charge utile des appareils d'un modèle
+pub trait DecodeurCharge: Sync {
+    fn decoder(&self, port: Option<u8>, charge: &[u8]) -> Result<MesuresDecodees, String>;
+}
+
+/// Balance BeeScale, trame de 8 octets en gros-boutiste sur le port 1 : poids (u32, grammes), température (i16,
+/// centièmes de degré), humidité (u8, %) et batterie (u8, %)
+pub struct DecodeurBeeScale;
+
+// Port des trames de mesure des balances BeeScale
+const PORT_BEESCALE: u8 = 1;
+
+impl DecodeurCharge for DecodeurBeeScale {
+    fn decoder(&self, port: Option<u8>, charge: &[u8]) -> Result<MesuresDecodees, String> {
+        if port != Some(PORT_BEESCALE) {
+            return Err(format!("Port BeeScale inconnu : {:?}, {} attendu", port, PORT_BEESCALE));
+        }
+        let [p0, p1, p2, p3, t0, t1, humidite, batterie] = charge else {
+            return Err(format!("Trame BeeScale de {} octets, 8 attendus", charge.len()));
+        };
+
+        let poids = u32::from_be_bytes([*p0, *p1, *p2, *p3]);
+        Ok(MesuresDecodees {
+            poids: Some(i32::try_from(poids).map_err(|_| format!("Poids hors limites : {} g", poids))?),
+            temperature: Some(i16::from_be_bytes([*t0, *t1]) as f64 / 100.0),
+            humidite: Some(*humidite as f64),
+            niveau_batterie: Some(*batterie as i32),
+        })
+    }
+}
+
+/// Format Cayenne LPP (canal, type, valeur) : température (0x67), humidité (0x68) et poids en kilogrammes
+/// sur une entrée analogique (0x02). Les autres types connus sont ignorés
+pub struct DecodeurCayenneLpp;
+
+impl DecodeurCharge for DecodeurCayenneLpp {
+    fn decoder(&self, _port: Option<u8>, charge: &[u8]) -> Result<MesuresDecodees, String> {
+        let mut mesures = MesuresDecodees::default();
+        let mut reste = charge;
+
+        while let [_canal, type_donnee, donnees @ ..] = reste {
+            let taille = match type_donnee {
+                0x00 | 0x01 | 0x66 | 0x68 => 1,
+                0x02 | 0x03 | 0x65 | 0x67 | 0x73 => 2,
+                0x71 | 0x86 => 6,
+                0x88 => 9,
+                _ => return Err(format!("Type Cayenne LPP inconnu : 0x{:02x}", type_donnee)),
+            };
+            if donnees.len() < taille {
+                return Err("Trame Cayenne LPP tronquée".to_string());
+            }
+
+            let valeur = &donnees[..taille];
+            match type_donnee {
+                0x02 => mesures.poids = Some((i16::from_be_bytes([valeur[0], valeur[1]]) as i32) * 10),
+                0x67 => mesures.temperature = Some(i16::from_be_bytes([valeur[0], valeur[1]]) as f64 / 10.0),
+                0x68 => mesures.humidite = Some(valeur[0] as f64 / 2.0),
+                _ => {}
+            }
+            reste = &donnees[taille..];
+        }
+
+        if !reste.is_empty() {
+            return Err("Trame Cayenne LPP tronquée".to_string());
+        }
+        Ok(mesures)
+    }
+}
+
+// Décodeurs par modèle d'appareil : un nouveau modèle s'ajoute en implémentant DecodeurCharge et en l'inscrivant ici
+const DECODEURS: &[(&str, &dyn DecodeurCharge)] = &[
+    ("BeeScale", &DecodeurBeeScale),
+    ("Cayenne LPP", &DecodeurCayenneLpp),
+];
+
+/// Décodeur associé à un modèle d'appareil, sans tenir compte de la casse
+pub fn decodeur_pour(modele: &str) -> Option<&'static dyn DecodeurCharge> {
+    DECODEURS
+        .iter()
+        .find(|(nom, _)| nom.eq_ignore_ascii_case(modele))
+        .map(|(_, decodeur)| *decodeur)
+}
+
+/// Enregistre un uplink LoRaWAN : la charge utile est décodée selon le modèle de l'appareil, l'état de
+/// l'appareil est mis à jour, puis le poids, la température et l'humidité sont enregistrés pour la ruche
+/// associée. Renvoie `None` si l'appareil n'est associé à aucune ruche, `NotFound` si le DevEUI est inconnu
+/// et `DeserializationError` si la charge utile ne peut être décodée
+pub fn enregistrer_uplink(conn: &mut DbConnection, uplink: Uplink) -> Result<Option<ResultatUplink>, Error> {
+    let appareil = appareil::table
+        .filter(appareil::dev_eui.eq(&uplink.dev_eui))
+        .first::<Appareil>(conn)?;
+
+    let decodeur = decodeur_pour(&appareil.modele)
+        .ok_or_else(|| Error::DeserializationError(format!("Aucun décodeur pour le modèle {}", appareil.modele).into()))?;
+    let mesures = decodeur
+        .decoder(uplink.port, &uplink.charge)
+        .map_err(|e| Error::DeserializationError(e.into()))?;
+
+    conn.transaction(|conn| {
+        appareil_service::marquer_connexion(
+            conn,
+            &appareil,
+            uplink.date_reception,
+            mesures.niveau_batterie,
+            None,
+            uplink.rssi,
+        )?;
+
+        let Some(ruche_id) = appareil.id_ruche else {
+            return Ok(None);
+        };
+
+        let poids = match mesures.poids {
+            Some(poids) => Some(poids_service::create_poids(conn, NewPoids {
+                id_ruche: Some(ruche_id),
+                poids_ruche: Some(poids),
+                date_creation: None,
+                horodatage: Some(uplink.date_reception),
+            })?),
+            None => None,
+        };
+
+        let releves = [
+            (MESURE_TEMPERATURE, mesures.temperature),
+            (MESURE_HUMIDITE, mesures.humidite),
+        ]
+        .into_iter()
+        .filter_map(|(type_mesure, valeur)| {
+            valeur.map(|valeur| releve_capteur_service::create_releve(conn, NewReleveCapteur {
+                id_ruche: ruche_id,
+                id_appareil: Some(appareil.id),
+                type_mesure: type_mesure.to_string(),
+                valeur,
+                date_releve: Some(uplink.date_reception),
+            }))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Some(ResultatUplink {
+            id_appareil: appareil.id,
+            poids,
+            releves,
+        }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::lorawan_models::UplinkLoRaWan;
+
+    // Uplink enregistré, ramené au format commun
+    fn uplink(json: &str) -> Uplink {
+        serde_json::from_str::<UplinkLoRaWan>(json)
+            .expect("uplink illisible")
+            .normaliser()
+            .expect("charge utile base64 invalide")
+    }
+
+    #[test]
+    fn decode_une_trame_beescale() {
+        let uplink = uplink(include_str!("../../fixtures/lorawan/ttn_beescale.json"));
+        assert_eq!(uplink.dev_eui, "70B3D57ED0000001");
+        assert_eq!(uplink.rssi, Some(-97));
+
+        let mesures = decodeur_pour("beescale").unwrap().decoder(uplink.port, &uplink.charge).unwrap();
+        assert_eq!(mesures.poids, Some(42350));
+        assert_eq!(mesures.temperature, Some(34.5));
+        assert_eq!(mesures.humidite, Some(58.0));
+        assert_eq!(mesures.niveau_batterie, Some(76));
+    }
+
+    #[test]
+    fn decode_une_trame_cayenne_lpp() {
+        let uplink = uplink(include_str!("../../fixtures/lorawan/chirpstack_cayenne_lpp.json"));
+        assert_eq!(uplink.dev_eui, "70B3D57ED0000002");
+        assert_eq!(uplink.rssi, Some(-88));
+
+        let mesures = decodeur_pour("Cayenne LPP").unwrap().decoder(uplink.port, &uplink.charge).unwrap();
+        assert_eq!(mesures.poids, Some(41800));
+        assert_eq!(mesures.temperature, Some(35.2));
+        assert_eq!(mesures.humidite, Some(61.0));
+        assert_eq!(mesures.niveau_batterie, None);
+    }
+
+    #[test]
+    fn refuse_une_trame_tronquee() {
+        let uplink = uplink(include_str!("../../fixtures/lorawan/ttn_beescale_trame_tronquee.json"));
+        assert!(DecodeurBeeScale.decoder(uplink.port, &uplink.charge).is_err());
+
+        // Température Cayenne LPP amputée de son second octet
+        assert!(DecodeurCayenneLpp.decoder(Some(1), &[0x02, 0x67, 0x01]).is_err());
+    }
+
+    #[test]
+    fn refuse_un_port_inconnu() {
+        let uplink = uplink(include_str!("../../fixtures/lorawan/ttn_beescale.json"));
+        assert!(DecodeurBeeScale.decoder(Some(2), &uplink.charge).is_err());
+        assert!(DecodeurBeeScale.decoder(None, &uplink.charge).is_err());
+    }
+
+    #[test]
+    fn ignore_la_casse_du_modele() {
+        assert!(decodeur_pour("BEESCALE").is_some());
+        assert!(decodeur_pour("cayenne lpp").is_some());
+        assert!(decodeur_pour("Inconnu").is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::db::DbConnection;
+use crate::models::lot_models::{LotMiel, NewLotMiel, UpdateLotMiel, LotProduction, LotPot, LotDetail, ProvenanceLot, TracabiliteLot};
+use crate::models::production_models::Production;
+use crate::schema::{lot_miel, lot_pot, lot_production, production, ruche, rucher};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Récupère tous les lots
+pub fn get_all_lots(conn: &mut DbConnection) -> Result<Vec<LotMiel>, Error> {
+    lot_miel::table
+        .order((lot_miel::date_extraction.desc().nulls_last(), lot_miel::id.desc()))
+        .load::<LotMiel>(conn)
+}
+
+/// Récupère un lot par son ID
+pub fn get_lot_by_id(conn: &mut DbConnection, id: i32) -> Result<LotMiel, Error> {
+    lot_miel::table.find(id).first::<LotMiel>(conn)
+}
+
+/// Récupère un lot avec ses productions et son conditionnement
+pub fn get_lot_detail(conn: &mut DbConnection, id: i32) -> Result<LotDetail, Error> {
+    let lot = get_lot_by_id(conn, id)?;
+
+    let productions = lot_production::table
+        .inner_join(production::table)
+        .filter(lot_production::id_lot.eq(id))
+        .order(production::id.asc())
+        .select(Production::as_select())
+        .load::<Production>(conn)?;
+
+    let pots = lot_pot::table
+        .filter(lot_pot::id_lot.eq(id))
+        .order(lot_pot::contenance.asc())
+        .load::<LotPot>(conn)?;
+
+    Ok(LotDetail { lot, productions, pots })
+}
+
+/// Crée un lot de miel à partir des productions données
+pub fn create_lot(conn: &mut DbConnection, new_lot: NewLotMiel) -> Result<LotDetail, Error> {
+    conn.transaction(|conn| {
+        let lot: LotMiel = diesel::insert_into(lot_miel::table)
+            .values(&new_lot)
+            .get_result(conn)?;
+
+        let liens: Vec<LotProduction> = new_lot.productions
+            .iter()
+            .map(|&id_production| LotProduction { id_lot: lot.id, id_production })
+            .collect();
+        diesel::insert_into(lot_production::table)
+            .values(&liens)
+            .execute(conn)?;
+
+        replace_pots(conn, lot.id, new_lot.pots)?;
+        get_lot_detail(conn, lot.id)
+    })
+}
+
+/// Met à jour les informations d'un lot
+pub fn update_lot(conn: &mut DbConnection, id: i32, updated_lot: UpdateLotMiel) -> Result<LotMiel, Error> {
+    diesel::update(lot_miel::table.find(id))
+        .set(&updated_lot)
+        .get_result(conn)
+}
+
+/// Supprime un lot
+pub fn delete_lot(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(lot_miel::table.find(id)).execute(conn)
+}
+
+/// Remplace le conditionnement d'un lot
+pub fn replace_pots(conn: &mut DbConnection, id: i32, pots: Vec<LotPot>) -> Result<Vec<LotPot>, Error> {
+    conn.transaction(|conn| {
+        diesel::delete(lot_pot::table.filter(lot_pot::id_lot.eq(id))).execute(conn)?;
+
+        let pots: Vec<LotPot> = pots
+            .into_iter()
+            .map(|pot| LotPot { id_lot: id, ..pot })
+            .collect();
+        diesel::insert_into(lot_pot::table)
+            .values(&pots)
+            .get_results(conn)
+    })
+}
+
+/// Retrouve les productions, ruches et ruchers ayant contribué à un lot
+pub fn get_tracabilite_lot(conn: &mut DbConnection, id: i32) -> Result<TracabiliteLot, Error> {
+    let lot = get_lot_by_id(conn, id)?;
+
+    let provenances = lot_production::table
+        .inner_join(production::table.inner_join(ruche::table.left_join(rucher::table)))
+        .filter(lot_production::id_lot.eq(id))
+        .order((ruche::id.asc(), production::id.asc()))
+        .select((
+            production::id,
+            production::date_creation,
+            production::quantite_production,
+            ruche::id,
+            ruche::nom_ruche,
+            ruche::numero_ruche,
+            rucher::id.nullable(),
+            rucher::nom_rucher.nullable(),
+        ))
+        .load::<ProvenanceLot>(conn)?;
+
+    Ok(TracabiliteLot { lot, provenances })
+}
+
+/// Récupère les lots auxquels une ruche a contribué
+pub fn get_lots_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Vec<LotMiel>, Error> {
+    let lots_de_la_ruche = lot_production::table
+        .inner_join(production::table)
+        .filter(production::id_ruche.eq(ruche_id))
+        .select(lot_production::id_lot);
+
+    lot_miel::table
+        .filter(lot_miel::id.eq_any(lots_de_la_ruche))
+        .order((lot_miel::date_extraction.desc().nulls_last(), lot_miel::id.desc()))
+        .load::<LotMiel>(conn)
+}
\ No newline at end of file
@@ -1,6 +1,6 @@
 use crate::db::DbConnection;
-use crate::models::materiel_models::{Materiel, NewMateriel, UpdateMateriel};
-use crate::schema::materiel;
+use crate::models::materiel_models::{Materiel, NewMateriel, UpdateMateriel, ETATS_A_REPARER};
+use crate::schema::{materiel, ruche};
 use diesel::prelude::*;
 use diesel::result::Error;
 
@@ -45,4 +45,16 @@ pub fn get_materiels_by_etat(conn: &mut DbConnection, etat: String) -> Result<Ve
     materiel::table
         .filter(materiel::etat_materiel.eq(etat))
         .load::<Materiel>(conn)
-}
\ No newline at end of file
+}
+
+/// Récupère les matériels à réparer des ruches d'un apiculteur
+pub fn get_materiels_a_reparer_by_apiculteur(conn: &mut DbConnection, apiculteur_id: i32) -> Result<Vec<Materiel>, Error> {
+    let ruches = ruche::table
+        .filter(ruche::id_apiculteur.eq(apiculteur_id))
+        .select(ruche::id.nullable());
+    materiel::table
+        .filter(materiel::id_ruche.eq_any(ruches))
+        .filter(materiel::etat_materiel.eq_any(ETATS_A_REPARER))
+        .order((materiel::id_ruche, materiel::id))
+        .load::<Materiel>(conn)
+}
@@ -0,0 +1,138 @@
+use crate::db::DbConnection;
+use crate::models::meteo_models::{ObservationMeteo, NewObservationMeteo, ConfigMeteo, SOURCE_MANUELLE};
+use crate::models::rucher_models::Rucher;
+use crate::schema::{observation_meteo, rucher};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::upsert::excluded;
+use std::fs;
+
+/// Fournisseur d'observations météo journalières pour l'emplacement d'un rucher
+pub trait FournisseurMeteo: Sync {
+    fn observations(
+        &self,
+        config: &ConfigMeteo,
+        rucher: &Rucher,
+        debut: NaiveDate,
+        fin: NaiveDate,
+    ) -> Result<Vec<NewObservationMeteo>, String>;
+}
+
+/// Observations lues dans le fichier `rucher_<id>.json` du répertoire configuré : un tableau d'objets
+/// `date_observation`, `temperature_min`, `temperature_max`, `precipitations` et `vent`
+pub struct FournisseurFichier;
+
+impl FournisseurMeteo for FournisseurFichier {
+    fn observations(
+        &self,
+        config: &ConfigMeteo,
+        rucher: &Rucher,
+        debut: NaiveDate,
+        fin: NaiveDate,
+    ) -> Result<Vec<NewObservationMeteo>, String> {
+        let chemin = config.repertoire.join(format!("rucher_{}.json", rucher.id));
+        let contenu = fs::read_to_string(&chemin)
+            .map_err(|e| format!("Lecture de {} impossible : {}", chemin.display(), e))?;
+        let observations = serde_json::from_str::<Vec<NewObservationMeteo>>(&contenu)
+            .map_err(|e| format!("Fichier {} illisible : {}", chemin.display(), e))?;
+
+        Ok(observations
+            .into_iter()
+            .filter(|observation| observation.date_observation >= debut && observation.date_observation <= fin)
+            .collect())
+    }
+}
+
+// Fournisseurs par nom : un nouveau fournisseur s'ajoute en implémentant FournisseurMeteo et en l'inscrivant ici
+const FOURNISSEURS: &[(&str, &dyn FournisseurMeteo)] = &[
+    ("fichier", &FournisseurFichier),
+];
+
+/// Fournisseur désigné par son nom, sans tenir compte de la casse
+pub fn fournisseur_pour(nom: &str) -> Option<&'static dyn FournisseurMeteo> {
+    FOURNISSEURS
+        .iter()
+        .find(|(nom_fournisseur, _)| nom_fournisseur.eq_ignore_ascii_case(nom))
+        .map(|(_, fournisseur)| *fournisseur)
+}
+
+/// Récupère les observations d'un rucher, éventuellement entre deux jours inclus, par date
+pub fn get_observations_by_rucher_id(
+    conn: &mut DbConnection,
+    rucher_id: i32,
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+) -> Result<Vec<ObservationMeteo>, Error> {
+    let mut query = observation_meteo::table
+        .filter(observation_meteo::id_rucher.eq(rucher_id))
+        .order(observation_meteo::date_observation.asc())
+        .into_boxed();
+
+    if let Some(debut) = debut {
+        query = query.filter(observation_meteo::date_observation.ge(debut));
+    }
+    if let Some(fin) = fin {
+        query = query.filter(observation_meteo::date_observation.le(fin));
+    }
+
+    query.load::<ObservationMeteo>(conn)
+}
+
+/// Enregistre l'observation d'un jour, en remplaçant celle du même jour au rucher
+pub fn enregistrer_observation(
+    conn: &mut DbConnection,
+    new_observation: NewObservationMeteo,
+) -> Result<ObservationMeteo, Error> {
+    diesel::insert_into(observation_meteo::table)
+        .values(&new_observation)
+        .on_conflict((observation_meteo::id_rucher, observation_meteo::date_observation))
+        .do_update()
+        .set(&new_observation)
+        .get_result::<ObservationMeteo>(conn)
+}
+
+/// Importe du fournisseur configuré les observations d'un rucher entre deux jours inclus. Les observations
+/// déjà importées sont mises à jour, celles saisies par l'apiculteur conservées. Renvoie les observations
+/// enregistrées, `NotFound` si le rucher n'existe pas et `DeserializationError` si le fournisseur échoue
+pub fn importer_observations(
+    conn: &mut DbConnection,
+    config: &ConfigMeteo,
+    rucher_id: i32,
+    debut: NaiveDate,
+    fin: NaiveDate,
+) -> Result<Vec<ObservationMeteo>, Error> {
+    let rucher = rucher::table.find(rucher_id).first::<Rucher>(conn)?;
+    let fournisseur = fournisseur_pour(&config.fournisseur)
+        .ok_or_else(|| Error::DeserializationError(format!("Fournisseur météo inconnu : {}", config.fournisseur).into()))?;
+    let observations = fournisseur
+        .observations(config, &rucher, debut, fin)
+        .map_err(|e| Error::DeserializationError(e.into()))?
+        .into_iter()
+        .map(|observation| NewObservationMeteo {
+            id_rucher: rucher.id,
+            source: config.fournisseur.to_lowercase(),
+            ..observation
+        })
+        .collect::<Vec<_>>();
+
+    let insertion = diesel::insert_into(observation_meteo::table)
+        .values(&observations)
+        .on_conflict((observation_meteo::id_rucher, observation_meteo::date_observation))
+        .do_update()
+        .set((
+            observation_meteo::temperature_min.eq(excluded(observation_meteo::temperature_min)),
+            observation_meteo::temperature_max.eq(excluded(observation_meteo::temperature_max)),
+            observation_meteo::precipitations.eq(excluded(observation_meteo::precipitations)),
+            observation_meteo::vent.eq(excluded(observation_meteo::vent)),
+            observation_meteo::source.eq(excluded(observation_meteo::source)),
+        ));
+    // Clause WHERE de la mise à jour en cas de conflit, et non filtre d'une requête
+    diesel::query_dsl::methods::FilterDsl::filter(insertion, observation_meteo::source.ne(SOURCE_MANUELLE))
+        .get_results::<ObservationMeteo>(conn)
+}
+
+/// Supprime une observation
+pub fn delete_observation(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(observation_meteo::table.find(id)).execute(conn)
+}
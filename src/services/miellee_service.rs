@@ -0,0 +1,201 @@
+use crate::db::DbConnection;
+use crate::models::miellee_models::{PoidsNetJour, FiabilitePrevision, DateProjetee, PrevisionMiellee};
+use crate::schema::{poids, pose_hausse};
+use crate::services::poids_service::tare_ruche;
+use chrono::{Days, NaiveDate};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+// Miel que contient une hausse pleine lorsque la ruche n'a encore aucune hausse récoltée, en grammes
+const CAPACITE_HAUSSE: i32 = 12000;
+
+// Remplissage des hausses posées à partir duquel en ajouter une
+const REMPLISSAGE_NOUVELLE_HAUSSE: f64 = 0.75;
+
+// Fenêtre étendue lorsque la fenêtre demandée compte moins de trois pesées, en jours
+const FENETRE_MAX: u64 = 60;
+
+// Au-delà, une projection n'a plus de sens, en jours
+const HORIZON: f64 = 180.0;
+
+// Quantiles à 97,5 % de la loi de Student pour 1 à 30 degrés de liberté
+const QUANTILES_STUDENT: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+    2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+    2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+// Dernière pesée nette de chaque jour d'une ruche à partir d'une date
+fn get_poids_nets_jours(conn: &mut DbConnection, ruche_id: i32, depuis: NaiveDate) -> Result<Vec<PoidsNetJour>, Error> {
+    let pesees = poids::table
+        .filter(poids::id_ruche.eq(ruche_id))
+        .filter(poids::date_creation.ge(depuis))
+        .filter(poids::poids_ruche.is_not_null())
+        .filter(poids::exclu.eq(false))
+        .distinct_on(poids::date_creation)
+        .order((poids::date_creation.asc(), poids::horodatage.desc(), poids::id.desc()))
+        .select((
+            poids::date_creation,
+            poids::poids_ruche - tare_ruche(poids::id_ruche, poids::date_creation),
+        ))
+        .load::<(Option<NaiveDate>, Option<i32>)>(conn)?;
+
+    Ok(pesees
+        .into_iter()
+        .filter_map(|(date, poids_net)| Some(PoidsNetJour { date: date?, poids_net: poids_net? }))
+        .collect())
+}
+
+// Pente de la droite des moindres carrés (grammes par jour) et demi-largeur de son intervalle de confiance
+// à 95 %, absente avec deux points
+fn ajuster_tendance(points: &[PoidsNetJour]) -> Option<(f64, Option<f64>)> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let origine = points[0].date;
+    let xy: Vec<(f64, f64)> = points
+        .iter()
+        .map(|point| ((point.date - origine).num_days() as f64, point.poids_net as f64))
+        .collect();
+    let n = xy.len() as f64;
+    let moyenne_x = xy.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let moyenne_y = xy.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = xy.iter().map(|(x, _)| (x - moyenne_x).powi(2)).sum();
+    let sxy: f64 = xy.iter().map(|(x, y)| (x - moyenne_x) * (y - moyenne_y)).sum();
+
+    let pente = sxy / sxx;
+    if xy.len() == 2 {
+        return Some((pente, None));
+    }
+
+    let ordonnee = moyenne_y - pente * moyenne_x;
+    let residus: f64 = xy.iter().map(|(x, y)| (y - ordonnee - pente * x).powi(2)).sum();
+    let degres = xy.len() - 2;
+    let erreur_pente = (residus / degres as f64 / sxx).sqrt();
+    let quantile = QUANTILES_STUDENT.get(degres - 1).copied().unwrap_or(1.96);
+    Some((pente, Some(quantile * erreur_pente)))
+}
+
+// Date à laquelle une quantité restante est atteinte au rythme d'une prise de poids journalière
+fn date_atteinte(depuis: NaiveDate, restant: f64, gain: f64) -> Option<NaiveDate> {
+    if restant <= 0.0 {
+        return Some(depuis);
+    }
+    if gain <= 0.0 || restant / gain > HORIZON {
+        return None;
+    }
+    depuis.checked_add_days(Days::new((restant / gain).ceil() as u64))
+}
+
+// Projection d'une quantité restante selon la tendance et ses bornes
+fn projeter(depuis: NaiveDate, restant: f64, gain: f64, marge: Option<f64>) -> Option<DateProjetee> {
+    Some(DateProjetee {
+        date: date_atteinte(depuis, restant, gain)?,
+        au_plus_tot: marge.and_then(|marge| date_atteinte(depuis, restant, gain + marge)),
+        au_plus_tard: marge.and_then(|marge| date_atteinte(depuis, restant, gain - marge)),
+    })
+}
+
+/// Prévoit la miellée d'une ruche : la prise de poids nette journalière est ajustée sur les pesées des
+/// `jours` derniers jours (postérieures à la dernière récolte), puis projetée pour dater le remplissage des
+/// hausses posées et la pose d'une hausse supplémentaire. Avec moins de trois pesées, la fenêtre est étendue
+/// jusqu'à 60 jours ; avec deux, la tendance est donnée sans bornes ; en deçà, aucune projection n'est faite
+pub fn prevoir_miellee(conn: &mut DbConnection, ruche_id: i32, aujourd_hui: NaiveDate, jours: u64) -> Result<PrevisionMiellee, Error> {
+    // Une récolte fait chuter le poids : la tendance ne porte que sur les pesées qui la suivent
+    let derniere_recolte = pose_hausse::table
+        .filter(pose_hausse::id_ruche.eq(ruche_id))
+        .filter(pose_hausse::date_retrait.le(aujourd_hui))
+        .select(diesel::dsl::max(pose_hausse::date_retrait))
+        .first::<Option<NaiveDate>>(conn)?;
+    let debut = |jours: u64| {
+        let debut = aujourd_hui - Days::new(jours);
+        derniere_recolte.map_or(debut, |recolte| debut.max(recolte))
+    };
+
+    let mut debut_fenetre = debut(jours);
+    let mut points = get_poids_nets_jours(conn, ruche_id, debut_fenetre)?;
+    points.retain(|point| point.date <= aujourd_hui);
+    if points.len() < 3 && jours < FENETRE_MAX {
+        debut_fenetre = debut(FENETRE_MAX);
+        points = get_poids_nets_jours(conn, ruche_id, debut_fenetre)?;
+        points.retain(|point| point.date <= aujourd_hui);
+    }
+
+    let tendance = ajuster_tendance(&points);
+    let fiabilite = match (points.len(), &tendance) {
+        (_, None) => FiabilitePrevision::Insuffisante,
+        (2, _) => FiabilitePrevision::Faible,
+        (n, _) if (n as u64) * 2 >= (aujourd_hui - debut_fenetre).num_days() as u64 => FiabilitePrevision::Elevee,
+        _ => FiabilitePrevision::Moyenne,
+    };
+
+    let recoltes = pose_hausse::table
+        .filter(pose_hausse::id_ruche.eq(ruche_id))
+        .filter(pose_hausse::poids_miel_extrait.is_not_null())
+        .select(pose_hausse::poids_miel_extrait)
+        .load::<Option<i32>>(conn)?;
+    let capacite_mesuree = !recoltes.is_empty();
+    let capacite_hausse = match capacite_mesuree {
+        true => (recoltes.iter().flatten().map(|&miel| miel as f64).sum::<f64>() / recoltes.len() as f64).round() as i32,
+        false => CAPACITE_HAUSSE,
+    };
+
+    // Hausses posées à la date de la prévision
+    let poses = pose_hausse::table
+        .filter(pose_hausse::id_ruche.eq(ruche_id))
+        .filter(pose_hausse::date_pose.le(aujourd_hui))
+        .filter(pose_hausse::date_retrait.is_null().or(pose_hausse::date_retrait.gt(aujourd_hui)))
+        .select(pose_hausse::date_pose)
+        .load::<NaiveDate>(conn)?;
+    let hausses_posees = poses.len() as i64;
+
+    // Miel monté dans les hausses : prise de poids nette depuis la dernière pesée précédant la première pose
+    let premiere_pose = poses.into_iter().min();
+    let miel_en_hausses = match (premiere_pose, points.last()) {
+        (Some(pose), Some(derniere)) => {
+            let reference = poids::table
+                .filter(poids::id_ruche.eq(ruche_id))
+                .filter(poids::poids_ruche.is_not_null())
+                .filter(poids::exclu.eq(false))
+                .filter(poids::date_creation.le(pose))
+                .order((poids::date_creation.desc(), poids::horodatage.desc(), poids::id.desc()))
+                .select(poids::poids_ruche - tare_ruche(poids::id_ruche, poids::date_creation))
+                .first::<Option<i32>>(conn)
+                .optional()?
+                .flatten();
+            reference.map(|reference| (derniere.poids_net - reference).max(0))
+        }
+        _ => None,
+    };
+
+    let capacite_totale = (hausses_posees as i32 * capacite_hausse) as f64;
+    let remplissage = miel_en_hausses
+        .filter(|_| capacite_totale > 0.0)
+        .map(|miel| miel as f64 / capacite_totale);
+
+    let (hausses_pleines, nouvelle_hausse) = match (tendance, miel_en_hausses, points.last()) {
+        (Some((gain, marge)), Some(miel), Some(derniere)) if capacite_totale > 0.0 => (
+            projeter(derniere.date, capacite_totale - miel as f64, gain, marge),
+            projeter(derniere.date, capacite_totale * REMPLISSAGE_NOUVELLE_HAUSSE - miel as f64, gain, marge),
+        ),
+        _ => (None, None),
+    };
+
+    Ok(PrevisionMiellee {
+        id_ruche: ruche_id,
+        debut_fenetre,
+        fiabilite,
+        gain_journalier: tendance.map(|(gain, _)| gain),
+        gain_journalier_min: tendance.and_then(|(gain, marge)| marge.map(|marge| gain - marge)),
+        gain_journalier_max: tendance.and_then(|(gain, marge)| marge.map(|marge| gain + marge)),
+        points,
+        hausses_posees,
+        capacite_hausse,
+        capacite_mesuree,
+        miel_en_hausses,
+        remplissage,
+        hausses_pleines,
+        nouvelle_hausse,
+    })
+}
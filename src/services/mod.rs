@@ -3,4 +3,24 @@ pub mod utilisateur_service;
 pub mod ruche_service;
 pub mod production_service;
 pub mod poids_service;
-pub mod materiel_service;
\ No newline at end of file
+pub mod materiel_service;
+pub mod configuration_ruche_service;
+pub mod hausse_service;
+pub mod lot_service;
+pub mod recolte_service;
+pub mod rucher_service;
+pub mod traitement_service;
+pub mod alerte_service;
+pub mod varroa_service;
+pub mod nourrissement_service;
+pub mod appareil_service;
+pub mod lorawan_service;
+pub mod mqtt_service;
+pub mod releve_capteur_service;
+pub mod miellee_service;
+pub mod anomalie_poids_service;
+pub mod meteo_service;
+pub mod floraison_service;
+pub mod sante_service;
+pub mod tableau_de_bord_service;
+pub mod chronologie_service;
\ No newline at end of file
@@ -0,0 +1,266 @@
+use crate::db::{DbConnection, Pool};
+use crate::models::mqtt_models::{ConfigMqtt, SujetRuche, MesureMqtt};
+use crate::models::poids_models::{Poids, NewPoids};
+use crate::schema::ruche;
+use crate::services::poids_service;
+use actix_web::{rt, web};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::result::Error;
+use log::{error, info, warn};
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, Publish, QoS};
+use std::time::Duration;
+
+// Attente avant une nouvelle tentative de connexion au broker, doublée à chaque échec
+const DELAI_RECONNEXION_MIN: Duration = Duration::from_secs(1);
+const DELAI_RECONNEXION_MAX: Duration = Duration::from_secs(60);
+
+// Tentatives d'enregistrement d'une pesée lorsque la base est en erreur, l'attente entre deux tentatives
+// étant doublée à chaque échec, et assez courte au total pour ne pas dépasser le keep-alive du broker
+const TENTATIVES_ENREGISTREMENT: u32 = 4;
+const DELAI_NOUVELLE_TENTATIVE: Duration = Duration::from_secs(1);
+
+/// Enregistre une pesée reçue sur MQTT pour la ruche désignée par le sujet, comme une pesée saisie.
+/// `NotFound` si la ruche n'existe pas ou n'appartient pas au rucher désigné par le sujet
+pub fn enregistrer_pesee(conn: &mut DbConnection, sujet: &SujetRuche, mesure: MesureMqtt) -> Result<Poids, Error> {
+    let id_rucher = ruche::table
+        .find(sujet.id_ruche)
+        .select(ruche::id_rucher)
+        .first::<Option<i32>>(conn)?;
+    if sujet.id_rucher.is_some_and(|rucher_id| Some(rucher_id) != id_rucher) {
+        return Err(Error::NotFound);
+    }
+
+    let (poids, date_mesure) = match mesure {
+        MesureMqtt::Poids(poids) => (poids, None),
+        MesureMqtt::Detaillee { poids, date_mesure } => (poids, date_mesure),
+    };
+
+    poids_service::create_poids(conn, NewPoids {
+        id_ruche: Some(sujet.id_ruche),
+        poids_ruche: Some(poids),
+        date_creation: date_mesure,
+        // Une pesée sans date est horodatée à sa réception
+        horodatage: date_mesure.is_none().then(|| Utc::now().naive_utc()),
+    })
+}
+
+/// Traite une publication et indique si elle peut être acquittée : c'est le cas une fois la pesée
+/// enregistrée ou si le message est invalide, mais pas si la base reste en erreur après plusieurs
+/// tentatives, afin que le broker le délivre de nouveau à la connexion suivante
+async fn traiter_publication(pool: &Pool, publication: &Publish) -> bool {
+    let Some(sujet) = SujetRuche::depuis_sujet(&publication.topic) else {
+        warn!("Message MQTT ignoré, aucune ruche dans le sujet {}", publication.topic);
+        return true;
+    };
+    let mesure = match serde_json::from_slice::<MesureMqtt>(&publication.payload) {
+        Ok(mesure) => mesure,
+        Err(e) => {
+            warn!("Message MQTT ignoré sur {}, pesée illisible : {}", publication.topic, e);
+            return true;
+        }
+    };
+
+    let mut delai = DELAI_NOUVELLE_TENTATIVE;
+    for tentative in 1..=TENTATIVES_ENREGISTREMENT {
+        let pool = pool.clone();
+        let result = web::block(move || {
+            let mut conn = pool.get()?;
+            Ok::<_, r2d2::Error>(enregistrer_pesee(&mut conn, &sujet, mesure))
+        }).await;
+
+        match result {
+            Ok(Ok(Ok(_))) => return true,
+            Ok(Ok(Err(Error::NotFound))) => {
+                warn!("Message MQTT ignoré, ruche inconnue ou hors du rucher du sujet {}", publication.topic);
+                return true;
+            }
+            Ok(Ok(Err(e))) => {
+                error!(
+                    "Erreur lors de l'enregistrement de la pesée reçue sur {} (tentative {}/{}): {}",
+                    publication.topic, tentative, TENTATIVES_ENREGISTREMENT, e
+                );
+            }
+            Ok(Err(e)) => {
+                error!("Erreur de connexion à la base de données (tentative {}/{}): {}", tentative, TENTATIVES_ENREGISTREMENT, e);
+            }
+            Err(e) => {
+                error!("Erreur lors de l'exécution de la requête (tentative {}/{}): {}", tentative, TENTATIVES_ENREGISTREMENT, e);
+            }
+        }
+
+        if tentative < TENTATIVES_ENREGISTREMENT {
+            rt::time::sleep(delai).await;
+            delai *= 2;
+        }
+    }
+    false
+}
+
+/// Tâche de fond abonnée aux pesées publiées sur le broker MQTT, lancée au démarrage du serveur.
+/// Les messages sont traités un à un : tant qu'une pesée est en cours d'enregistrement, le broker n'est plus
+/// lu et les messages s'accumulent de son côté. La connexion est rétablie en cas de perte, avec une session
+/// persistante pour que les messages non acquittés soient de nouveau délivrés ; elle est aussi coupée
+/// lorsqu'une pesée n'a pu être enregistrée, le broker ne délivrant de nouveau un message qu'à la reconnexion
+pub async fn souscrire(pool: Pool, config: ConfigMqtt) {
+    let mut options = MqttOptions::new(&config.identifiant_client, &config.hote, config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_clean_session(false);
+    options.set_manual_acks(true);
+    if let (Some(utilisateur), Some(mot_de_passe)) = (&config.utilisateur, &config.mot_de_passe) {
+        options.set_credentials(utilisateur, mot_de_passe);
+    }
+
+    let (client, mut eventloop) = AsyncClient::new(options, config.capacite);
+    let mut delai = DELAI_RECONNEXION_MIN;
+
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                info!("Connecté au broker MQTT {}:{}", config.hote, config.port);
+                delai = DELAI_RECONNEXION_MIN;
+                for sujet in &config.sujets {
+                    if let Err(e) = client.try_subscribe(sujet, QoS::AtLeastOnce) {
+                        error!("Erreur lors de l'abonnement au sujet MQTT {}: {}", sujet, e);
+                    }
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publication))) => {
+                if !traiter_publication(&pool, &publication).await {
+                    warn!(
+                        "Pesée reçue sur {} non enregistrée, reconnexion au broker pour qu'il la délivre de nouveau",
+                        publication.topic
+                    );
+                    eventloop.clean();
+                } else if let Err(e) = client.try_ack(&publication) {
+                    error!("Erreur lors de l'acquittement du message MQTT: {}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!(
+                    "Connexion au broker MQTT {}:{} perdue ({}), nouvelle tentative dans {} s",
+                    config.hote,
+                    config.port,
+                    e,
+                    delai.as_secs()
+                );
+                rt::time::sleep(delai).await;
+                delai = (delai * 2).min(DELAI_RECONNEXION_MAX);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::establish_connection_pool;
+    use crate::schema::poids;
+    use rumqttc::MqttOptions;
+    use std::env;
+
+    #[test]
+    fn lit_la_ruche_et_le_rucher_du_sujet() {
+        let sujet = SujetRuche::depuis_sujet("apiary/3/hive/12/weight").unwrap();
+        assert_eq!(sujet.id_rucher, Some(3));
+        assert_eq!(sujet.id_ruche, 12);
+
+        // Le rucher est facultatif et les segments peuvent être précédés d'un préfixe
+        let sujet = SujetRuche::depuis_sujet("balances/hive/7/weight").unwrap();
+        assert_eq!(sujet.id_rucher, None);
+        assert_eq!(sujet.id_ruche, 7);
+    }
+
+    #[test]
+    fn ignore_un_sujet_mal_forme() {
+        for sujet in ["apiary/3/weight", "apiary/3/hive", "apiary/3/hive/douze/weight", "apiary/x/hive/12/weight", ""] {
+            assert!(SujetRuche::depuis_sujet(sujet).is_none(), "{}", sujet);
+        }
+    }
+
+    #[test]
+    fn lit_une_pesee() {
+        let Ok(MesureMqtt::Poids(poids)) = serde_json::from_str::<MesureMqtt>("42350") else {
+            panic!("poids seul illisible");
+        };
+        assert_eq!(poids, 42350);
+
+        let Ok(MesureMqtt::Detaillee { poids, date_mesure }) =
+            serde_json::from_str::<MesureMqtt>(r#"{"poids": 42350, "date_mesure": "2026-10-18"}"#)
+        else {
+            panic!("pesée détaillée illisible");
+        };
+        assert_eq!(poids, 42350);
+        assert_eq!(date_mesure, chrono::NaiveDate::from_ymd_opt(2026, 10, 18));
+
+        let Ok(MesureMqtt::Detaillee { date_mesure, .. }) = serde_json::from_str::<MesureMqtt>(r#"{"poids": 42350}"#)
+        else {
+            panic!("pesée sans date illisible");
+        };
+        assert_eq!(date_mesure, None);
+    }
+
+    #[test]
+    fn refuse_une_pesee_illisible() {
+        for charge in ["", "42.5", "\"42350\"", r#"{"poids": "lourd"}"#, r#"{"date_mesure": "2026-10-18"}"#, "-"] {
+            assert!(serde_json::from_str::<MesureMqtt>(charge).is_err(), "{}", charge);
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite un broker MQTT local (MQTT_BROKER_HOST, localhost par défaut) et une base PostgreSQL migrée (DATABASE_URL)"]
+    async fn une_pesee_publiee_est_enregistree() {
+        dotenv::dotenv().ok();
+        let pool = establish_connection_pool(&env::var("DATABASE_URL").expect("DATABASE_URL doit être défini")).unwrap();
+        let hote = env::var("MQTT_BROKER_HOST").unwrap_or_else(|_| "localhost".to_string());
+        let port = env::var("MQTT_BROKER_PORT").map_or(1883, |port| port.parse::<u16>().unwrap());
+
+        let mut conn = pool.get().unwrap();
+        let ruche_id = diesel::insert_into(ruche::table)
+            .default_values()
+            .returning(ruche::id)
+            .get_result::<i32>(&mut conn)
+            .unwrap();
+        let sujet = format!("happytobees-test/hive/{}/weight", ruche_id);
+
+        rt::spawn(souscrire(pool.clone(), ConfigMqtt {
+            hote: hote.clone(),
+            port,
+            identifiant_client: format!("happytobees-test-{}", ruche_id),
+            utilisateur: None,
+            mot_de_passe: None,
+            sujets: vec![sujet.clone()],
+            capacite: 10,
+        }));
+        let (client, mut eventloop) =
+            AsyncClient::new(MqttOptions::new(format!("happytobees-test-pub-{}", ruche_id), hote, port), 10);
+        rt::spawn(async move {
+            loop {
+                if eventloop.poll().await.is_err() {
+                    rt::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+        });
+
+        // Une publication antérieure à l'abonnement est perdue : elle est répétée jusqu'à l'enregistrement
+        let mut pesees = Vec::new();
+        for _ in 0..10 {
+            client.publish(&sujet, QoS::AtLeastOnce, false, r#"{"poids": 41234}"#).await.unwrap();
+            rt::time::sleep(Duration::from_secs(1)).await;
+            pesees = poids::table
+                .filter(poids::id_ruche.eq(ruche_id))
+                .select(poids::poids_ruche)
+                .load::<Option<i32>>(&mut conn)
+                .unwrap();
+            if !pesees.is_empty() {
+                break;
+            }
+        }
+
+        diesel::delete(poids::table.filter(poids::id_ruche.eq(ruche_id))).execute(&mut conn).unwrap();
+        diesel::delete(ruche::table.find(ruche_id)).execute(&mut conn).unwrap();
+        assert!(!pesees.is_empty(), "aucune pesée enregistrée");
+        assert!(pesees.iter().all(|poids| *poids == Some(41234)));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::db::DbConnection;
+use crate::models::alerte_models::{Alerte, NewAlerte, ALERTE_RESERVES};
+use crate::models::nourrissement_models::{
+    Nourrissement, NewNourrissement, TypeNourriture, SeuilReserve, NewSeuilReserve, ReserveHivernale,
+};
+use crate::schema::{nourrissement, poids, seuil_reserve, type_nourriture};
+use crate::services::alerte_service;
+use crate::services::poids_service::tare_ruche;
+use chrono::{Datelike, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+// Poids estimé des abeilles, du couvain et des cires d'une colonie hivernante, en grammes
+const POIDS_COLONIE: i32 = 6000;
+
+/// Récupère les nourrissements d'une ruche, du plus récent au plus ancien
+pub fn get_nourrissements_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Vec<Nourrissement>, Error> {
+    nourrissement::table
+        .filter(nourrissement::id_ruche.eq(ruche_id))
+        .order((nourrissement::date_nourrissement.desc(), nourrissement::id.desc()))
+        .load::<Nourrissement>(conn)
+}
+
+/// Enregistre un nourrissement puis réévalue les réserves de la ruche
+pub fn create_nourrissement(conn: &mut DbConnection, new_nourrissement: NewNourrissement) -> Result<Nourrissement, Error> {
+    conn.transaction(|conn| {
+        let nourrissement = diesel::insert_into(nourrissement::table)
+            .values(&new_nourrissement)
+            .get_result::<Nourrissement>(conn)?;
+
+        verifier_reserves(conn, nourrissement.id_ruche)?;
+        Ok(nourrissement)
+    })
+}
+
+/// Supprime un nourrissement
+pub fn delete_nourrissement(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(nourrissement::table.find(id)).execute(conn)
+}
+
+/// Récupère les types de nourriture
+pub fn get_all_types_nourriture(conn: &mut DbConnection) -> Result<Vec<TypeNourriture>, Error> {
+    type_nourriture::table
+        .order(type_nourriture::code)
+        .load::<TypeNourriture>(conn)
+}
+
+/// Récupère tous les seuils de réserves
+pub fn get_all_seuils(conn: &mut DbConnection) -> Result<Vec<SeuilReserve>, Error> {
+    seuil_reserve::table
+        .order(seuil_reserve::mois_debut)
+        .load::<SeuilReserve>(conn)
+}
+
+/// Seuil de réserves applicable pour un mois (le plus exigeant si plusieurs saisons se chevauchent)
+pub fn get_seuil_applicable(conn: &mut DbConnection, mois: i32) -> Result<Option<SeuilReserve>, Error> {
+    use crate::schema::seuil_reserve::dsl;
+
+    dsl::seuil_reserve
+        .filter(
+            dsl::mois_debut.le(dsl::mois_fin)
+                .and(dsl::mois_debut.le(mois))
+                .and(dsl::mois_fin.ge(mois))
+                .or(dsl::mois_debut.gt(dsl::mois_fin)
+                    .and(dsl::mois_debut.le(mois).or(dsl::mois_fin.ge(mois)))),
+        )
+        .order(dsl::seuil.desc())
+        .first::<SeuilReserve>(conn)
+        .optional()
+}
+
+/// Crée un seuil de réserves
+pub fn create_seuil(conn: &mut DbConnection, new_seuil: NewSeuilReserve) -> Result<SeuilReserve, Error> {
+    diesel::insert_into(seuil_reserve::table)
+        .values(&new_seuil)
+        .get_result(conn)
+}
+
+/// Met à jour un seuil de réserves
+pub fn update_seuil(conn: &mut DbConnection, id: i32, updated_seuil: NewSeuilReserve) -> Result<SeuilReserve, Error> {
+    diesel::update(seuil_reserve::table.find(id))
+        .set(&updated_seuil)
+        .get_result(conn)
+}
+
+/// Supprime un seuil de réserves
+pub fn delete_seuil(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(seuil_reserve::table.find(id)).execute(conn)
+}
+
+/// Estime les réserves d'une ruche à une date : dernier poids relevé non écarté, moins la tare de la ruche
+/// et le poids de la colonie, plus les réserves apportées par les nourrissements postérieurs à la pesée
+pub fn estimer_reserves(conn: &mut DbConnection, ruche_id: i32, date: NaiveDate) -> Result<ReserveHivernale, Error> {
+    let pesee = poids::table
+        .filter(poids::id_ruche.eq(ruche_id))
+        .filter(poids::poids_ruche.is_not_null())
+        .filter(poids::exclu.eq(false))
+        .filter(poids::date_creation.le(date))
+        .order((poids::date_creation.desc(), poids::horodatage.desc(), poids::id.desc()))
+        .select((poids::date_creation, poids::poids_ruche))
+        .first::<(Option<NaiveDate>, Option<i32>)>(conn)
+        .optional()?;
+    let (date_pesee, poids_ruche) = pesee.unwrap_or((None, None));
+
+    // Tare à la date de la pesée, les hausses ayant pu être retirées depuis
+    let tare_materiel = diesel::select(tare_ruche(Some(ruche_id), Some(date_pesee.unwrap_or(date))))
+        .get_result::<Option<i32>>(conn)?
+        .unwrap_or(0);
+
+    // Les nourrissements du jour de la pesée sont supposés déjà pesés
+    let apports_nourrissement = match date_pesee {
+        Some(date_pesee) => nourrissement::table
+            .inner_join(type_nourriture::table)
+            .filter(nourrissement::id_ruche.eq(ruche_id))
+            .filter(nourrissement::date_nourrissement.gt(date_pesee))
+            .filter(nourrissement::date_nourrissement.le(date))
+            .select((nourrissement::quantite, type_nourriture::facteur_reserve))
+            .load::<(i32, f64)>(conn)?
+            .into_iter()
+            .map(|(quantite, facteur)| quantite as f64 * facteur)
+            .sum::<f64>()
+            .round() as i32,
+        None => 0,
+    };
+
+    // Calcul en i64 pour ne pas déborder sur des poids aberrants, le résultat étant borné à i32::MAX
+    let reserves_estimees = poids_ruche.map(|poids_ruche| {
+        let reserves = i64::from(poids_ruche) - i64::from(tare_materiel) - i64::from(POIDS_COLONIE)
+            + i64::from(apports_nourrissement);
+        i32::try_from(reserves.max(0)).unwrap_or(i32::MAX)
+    });
+    let seuil = get_seuil_applicable(conn, date.month() as i32)?;
+    let sous_le_seuil = match (&seuil, reserves_estimees) {
+        (Some(seuil), Some(reserves)) => reserves < seuil.seuil,
+        _ => false,
+    };
+
+    Ok(ReserveHivernale {
+        id_ruche: ruche_id,
+        date_pesee,
+        poids_ruche,
+        tare_materiel,
+        poids_colonie: POIDS_COLONIE,
+        apports_nourrissement,
+        reserves_estimees,
+        seuil,
+        sous_le_seuil,
+    })
+}
+
+/// Lève une alerte lorsque les réserves estimées du jour passent sous le seuil de la saison,
+/// et résout l'alerte ouverte lorsqu'elles le dépassent de nouveau
+pub fn verifier_reserves(conn: &mut DbConnection, ruche_id: i32) -> Result<Option<Alerte>, Error> {
+    let reserve = estimer_reserves(conn, ruche_id, Utc::now().date_naive())?;
+
+    match (&reserve.seuil, reserve.reserves_estimees) {
+        (Some(seuil), Some(reserves)) if reserve.sous_le_seuil => {
+            let alerte = alerte_service::lever_alerte(conn, NewAlerte {
+                id_ruche: Some(ruche_id),
+                type_alerte: ALERTE_RESERVES.to_string(),
+                message: format!(
+                    "Réserves estimées à {} g, sous le seuil {} de {} g",
+                    reserves,
+                    seuil.libelle.as_deref().unwrap_or("de saison"),
+                    seuil.seuil
+                ),
+                valeur: Some(reserves as f64),
+                seuil: Some(seuil.seuil as f64),
+                id_appareil: None,
+                id_apiculteur: None,
+            })?;
+            Ok(Some(alerte))
+        }
+        (_, Some(_)) => {
+            alerte_service::resoudre_alertes(conn, ruche_id, ALERTE_RESERVES)?;
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connexion_de_test;
+    use crate::models::poids_models::NewPoids;
+    use crate::schema::ruche;
+    use crate::services::poids_service;
+
+    #[test]
+    #[ignore = "nécessite une base PostgreSQL migrée (DATABASE_URL)"]
+    fn une_pesee_ecartee_est_ignoree() {
+        let mut conn = connexion_de_test();
+        conn.test_transaction::<_, Error, _>(|conn| {
+            let ruche_id = diesel::insert_into(ruche::table)
+                .default_values()
+                .returning(ruche::id)
+                .get_result::<i32>(conn)?;
+            let veille = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+            let jour = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+
+            let retenue = poids_service::create_poids(conn, NewPoids {
+                id_ruche: Some(ruche_id),
+                poids_ruche: Some(40000),
+                date_creation: Some(veille),
+                horodatage: veille.and_hms_opt(18, 0, 0),
+            })?;
+            let aberrante = poids_service::create_poids(conn, NewPoids {
+                id_ruche: Some(ruche_id),
+                poids_ruche: Some(-5000),
+                date_creation: Some(jour),
+                horodatage: jour.and_hms_opt(8, 0, 0),
+            })?;
+            assert!(aberrante.exclu);
+
+            let reserve = estimer_reserves(conn, ruche_id, jour)?;
+            assert_eq!(reserve.date_pesee, Some(veille));
+            assert_eq!(reserve.poids_ruche, Some(40000));
+
+            let derniere = poids_service::get_last_poids_by_ruche_id(conn, ruche_id)?;
+            assert_eq!(derniere.map(|poids| poids.id), Some(retenue.id));
+            Ok(())
+        });
+    }
+}
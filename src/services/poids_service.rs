@@ -1,11 +1,22 @@
 use crate::db::DbConnection;
-use crate::models::poids_models::{Poids, NewPoids, UpdatePoids};
-use crate::schema::poids;
+use crate::models::poids_models::{
+    Poids, NewPoids, UpdatePoids, PoidsNet, TypePoids, MoyennePeriode, CorrectionPoids, NewCorrectionPoids,
+    PoidsJour, VariationJour,
+};
+use crate::models::releve_capteur_models::{RetentionMesures, SERIE_POIDS};
+use crate::schema::{correction_poids, poids, ruche};
+use crate::services::{anomalie_poids_service, meteo_service, nourrissement_service, releve_capteur_service, ruche_service};
 use diesel::prelude::*;
 use diesel::result::Error;
-use chrono::{NaiveDate, Datelike};
+use diesel::sql_types::{Date, Integer, Nullable, Text};
+use chrono::{NaiveDate, Utc};
 use std::collections::HashMap;
 
+diesel::define_sql_function! {
+    /// Tare de la ruche à une date (matériel fixe, hausses posées et hausses non suivies), en grammes
+    fn tare_ruche(ruche_id: Nullable<Integer>, jour: Nullable<Date>) -> Nullable<Integer>;
+}
+
 /// Récupère tous les poids
 pub fn get_all_poids(conn: &mut DbConnection) -> Result<Vec<Poids>, Error> {
     poids::table.load::<Poids>(conn)
@@ -16,23 +27,128 @@ pub fn get_poids_by_id(conn: &mut DbConnection, id: i32) -> Result<Poids, Error>
     poids::table.find(id).first::<Poids>(conn)
 }
 
-/// Crée un nouveau poids
+// Détecte les anomalies des pesées de la ruche autour d'une pesée nouvelle ou corrigée et réévalue ses réserves
+fn apres_pesee(conn: &mut DbConnection, poids: &mut Poids) -> Result<(), Error> {
+    let Some(ruche_id) = poids.id_ruche else {
+        return Ok(());
+    };
+
+    let anomalies = anomalie_poids_service::detecter_anomalies(conn, ruche_id, poids.horodatage)?;
+    poids.exclu |= anomalies.iter().any(|anomalie| anomalie.id_poids == poids.id);
+    nourrissement_service::verifier_reserves(conn, ruche_id)?;
+    Ok(())
+}
+
+/// Crée un nouveau poids, détecte les anomalies qu'il révèle puis réévalue les réserves de la ruche
 pub fn create_poids(conn: &mut DbConnection, new_poids: NewPoids) -> Result<Poids, Error> {
-    diesel::insert_into(poids::table)
-        .values(&new_poids)
-        .get_result(conn)
+    conn.transaction(|conn| {
+        let mut poids = diesel::insert_into(poids::table)
+            .values(&new_poids)
+            .get_result::<Poids>(conn)?;
+
+        apres_pesee(conn, &mut poids)?;
+        Ok(poids)
+    })
+}
+
+/// Enregistre une pesée saisie par un apiculteur sur l'une de ses ruches (`NotFound` sinon)
+pub fn create_poids_apiculteur(conn: &mut DbConnection, new_poids: NewPoids, apiculteur_id: i32) -> Result<Poids, Error> {
+    let ruche_id = new_poids.id_ruche.ok_or(Error::NotFound)?;
+    ruche_service::verifier_proprietaire(conn, ruche_id, apiculteur_id)?;
+    create_poids(conn, new_poids)
+}
+
+/// Met à jour un poids existant en conservant la correction dans son historique, avec son motif,
+/// puis détecte les anomalies et réévalue les réserves de la ruche. Une pesée déjà agrégée est retirée de ses
+/// agrégats puis agrégée de nouveau avec ses valeurs corrigées
+pub fn update_poids(
+    conn: &mut DbConnection,
+    id: i32,
+    updated_poids: UpdatePoids,
+    motif: Option<String>,
+    retention: &RetentionMesures,
+) -> Result<Poids, Error> {
+    conn.transaction(|conn| {
+        let ancien = poids::table.find(id).first::<Poids>(conn)?;
+        releve_capteur_service::retirer_pesee_des_agregats(conn, &ancien)?;
+        let mut poids = diesel::update(poids::table.find(id))
+            .set((&updated_poids, poids::agrege.eq(false)))
+            .get_result::<Poids>(conn)?;
+
+        if (ancien.id_ruche, ancien.poids_ruche, ancien.horodatage) != (poids.id_ruche, poids.poids_ruche, poids.horodatage) {
+            diesel::insert_into(correction_poids::table)
+                .values(NewCorrectionPoids {
+                    id_poids: id,
+                    ancienne_ruche: ancien.id_ruche,
+                    nouvelle_ruche: poids.id_ruche,
+                    ancien_poids: ancien.poids_ruche,
+                    nouveau_poids: poids.poids_ruche,
+                    ancien_horodatage: ancien.horodatage,
+                    nouvel_horodatage: poids.horodatage,
+                    motif,
+                })
+                .execute(conn)?;
+        }
+
+        apres_pesee(conn, &mut poids)?;
+        if ancien.agrege {
+            releve_capteur_service::agreger_mesures_en_attente(conn, retention)?;
+            poids.agrege = poids::table.find(id).select(poids::agrege).first(conn)?;
+        }
+        Ok(poids)
+    })
 }
 
-/// Met à jour un poids existant
-pub fn update_poids(conn: &mut DbConnection, id: i32, updated_poids: UpdatePoids) -> Result<Poids, Error> {
-    diesel::update(poids::table.find(id))
-        .set(&updated_poids)
-        .get_result(conn)
+// Vérifie que la pesée porte sur une ruche de l'apiculteur (`NotFound` sinon)
+fn verifier_pesee_apiculteur(conn: &mut DbConnection, id: i32, apiculteur_id: i32) -> Result<(), Error> {
+    let ruche_id = poids::table.find(id).select(poids::id_ruche).first::<Option<i32>>(conn)?.ok_or(Error::NotFound)?;
+    ruche_service::verifier_proprietaire(conn, ruche_id, apiculteur_id)
 }
 
-/// Supprime un poids
+/// Corrige une pesée d'une ruche d'un apiculteur, éventuellement en la déplaçant sur une autre de ses ruches
+/// (`NotFound` sinon)
+pub fn update_poids_apiculteur(
+    conn: &mut DbConnection,
+    id: i32,
+    updated_poids: UpdatePoids,
+    motif: Option<String>,
+    retention: &RetentionMesures,
+    apiculteur_id: i32,
+) -> Result<Poids, Error> {
+    conn.transaction(|conn| {
+        verifier_pesee_apiculteur(conn, id, apiculteur_id)?;
+        if let Some(ruche_id) = updated_poids.id_ruche {
+            ruche_service::verifier_proprietaire(conn, ruche_id, apiculteur_id)?;
+        }
+        update_poids(conn, id, updated_poids, motif, retention)
+    })
+}
+
+/// Récupère l'historique des corrections d'une pesée, de la plus ancienne à la plus récente
+pub fn get_corrections_by_poids_id(conn: &mut DbConnection, poids_id: i32) -> Result<Vec<CorrectionPoids>, Error> {
+    correction_poids::table
+        .filter(correction_poids::id_poids.eq(poids_id))
+        .order(correction_poids::id.asc())
+        .load::<CorrectionPoids>(conn)
+}
+
+/// Supprime un poids, en le retirant de ses agrégats s'il y était déjà compté
 pub fn delete_poids(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
-    diesel::delete(poids::table.find(id)).execute(conn)
+    conn.transaction(|conn| {
+        let Some(pesee) = poids::table.find(id).first::<Poids>(conn).optional()? else {
+            return Ok(0);
+        };
+        releve_capteur_service::retirer_pesee_des_agregats(conn, &pesee)?;
+        diesel::delete(poids::table.find(id)).execute(conn)
+    })
+}
+
+/// Supprime une pesée d'une ruche d'un apiculteur (`NotFound` sinon)
+pub fn delete_poids_apiculteur(conn: &mut DbConnection, id: i32, apiculteur_id: i32) -> Result<usize, Error> {
+    conn.transaction(|conn| {
+        verifier_pesee_apiculteur(conn, id, apiculteur_id)?;
+        delete_poids(conn, id)
+    })
 }
 
 /// Récupère les poids d'une ruche spécifique
@@ -53,102 +169,204 @@ pub fn get_poids_by_date_range(
         .load::<Poids>(conn)
 }
 
-/// Récupère le dernier poids enregistré pour une ruche
+/// Récupère le dernier poids enregistré pour une ruche, hors pesées écartées
 pub fn get_last_poids_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Option<Poids>, Error> {
     poids::table
         .filter(poids::id_ruche.eq(ruche_id))
-        .order(poids::date_creation.desc())
+        .filter(poids::exclu.eq(false))
+        .order((poids::date_creation.desc(), poids::horodatage.desc(), poids::id.desc()))
         .first::<Poids>(conn)
         .optional()
 }
 
-pub fn get_annual_average_weight(
+/// Récupère la dernière pesée retenue et renseignée de chaque ruche d'un apiculteur, avec le nom de la ruche
+pub fn get_derniers_poids_by_apiculteur(conn: &mut DbConnection, apiculteur_id: i32) -> Result<Vec<(Poids, Option<String>)>, Error> {
+    poids::table
+        .inner_join(ruche::table.on(poids::id_ruche.eq(ruche::id.nullable())))
+        .filter(ruche::id_apiculteur.eq(apiculteur_id))
+        .filter(poids::exclu.eq(false))
+        .filter(poids::poids_ruche.is_not_null())
+        .distinct_on(poids::id_ruche)
+        .order((
+            poids::id_ruche,
+            poids::date_creation.desc().nulls_last(),
+            poids::horodatage.desc().nulls_last(),
+            poids::id.desc(),
+        ))
+        .select((Poids::as_select(), ruche::nom_ruche))
+        .load::<(Poids, Option<String>)>(conn)
+}
+
+/// Récupère les pesées d'une ruche avec leur tare et leur poids net
+pub fn get_poids_net_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Vec<PoidsNet>, Error> {
+    use crate::schema::poids::dsl::*;
+
+    poids
+        .filter(id_ruche.eq(ruche_id))
+        .order((date_creation.asc(), id.asc()))
+        .select((
+            id,
+            id_ruche,
+            date_creation,
+            poids_ruche,
+            tare_ruche(id_ruche, date_creation),
+            poids_ruche - tare_ruche(id_ruche, date_creation),
+        ))
+        .load::<PoidsNet>(conn)
+}
+
+// Moyenne des pesées d'une ruche par {periode} (year ou month) entre $2 (inclus) et $3 (exclu), brute ou nette
+// de la tare ({tare}), hors pesées écartées pour anomalie. Les pesées sont d'abord regroupées par jour, afin de
+// ne calculer la tare qu'une fois par jour. Les jours antérieurs à $4, dont les pesées ont été élaguées, sont
+// lus dans les agrégats journaliers, à l'exception des pesées non horodatées qui ne sont ni agrégées ni élaguées
+const MOYENNES_POIDS_SQL: &str = "
+    WITH jours AS (
+        SELECT date_creation AS jour, sum(poids_ruche)::float8 AS somme, count(*) AS nombre
+        FROM poids
+        WHERE id_ruche = $1 AND date_creation >= $2 AND date_creation < $3 AND poids_ruche IS NOT NULL AND NOT exclu
+          AND (date_creation >= $4 OR horodatage IS NULL)
+        GROUP BY date_creation
+        UNION ALL
+        SELECT debut::date, somme, nombre_mesures::int8
+        FROM mesure_journaliere
+        WHERE id_ruche = $1 AND type_mesure = $5 AND debut >= $2 AND debut < $3 AND debut < $4
+    )
+    SELECT date_part('{periode}', jour)::int4 AS periode, ((sum(somme) - {tare}) / sum(nombre))::float8 AS moyenne
+    FROM jours
+    GROUP BY 1";
+
+// Moyennes des pesées d'une ruche par année ou par mois, pour les périodes comportant des pesées
+fn moyennes_poids(
     conn: &mut DbConnection,
     ruche_id: i32,
-    year: i32
-) -> Result<f64, Error> {
-    use crate::schema::poids::dsl::*;
+    periode: &str,
+    debut: NaiveDate,
+    fin: NaiveDate,
+    type_poids: TypePoids,
+    retention: &RetentionMesures
+) -> Result<Vec<MoyennePeriode>, Error> {
+    let limite_brute = retention.limite_brute(Utc::now().date_naive()).date();
 
-    let start_date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
-    let end_date = NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap();
+    let tare = match type_poids {
+        TypePoids::Brut => "0",
+        TypePoids::Net => "sum(nombre * tare_ruche($1, jour))",
+    };
 
-    let poids_records = poids
-        .filter(id_ruche.eq(ruche_id))
-        .filter(date_creation.ge(start_date))
-        .filter(date_creation.lt(end_date))
-        .select((poids_ruche, date_creation))
-        .load::<(Option<i32>, Option<NaiveDate>)>(conn)?;
-
-    if poids_records.is_empty() {
-        return Ok(0.0);
-    }
-
-    let mut total_weight = 0;
-    let mut count = 0;
-
-    for (weight, _date) in poids_records {
-        if let Some(w) = weight {
-            total_weight += w;
-            count += 1;
-        }
-    }
+    diesel::sql_query(MOYENNES_POIDS_SQL.replace("{periode}", periode).replace("{tare}", tare))
+        .bind::<Integer, _>(ruche_id)
+        .bind::<Date, _>(debut)
+        .bind::<Date, _>(fin)
+        .bind::<Date, _>(limite_brute)
+        .bind::<Text, _>(SERIE_POIDS)
+        .load::<MoyennePeriode>(conn)
+}
+
+// Premier jour d'une année
+fn debut_annee(year: i32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, 1, 1).unwrap()
+}
 
-    if count > 0 {
-        Ok(total_weight as f64 / count as f64)
-    } else {
-        Ok(0.0)
-    }
+pub fn get_annual_average_weight(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    year: i32,
+    type_poids: TypePoids,
+    retention: &RetentionMesures
+) -> Result<f64, Error> {
+    let moyennes = moyennes_poids(conn, ruche_id, "year", debut_annee(year), debut_annee(year + 1), type_poids, retention)?;
+    Ok(moyennes.first().map_or(0.0, |moyenne| moyenne.moyenne))
 }
 
 pub fn get_annual_average_weight_by_month(
     conn: &mut DbConnection,
     ruche_id: i32,
-    year: i32
+    year: i32,
+    type_poids: TypePoids,
+    retention: &RetentionMesures
 ) -> Result<HashMap<u32, f64>, Error> {
-    use crate::schema::poids::dsl::*;
-
-    let start_date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
-    let end_date = NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap();
+    let moyennes = moyennes_poids(conn, ruche_id, "month", debut_annee(year), debut_annee(year + 1), type_poids, retention)?;
+    Ok(moyennes
+        .into_iter()
+        .map(|moyenne| (moyenne.periode as u32, moyenne.moyenne))
+        .collect())
+}
 
-    let poids_records = poids
-        .filter(id_ruche.eq(ruche_id))
-        .filter(date_creation.ge(start_date))
-        .filter(date_creation.lt(end_date))
-        .select((poids_ruche, date_creation))
-        .load::<(Option<i32>, Option<NaiveDate>)>(conn)?;
-
-    let mut monthly_weights: HashMap<u32, Vec<i32>> = HashMap::new();
-
-    // Regrouper les poids par mois
-    for (weight, date) in poids_records {
-        if let (Some(w), Some(d)) = (weight, date) {
-            let month = d.month();
-            monthly_weights.entry(month).or_insert_with(Vec::new).push(w);
-        }
-    }
+pub fn get_weight_evolution(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    years: Vec<i32>,
+    type_poids: TypePoids,
+    retention: &RetentionMesures
+) -> Result<HashMap<i32, f64>, Error> {
+    let (Some(&premiere), Some(&derniere)) = (years.iter().min(), years.iter().max()) else {
+        return Ok(HashMap::new());
+    };
 
-    // Calculer la moyenne pour chaque mois
-    let mut result: HashMap<u32, f64> = HashMap::new();
-    for (month, weights) in monthly_weights {
-        let sum: i32 = weights.iter().sum();
-        let count = weights.len() as f64;
-        result.insert(month, sum as f64 / count);
-    }
+    // Une seule requête couvre toutes les années demandées, une année sans pesée valant 0
+    let moyennes: HashMap<i32, f64> = moyennes_poids(conn, ruche_id, "year", debut_annee(premiere), debut_annee(derniere + 1), type_poids, retention)?
+        .into_iter()
+        .map(|moyenne| (moyenne.periode, moyenne.moyenne))
+        .collect();
 
-    Ok(result)
+    Ok(years
+        .into_iter()
+        .map(|year| (year, moyennes.get(&year).copied().unwrap_or(0.0)))
+        .collect())
 }
 
-pub fn get_weight_evolution(
+// Dernière pesée de chaque jour d'une ruche entre $2 et $3 inclus, brute ou nette de la tare ({tare}), hors pesées
+// écartées pour anomalie, avec sa variation depuis la veille. La veille de $2 est lue pour la variation du premier jour
+const VARIATIONS_POIDS_SQL: &str = "
+    WITH jours AS (
+        SELECT DISTINCT ON (date_creation) date_creation AS jour, poids_ruche - {tare} AS poids
+        FROM poids
+        WHERE id_ruche = $1 AND date_creation >= $2 - 1 AND date_creation <= $3 AND poids_ruche IS NOT NULL AND NOT exclu
+        ORDER BY date_creation, horodatage DESC, id DESC
+    ),
+    variations AS (
+        SELECT jour, poids,
+               CASE WHEN LAG(jour) OVER (ORDER BY jour) = jour - 1 THEN poids - LAG(poids) OVER (ORDER BY jour) END AS variation
+        FROM jours
+    )
+    SELECT jour AS date, poids, variation
+    FROM variations
+    WHERE jour >= $2
+    ORDER BY jour";
+
+/// Variation journalière du poids d'une ruche entre deux jours inclus, avec la météo observée chaque jour
+/// à son rucher. `NotFound` si la ruche n'existe pas
+pub fn get_variations_journalieres(
     conn: &mut DbConnection,
     ruche_id: i32,
-    years: Vec<i32>
-) -> Result<HashMap<i32, f64>, Error> {
-    let mut result: HashMap<i32, f64> = HashMap::new();
+    debut: NaiveDate,
+    fin: NaiveDate,
+    type_poids: TypePoids
+) -> Result<Vec<VariationJour>, Error> {
+    let rucher_id = ruche::table
+        .find(ruche_id)
+        .select(ruche::id_rucher)
+        .first::<Option<i32>>(conn)?;
+
+    let tare = match type_poids {
+        TypePoids::Brut => "0",
+        TypePoids::Net => "tare_ruche($1, date_creation)",
+    };
+    let jours = diesel::sql_query(VARIATIONS_POIDS_SQL.replace("{tare}", tare))
+        .bind::<Integer, _>(ruche_id)
+        .bind::<Date, _>(debut)
+        .bind::<Date, _>(fin)
+        .load::<PoidsJour>(conn)?;
 
-    for year in years {
-        let avg = get_annual_average_weight(conn, ruche_id, year)?;
-        result.insert(year, avg);
-    }
+    let mut meteo: HashMap<NaiveDate, _> = match rucher_id {
+        Some(rucher_id) => meteo_service::get_observations_by_rucher_id(conn, rucher_id, Some(debut), Some(fin))?
+            .into_iter()
+            .map(|observation| (observation.date_observation, observation))
+            .collect(),
+        None => HashMap::new(),
+    };
 
-    Ok(result)
+    Ok(jours
+        .into_iter()
+        .map(|poids| VariationJour { meteo: meteo.remove(&poids.date), poids })
+        .collect())
 }
\ No newline at end of file
@@ -1,8 +1,14 @@
 use crate::db::DbConnection;
-use crate::models::production_models::{Production, NewProduction, UpdateProduction};
-use crate::schema::production;
+use crate::models::production_models::{
+    Production, NewProduction, UpdateProduction, UniteMesure, TotalProduction, StatistiquesProduction,
+    Perimetre, StatistiqueAnnuelle, StatistiqueSaisonniere, ProductionRuche, ClassementAnnuel, ProductionEnregistree,
+};
+use crate::schema::{production, unite_mesure};
+use crate::services::traitement_service;
 use diesel::prelude::*;
 use diesel::result::Error;
+use diesel::sql_types::{BigInt, Date, Integer, Nullable, Varchar};
+use chrono::NaiveDate;
 
 /// Récupère toutes les productions
 pub fn get_all_productions(conn: &mut DbConnection) -> Result<Vec<Production>, Error> {
@@ -14,18 +20,38 @@ pub fn get_production_by_id(conn: &mut DbConnection, id: i32) -> Result<Producti
     production::table.find(id).first::<Production>(conn)
 }
 
-/// Crée une nouvelle production
-pub fn create_production(conn: &mut DbConnection, new_production: NewProduction) -> Result<Production, Error> {
-    diesel::insert_into(production::table)
-        .values(&new_production)
-        .get_result(conn)
+// Production accompagnée des traitements dont elle tombe dans le délai d'attente
+fn avec_delais_attente(conn: &mut DbConnection, production: Production) -> Result<ProductionEnregistree, Error> {
+    let delais_attente = traitement_service::get_delais_attente_production(conn, production.id)?;
+    Ok(ProductionEnregistree {
+        production,
+        en_delai_attente: !delais_attente.is_empty(),
+        delais_attente,
+    })
 }
 
-/// Met à jour une production existante
-pub fn update_production(conn: &mut DbConnection, id: i32, updated_production: UpdateProduction) -> Result<Production, Error> {
-    diesel::update(production::table.find(id))
-        .set(&updated_production)
-        .get_result(conn)
+/// Crée une nouvelle production, signalée si elle est datée pendant le délai d'attente d'un traitement
+pub fn create_production(conn: &mut DbConnection, new_production: NewProduction) -> Result<ProductionEnregistree, Error> {
+    conn.transaction(|conn| {
+        let production = diesel::insert_into(production::table)
+            .values(&new_production)
+            .get_result(conn)?;
+        avec_delais_attente(conn, production)
+    })
+}
+
+/// Met à jour une production existante, signalée si elle est datée pendant le délai d'attente d'un traitement
+pub fn update_production(
+    conn: &mut DbConnection,
+    id: i32,
+    updated_production: UpdateProduction
+) -> Result<ProductionEnregistree, Error> {
+    conn.transaction(|conn| {
+        let production = diesel::update(production::table.find(id))
+            .set(&updated_production)
+            .get_result(conn)?;
+        avec_delais_attente(conn, production)
+    })
 }
 
 /// Supprime une production
@@ -52,15 +78,288 @@ pub fn get_productions_by_date_range(
 }
 
 // Statistiques de production
-pub fn get_total_production_by_ruche(conn: &mut DbConnection, ruche_id: i32) -> Result<i32, Error> {
-    use crate::schema::production::dsl::*;
-    use diesel::dsl::sum;
+// Les requêtes portent sur les ruches d'un périmètre ({perimetre}, identifiant en $1).
+// Les totaux sont convertis dans l'unité de base de leur grandeur (g, ml),
+// ou dans l'unité demandée lorsqu'elle mesure la même grandeur
+const TOTAL_PAR_TYPE_SQL: &str = "
+    SELECT p.type_produit,
+           NULL::varchar AS variete_miel,
+           COALESCE(SUM(p.quantite_production * u.facteur), 0) / COALESCE(cible.facteur, 1) AS quantite,
+           COALESCE(cible.code, u.unite_base) AS unite
+    FROM production p
+    JOIN ruche r ON r.id = p.id_ruche
+    JOIN unite_mesure u ON u.code = p.unite
+    LEFT JOIN unite_mesure cible ON cible.code = $2 AND cible.unite_base = u.unite_base
+    WHERE {perimetre} AND ($3::date IS NULL OR p.date_creation >= $3)
+    GROUP BY p.type_produit, u.unite_base, cible.code, cible.facteur
+    ORDER BY p.type_produit, unite";
 
-    production
-        .filter(id_ruche.eq(ruche_id))
-        .select(sum(quantite_production))
-        .first::<Option<i64>>(conn)
-        .map(|result| result.unwrap_or(0) as i32)
+const TOTAL_PAR_VARIETE_SQL: &str = "
+    SELECT p.type_produit,
+           p.variete_miel,
+           COALESCE(SUM(p.quantite_production * u.facteur), 0) / COALESCE(cible.facteur, 1) AS quantite,
+           COALESCE(cible.code, u.unite_base) AS unite
+    FROM production p
+    JOIN ruche r ON r.id = p.id_ruche
+    JOIN unite_mesure u ON u.code = p.unite
+    LEFT JOIN unite_mesure cible ON cible.code = $2 AND cible.unite_base = u.unite_base
+    WHERE {perimetre} AND p.type_produit = 'miel'
+    GROUP BY p.type_produit, p.variete_miel, u.unite_base, cible.code, cible.facteur
+    ORDER BY p.variete_miel NULLS LAST, unite";
+
+// Productions datées d'un type ($2) converties dans l'unité $3, puis cumulées par ruche et par année.
+// Les productions mesurées dans une autre grandeur que l'unité demandée sont ignorées.
+// Les cadres de couvain sont la moyenne des relevés de l'année, à défaut le dernier relevé antérieur
+const PRODUCTION_PAR_RUCHE_CTE: &str = "
+    WITH cible AS (
+        SELECT unite_base, facteur FROM unite_mesure WHERE code = $3
+    ),
+    prod AS (
+        SELECT p.id_ruche,
+               p.date_creation,
+               p.quantite_production * u.facteur / cible.facteur AS quantite
+        FROM production p
+        JOIN ruche r ON r.id = p.id_ruche
+        JOIN unite_mesure u ON u.code = p.unite
+        JOIN cible ON cible.unite_base = u.unite_base
+        WHERE {perimetre}
+          AND p.type_produit = $2
+          AND p.date_creation IS NOT NULL
+          AND p.quantite_production IS NOT NULL
+    ),
+    par_ruche AS (
+        SELECT pr.id_ruche,
+               pr.annee,
+               pr.quantite,
+               COALESCE(releves.cadres, dernier.cadres) AS cadres_couvain
+        FROM (
+            SELECT id_ruche, EXTRACT(YEAR FROM date_creation)::int4 AS annee, SUM(quantite) AS quantite
+            FROM prod
+            GROUP BY id_ruche, annee
+        ) pr
+        LEFT JOIN LATERAL (
+            SELECT AVG(c.nombre_cadre_couvain)::float8 AS cadres
+            FROM configuration_ruche c
+            WHERE c.id_ruche = pr.id_ruche
+              AND c.nombre_cadre_couvain IS NOT NULL
+              AND EXTRACT(YEAR FROM c.date_releve) = pr.annee
+        ) releves ON TRUE
+        LEFT JOIN LATERAL (
+            SELECT c.nombre_cadre_couvain::float8 AS cadres
+            FROM configuration_ruche c
+            WHERE c.id_ruche = pr.id_ruche
+              AND c.nombre_cadre_couvain IS NOT NULL
+              AND c.date_releve < make_date(pr.annee + 1, 1, 1)
+            ORDER BY c.date_releve DESC
+            LIMIT 1
+        ) dernier ON TRUE
+    )";
+
+const PAR_ANNEE_SQL: &str = ",
+    annuel AS (
+        SELECT annee,
+               SUM(quantite) AS quantite,
+               COUNT(*) AS nombre_ruches,
+               AVG(quantite) AS moyenne_par_ruche,
+               percentile_cont(0.5) WITHIN GROUP (ORDER BY quantite) AS mediane_par_ruche,
+               SUM(quantite) FILTER (WHERE cadres_couvain > 0)
+                   / NULLIF(SUM(cadres_couvain) FILTER (WHERE cadres_couvain > 0), 0) AS quantite_par_cadre_couvain
+        FROM par_ruche
+        GROUP BY annee
+    )
+    SELECT a.annee,
+           a.quantite,
+           a.nombre_ruches,
+           a.moyenne_par_ruche,
+           a.mediane_par_ruche,
+           a.quantite_par_cadre_couvain,
+           prec.quantite AS quantite_annee_precedente,
+           (a.quantite - prec.quantite) / NULLIF(prec.quantite, 0) * 100 AS evolution_pourcentage
+    FROM annuel a
+    LEFT JOIN annuel prec ON prec.annee = a.annee - 1
+    ORDER BY a.annee";
+
+// L'hiver regroupe janvier, février et décembre d'une même année civile
+const PAR_SAISON_SQL: &str = "
+    SELECT EXTRACT(YEAR FROM date_creation)::int4 AS annee,
+           CASE
+               WHEN EXTRACT(MONTH FROM date_creation) IN (3, 4, 5) THEN 'printemps'
+               WHEN EXTRACT(MONTH FROM date_creation) IN (6, 7, 8) THEN 'ete'
+               WHEN EXTRACT(MONTH FROM date_creation) IN (9, 10, 11) THEN 'automne'
+               ELSE 'hiver'
+           END AS saison,
+           SUM(quantite) AS quantite
+    FROM prod
+    GROUP BY 1, 2
+    ORDER BY 1, MIN(EXTRACT(MONTH FROM date_creation))";
+
+// Ne conserve que les $4 premières et $4 dernières ruches de chaque année
+const CLASSEMENT_SQL: &str = ",
+    classe AS (
+        SELECT pr.id_ruche,
+               r.nom_ruche,
+               pr.annee,
+               pr.quantite,
+               pr.cadres_couvain,
+               pr.quantite / NULLIF(pr.cadres_couvain, 0) AS quantite_par_cadre_couvain,
+               ROW_NUMBER() OVER (PARTITION BY pr.annee ORDER BY pr.quantite DESC, pr.id_ruche) AS rang,
+               COUNT(*) OVER (PARTITION BY pr.annee) AS nombre_ruches
+        FROM par_ruche pr
+        JOIN ruche r ON r.id = pr.id_ruche
+    )
+    SELECT *
+    FROM classe
+    WHERE rang <= $4 OR rang > nombre_ruches - $4
+    ORDER BY annee, rang";
+
+// Nombre de ruches retenues par défaut parmi les meilleures et les moins bonnes
+const LIMITE_CLASSEMENT: i64 = 3;
+
+fn sql_perimetre(sql: &str, perimetre: Perimetre) -> String {
+    sql.replace("{perimetre}", perimetre.condition())
+}
+
+/// Quantités totales produites sur un périmètre, par type de produit, éventuellement depuis une date
+pub fn get_total_production_by_type(
+    conn: &mut DbConnection,
+    perimetre: Perimetre,
+    id: i32,
+    unite: Option<String>,
+    depuis: Option<NaiveDate>,
+) -> Result<Vec<TotalProduction>, Error> {
+    diesel::sql_query(sql_perimetre(TOTAL_PAR_TYPE_SQL, perimetre))
+        .bind::<Integer, _>(id)
+        .bind::<Nullable<Varchar>, _>(unite)
+        .bind::<Nullable<Date>, _>(depuis)
+        .load::<TotalProduction>(conn)
+}
+
+/// Quantités totales de miel produites sur un périmètre, par variété
+pub fn get_total_production_by_variete(
+    conn: &mut DbConnection,
+    perimetre: Perimetre,
+    id: i32,
+    unite: Option<String>,
+) -> Result<Vec<TotalProduction>, Error> {
+    diesel::sql_query(sql_perimetre(TOTAL_PAR_VARIETE_SQL, perimetre))
+        .bind::<Integer, _>(id)
+        .bind::<Nullable<Varchar>, _>(unite)
+        .load::<TotalProduction>(conn)
+}
+
+/// Production annuelle d'un type de produit : total, moyenne et médiane par ruche,
+/// production par cadre de couvain et évolution par rapport à l'année précédente
+pub fn get_production_by_annee(
+    conn: &mut DbConnection,
+    perimetre: Perimetre,
+    id: i32,
+    type_produit: &str,
+    unite: &str,
+) -> Result<Vec<StatistiqueAnnuelle>, Error> {
+    let sql = sql_perimetre(PRODUCTION_PAR_RUCHE_CTE, perimetre) + PAR_ANNEE_SQL;
+    diesel::sql_query(sql)
+        .bind::<Integer, _>(id)
+        .bind::<Varchar, _>(type_produit)
+        .bind::<Varchar, _>(unite)
+        .load::<StatistiqueAnnuelle>(conn)
+}
+
+/// Production d'un type de produit par année et par saison
+pub fn get_production_by_saison(
+    conn: &mut DbConnection,
+    perimetre: Perimetre,
+    id: i32,
+    type_produit: &str,
+    unite: &str,
+) -> Result<Vec<StatistiqueSaisonniere>, Error> {
+    let sql = sql_perimetre(PRODUCTION_PAR_RUCHE_CTE, perimetre) + PAR_SAISON_SQL;
+    diesel::sql_query(sql)
+        .bind::<Integer, _>(id)
+        .bind::<Varchar, _>(type_produit)
+        .bind::<Varchar, _>(unite)
+        .load::<StatistiqueSaisonniere>(conn)
+}
+
+/// Meilleures et moins bonnes ruches de chaque année
+pub fn get_classement_ruches(
+    conn: &mut DbConnection,
+    perimetre: Perimetre,
+    id: i32,
+    type_produit: &str,
+    unite: &str,
+    limite: i64,
+) -> Result<Vec<ClassementAnnuel>, Error> {
+    let sql = sql_perimetre(PRODUCTION_PAR_RUCHE_CTE, perimetre) + CLASSEMENT_SQL;
+    let lignes = diesel::sql_query(sql)
+        .bind::<Integer, _>(id)
+        .bind::<Varchar, _>(type_produit)
+        .bind::<Varchar, _>(unite)
+        .bind::<BigInt, _>(limite)
+        .load::<ProductionRuche>(conn)?;
+
+    // Les lignes arrivent triées par année puis par rang
+    let mut classement: Vec<ClassementAnnuel> = Vec::new();
+    for ligne in lignes {
+        if classement.last().map(|c| c.annee) != Some(ligne.annee) {
+            classement.push(ClassementAnnuel {
+                annee: ligne.annee,
+                meilleures: Vec::new(),
+                moins_bonnes: Vec::new(),
+            });
+        }
+        let annee = classement.last_mut().unwrap();
+        // Une ruche déjà parmi les meilleures n'est pas reprise parmi les moins bonnes
+        if ligne.rang <= limite {
+            annee.meilleures.push(ligne);
+        } else if ligne.rang > ligne.nombre_ruches - limite {
+            annee.moins_bonnes.insert(0, ligne);
+        }
+    }
+
+    Ok(classement)
+}
+
+/// Statistiques de production d'une ruche, d'un rucher ou d'un apiculteur
+pub fn get_production_statistics(
+    conn: &mut DbConnection,
+    perimetre: Perimetre,
+    id: i32,
+    type_produit: Option<String>,
+    unite: Option<String>,
+    limite: Option<i64>,
+) -> Result<StatistiquesProduction, Error> {
+    let type_produit = type_produit.unwrap_or_else(|| "miel".to_string());
+    let limite = limite.unwrap_or(LIMITE_CLASSEMENT).max(1);
+    // Une unité inconnue est signalée plutôt que de renvoyer des statistiques vides
+    let unite_detail = unite_mesure::table
+        .find(unite.clone().unwrap_or_else(|| "g".to_string()))
+        .first::<UniteMesure>(conn)?
+        .code;
+
+    let par_type = get_total_production_by_type(conn, perimetre, id, unite.clone(), None)?;
+    let par_variete = get_total_production_by_variete(conn, perimetre, id, unite)?;
+    let par_annee = get_production_by_annee(conn, perimetre, id, &type_produit, &unite_detail)?;
+    let par_saison = get_production_by_saison(conn, perimetre, id, &type_produit, &unite_detail)?;
+    let classement = get_classement_ruches(conn, perimetre, id, &type_produit, &unite_detail, limite)?;
+
+    Ok(StatistiquesProduction {
+        perimetre,
+        id,
+        par_type,
+        par_variete,
+        type_produit,
+        unite: unite_detail,
+        par_annee,
+        par_saison,
+        classement,
+    })
+}
+
+/// Récupère les unités de mesure disponibles
+pub fn get_all_unites(conn: &mut DbConnection) -> Result<Vec<UniteMesure>, Error> {
+    unite_mesure::table
+        .order((unite_mesure::unite_base, unite_mesure::facteur))
+        .load::<UniteMesure>(conn)
 }
 
 pub fn get_production_by_date_range(
@@ -0,0 +1,89 @@
+use crate::db::DbConnection;
+use crate::models::hausse_models::{PoseHausse, RetraitHausse, ErreurHausse};
+use crate::models::lot_models::NewLotMiel;
+use crate::models::production_models::Production;
+use crate::models::recolte_models::{Recolte, NewRecolte, RecolteDetail};
+use crate::schema::{pose_hausse, production, recolte};
+use crate::services::{hausse_service, lot_service};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Récupère toutes les sessions de récolte, de la plus récente à la plus ancienne
+pub fn get_all_recoltes(conn: &mut DbConnection) -> Result<Vec<Recolte>, Error> {
+    recolte::table
+        .order((recolte::date_recolte.desc(), recolte::id.desc()))
+        .load::<Recolte>(conn)
+}
+
+/// Récupère une session de récolte avec ses hausses, ses productions et son lot
+pub fn get_recolte_by_id(conn: &mut DbConnection, id: i32) -> Result<RecolteDetail, Error> {
+    let recolte = recolte::table.find(id).first::<Recolte>(conn)?;
+    load_detail(conn, recolte)
+}
+
+fn load_detail(conn: &mut DbConnection, recolte: Recolte) -> Result<RecolteDetail, Error> {
+    let hausses = pose_hausse::table
+        .filter(pose_hausse::id_recolte.eq(recolte.id))
+        .order(pose_hausse::id.asc())
+        .load::<PoseHausse>(conn)?;
+
+    let production_ids: Vec<i32> = hausses.iter().filter_map(|h| h.id_production).collect();
+    let productions = production::table
+        .filter(production::id.eq_any(&production_ids))
+        .order(production::id.asc())
+        .load::<Production>(conn)?;
+
+    let lot = match recolte.id_lot {
+        Some(id_lot) => Some(lot_service::get_lot_by_id(conn, id_lot)?),
+        None => None,
+    };
+
+    Ok(RecolteDetail { recolte, lot, hausses, productions })
+}
+
+/// Enregistre une session de récolte : retire et pèse les hausses, crée une production
+/// par ruche récoltée et le lot de miel correspondant, dans une seule transaction
+pub fn create_recolte(conn: &mut DbConnection, new_recolte: NewRecolte) -> Result<RecolteDetail, ErreurHausse> {
+    conn.transaction(|conn| {
+        let created: Recolte = diesel::insert_into(recolte::table)
+            .values(&new_recolte)
+            .get_result(conn)?;
+
+        let mut pose_ids = Vec::with_capacity(new_recolte.hausses.len());
+        for hausse in &new_recolte.hausses {
+            let retrait = RetraitHausse {
+                date_retrait: Some(created.date_recolte),
+                poids_miel_extrait: None,
+                poids_plein: Some(hausse.poids_plein),
+                poids_vide: Some(hausse.poids_vide),
+            };
+            let pose = hausse_service::remove_hausse(conn, hausse.id_materiel, retrait)?;
+            diesel::update(pose_hausse::table.find(pose.id))
+                .set(pose_hausse::id_recolte.eq(created.id))
+                .execute(conn)?;
+            pose_ids.push(pose.id);
+        }
+
+        let productions = hausse_service::create_productions_for_poses(conn, &pose_ids, created.date_recolte, created.type_miel.clone())?;
+        let production_ids: Vec<i32> = productions.iter().map(|p| p.id).collect();
+
+        let numero_lot = new_recolte.numero_lot.clone()
+            .unwrap_or_else(|| format!("LOT-{}-{}", created.date_recolte.format("%Y%m%d"), created.id));
+        let new_lot = NewLotMiel {
+            numero_lot,
+            type_miel: created.type_miel.clone(),
+            date_extraction: Some(created.date_recolte),
+            taux_humidite: created.taux_humidite,
+            dluo: None,
+            productions: production_ids,
+            pots: Vec::new(),
+        };
+        let lot = lot_service::create_lot(conn, new_lot)?;
+
+        let created: Recolte = diesel::update(recolte::table.find(created.id))
+            .set(recolte::id_lot.eq(lot.lot.id))
+            .get_result(conn)?;
+
+        Ok(load_detail(conn, created)?)
+    })
+}
\ No newline at end of file
@@ -0,0 +1,671 @@
+use crate::db::{DbConnection, Pool};
+use crate::models::poids_models::{Poids, TypePoids};
+use crate::models::releve_capteur_models::{
+    ReleveCapteur, NewReleveCapteur, TypeMesure, PointSerie, Serie, SeriesRuche, Agregat, Intervalle,
+    ParametresSerie, PointAgrege, SerieAgregee, Borne, RetentionMesures, BilanRetention, NombreAgregats, SERIE_POIDS,
+    UNITE_POIDS,
+};
+use crate::schema::{poids, releve_capteur, type_mesure, mesure_horaire, mesure_journaliere};
+use crate::services::floraison_service;
+use crate::services::poids_service::tare_ruche;
+use actix_web::{rt, web};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::{Double, Integer, Nullable, Text, Timestamp};
+use log::{error, info};
+
+/// Récupère les types de mesure
+pub fn get_all_types_mesure(conn: &mut DbConnection) -> Result<Vec<TypeMesure>, Error> {
+    type_mesure::table
+        .order(type_mesure::code)
+        .load::<TypeMesure>(conn)
+}
+
+// Instants bornant une période du jour de début au jour de fin inclus
+fn bornes(debut: Option<NaiveDate>, fin: Option<NaiveDate>) -> (Option<NaiveDateTime>, Option<NaiveDateTime>) {
+    (
+        debut.map(|debut| debut.and_time(NaiveTime::MIN)),
+        fin.and_then(|fin| fin.succ_opt()).map(|lendemain| lendemain.and_time(NaiveTime::MIN)),
+    )
+}
+
+/// Récupère les relevés d'une ruche, éventuellement d'un type et sur une période, du plus ancien au plus récent
+pub fn get_releves_by_ruche_id(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    type_mesure: Option<String>,
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+) -> Result<Vec<ReleveCapteur>, Error> {
+    let (depuis, jusqu_a) = bornes(debut, fin);
+    let mut query = releve_capteur::table
+        .filter(releve_capteur::id_ruche.eq(ruche_id))
+        .order((releve_capteur::date_releve.asc(), releve_capteur::id.asc()))
+        .into_boxed();
+
+    if let Some(type_mesure) = type_mesure {
+        query = query.filter(releve_capteur::type_mesure.eq(type_mesure));
+    }
+    if let Some(depuis) = depuis {
+        query = query.filter(releve_capteur::date_releve.ge(depuis));
+    }
+    if let Some(jusqu_a) = jusqu_a {
+        query = query.filter(releve_capteur::date_releve.lt(jusqu_a));
+    }
+
+    query.load::<ReleveCapteur>(conn)
+}
+
+/// Enregistre un relevé dans l'unité de son type de mesure (`NotFound` si le type est inconnu)
+pub fn create_releve(conn: &mut DbConnection, new_releve: NewReleveCapteur) -> Result<ReleveCapteur, Error> {
+    let unite = type_mesure::table
+        .find(&new_releve.type_mesure)
+        .select(type_mesure::unite)
+        .first::<String>(conn)?;
+
+    diesel::insert_into(releve_capteur::table)
+        .values((&new_releve, releve_capteur::unite.eq(unite)))
+        .get_result(conn)
+}
+
+/// Supprime un relevé
+pub fn delete_releve(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(releve_capteur::table.find(id)).execute(conn)
+}
+
+// Série des pesées d'une ruche, brutes ou nettes de la tare à leur date, datées du début de leur jour
+fn get_serie_poids(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+    type_poids: TypePoids,
+) -> Result<Serie, Error> {
+    let mut query = poids::table
+        .filter(poids::id_ruche.eq(ruche_id))
+        .filter(poids::date_creation.is_not_null())
+        .filter(poids::exclu.eq(false))
+        .order((poids::date_creation.asc(), poids::id.asc()))
+        .into_boxed();
+
+    if let Some(debut) = debut {
+        query = query.filter(poids::date_creation.ge(debut));
+    }
+    if let Some(fin) = fin {
+        query = query.filter(poids::date_creation.le(fin));
+    }
+
+    let pesees = match type_poids {
+        TypePoids::Brut => query
+            .select((poids::date_creation, poids::poids_ruche))
+            .load::<(Option<NaiveDate>, Option<i32>)>(conn)?,
+        TypePoids::Net => query
+            .select((
+                poids::date_creation,
+                poids::poids_ruche - tare_ruche(poids::id_ruche, poids::date_creation),
+            ))
+            .load::<(Option<NaiveDate>, Option<i32>)>(conn)?,
+    };
+
+    Ok(Serie {
+        type_mesure: SERIE_POIDS.to_string(),
+        unite: UNITE_POIDS.to_string(),
+        points: pesees
+            .into_iter()
+            .filter_map(|(date, valeur)| Some(PointSerie {
+                date: date?.and_time(NaiveTime::MIN),
+                valeur: valeur? as f64,
+            }))
+            .collect(),
+    })
+}
+
+// Période couverte par une série, jours inclus : ses bornes, complétées par la première et la dernière date de
+// ses points. Aucune si la série est vide et sans borne
+fn periode(
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+    dates: impl Iterator<Item = NaiveDate> + Clone,
+) -> Option<(NaiveDate, NaiveDate)> {
+    Some((debut.or_else(|| dates.clone().min())?, fin.or_else(|| dates.max())?))
+}
+
+/// Séries d'une ruche sur une période (jours inclus), pour les types demandés (`poids` désignant les pesées)
+/// ou, à défaut, pour les pesées et chaque type relevé sur la ruche durant la période.
+/// `NotFound` si un type demandé est inconnu
+pub fn get_series(
+    conn: &mut DbConnection,
+    ruche_id: i32,
+    types: Option<Vec<String>>,
+    debut: Option<NaiveDate>,
+    fin: Option<NaiveDate>,
+    type_poids: TypePoids,
+) -> Result<SeriesRuche, Error> {
+    let types = match types {
+        Some(types) => types,
+        None => {
+            let (depuis, jusqu_a) = bornes(debut, fin);
+            let mut query = releve_capteur::table
+                .filter(releve_capteur::id_ruche.eq(ruche_id))
+                .select(releve_capteur::type_mesure)
+                .distinct()
+                .order(releve_capteur::type_mesure)
+                .into_boxed();
+            if let Some(depuis) = depuis {
+                query = query.filter(releve_capteur::date_releve.ge(depuis));
+            }
+            if let Some(jusqu_a) = jusqu_a {
+                query = query.filter(releve_capteur::date_releve.lt(jusqu_a));
+            }
+
+            let mut types = vec![SERIE_POIDS.to_string()];
+            types.extend(query.load::<String>(conn)?);
+            types
+        }
+    };
+
+    let mut series = Vec::with_capacity(types.len());
+    for code in types {
+        if code == SERIE_POIDS {
+            series.push(get_serie_poids(conn, ruche_id, debut, fin, type_poids)?);
+            continue;
+        }
+
+        let type_mesure = type_mesure::table.find(&code).first::<TypeMesure>(conn)?;
+        let points = get_releves_by_ruche_id(conn, ruche_id, Some(code), debut, fin)?
+            .into_iter()
+            .map(|releve| PointSerie {
+                date: releve.date_releve,
+                valeur: releve.valeur,
+            })
+            .collect();
+
+        series.push(Serie {
+            type_mesure: type_mesure.code,
+            unite: type_mesure.unite,
+            points,
+        });
+    }
+
+    let dates = series.iter().flat_map(|serie| serie.points.iter().map(|point| point.date.date()));
+    let floraisons = match periode(debut, fin, dates) {
+        Some((depuis, jusqu_a)) => floraison_service::get_floraisons_ruche(conn, ruche_id, depuis, jusqu_a)?,
+        None => Vec::new(),
+    };
+
+    Ok(SeriesRuche {
+        id_ruche: ruche_id,
+        debut,
+        fin,
+        series,
+        floraisons,
+    })
+}
+
+// Mesures brutes dont est tirée une série agrégée : pesées (hors anomalies) ou relevés d'un type
+const SOURCE_POIDS: &str = "
+    SELECT horodatage AS instant, poids_ruche::float8 AS valeur
+    FROM poids
+    WHERE id_ruche = $1 AND horodatage IS NOT NULL AND poids_ruche IS NOT NULL AND NOT exclu";
+const SOURCE_RELEVES: &str = "
+    SELECT date_releve AS instant, valeur
+    FROM releve_capteur
+    WHERE id_ruche = $1 AND type_mesure = $5";
+
+// Pesées nettes de la tare de la ruche à leur date
+const MESURES_NETTES: &str = "
+    SELECT instant, nombre_mesures, somme - nombre_mesures * tare AS somme, minimum - tare AS minimum,
+           maximum - tare AS maximum, premiere - tare AS premiere, derniere - tare AS derniere
+    FROM completes, LATERAL (SELECT tare_ruche($1, instant::date)::float8 AS tare) tare";
+
+// Mesures agrégées par intervalles de $2 entre $3 (inclus) et $4 (exclu). Les mesures brutes sont utilisées
+// à partir de $6, les agrégats horaires entre $7 et $6 et les agrégats journaliers avant $7. Les intervalles
+// sont alignés sur le lundi 3 janvier 2000, de sorte que les semaines commencent le lundi
+const SERIE_AGREGEE_SQL: &str = "
+    WITH brutes AS ({source}),
+    completes AS (
+        SELECT instant, 1 AS nombre_mesures, valeur AS somme, valeur AS minimum, valeur AS maximum,
+               valeur AS premiere, valeur AS derniere
+        FROM brutes
+        WHERE instant >= $6
+        UNION ALL
+        SELECT debut, nombre_mesures, somme, minimum, maximum, premiere, derniere
+        FROM mesure_horaire
+        WHERE id_ruche = $1 AND type_mesure = $5 AND debut >= $7 AND debut < $6
+        UNION ALL
+        SELECT debut, nombre_mesures, somme, minimum, maximum, premiere, derniere
+        FROM mesure_journaliere
+        WHERE id_ruche = $1 AND type_mesure = $5 AND debut < $7
+    ),
+    mesures AS ({mesures}),
+    intervalles AS (
+        SELECT date_bin($2::interval, instant, TIMESTAMP '2000-01-03') AS date,
+               {agregation},
+               sum(nombre_mesures) AS nombre_mesures
+        FROM mesures
+        WHERE ($3::timestamp IS NULL OR instant >= $3)
+          AND ($4::timestamp IS NULL OR instant < $4)
+        GROUP BY 1
+    )
+    SELECT date, {valeur} AS valeur, nombre_mesures
+    FROM intervalles
+    ORDER BY date";
+
+// Agrégation calculée sur chaque intervalle, et valeur qui en est tirée
+fn sql_agregat(agregat: Agregat) -> (&'static str, &'static str) {
+    match agregat {
+        Agregat::Avg => ("sum(somme) / sum(nombre_mesures) AS valeur", "valeur"),
+        Agregat::Min => ("min(minimum) AS valeur", "valeur"),
+        Agregat::Max => ("max(maximum) AS valeur", "valeur"),
+        Agregat::Last => ("(array_agg(derniere ORDER BY instant DESC))[1] AS valeur", "valeur"),
+        Agregat::Delta => (
+            "(array_agg(premiere ORDER BY instant))[1] AS premiere, (array_agg(derniere ORDER BY instant DESC))[1] AS derniere",
+            "derniere - COALESCE(LAG(derniere) OVER (ORDER BY date), premiere)",
+        ),
+    }
+}
+
+/// Série d'un type de mesure, ou des pesées (`poids`), agrégée par intervalles en SQL. Au-delà de la rétention
+/// des mesures brutes, la série est tirée des agrégats horaires pour les intervalles de moins d'un jour tant
+/// qu'ils sont conservés, des agrégats journaliers sinon. La variation (`delta`) du premier intervalle est
+/// calculée depuis sa première mesure. `NotFound` si le type est inconnu
+pub fn get_serie_agregee(
+    conn: &mut DbConnection,
+    retention: &RetentionMesures,
+    ruche_id: i32,
+    type_mesure: String,
+    parametres: ParametresSerie,
+) -> Result<SerieAgregee, Error> {
+    let (source, unite) = if type_mesure == SERIE_POIDS {
+        (SOURCE_POIDS, UNITE_POIDS.to_string())
+    } else {
+        let unite = type_mesure::table
+            .find(&type_mesure)
+            .select(type_mesure::unite)
+            .first::<String>(conn)?;
+        (SOURCE_RELEVES, unite)
+    };
+    let mesures = match parametres.poids {
+        TypePoids::Net if source == SOURCE_POIDS => MESURES_NETTES,
+        _ => "SELECT * FROM completes",
+    };
+
+    let aujourd_hui = Utc::now().date_naive();
+    let limite_brute = retention.limite_brute(aujourd_hui);
+    let limite_horaire = match parametres.bucket {
+        Intervalle::QuinzeMinutes | Intervalle::Heure => retention.limite_horaire(aujourd_hui),
+        Intervalle::Jour | Intervalle::Semaine => limite_brute,
+    };
+
+    let (agregation, valeur) = sql_agregat(parametres.agg);
+    let sql = SERIE_AGREGEE_SQL
+        .replace("{source}", source)
+        .replace("{mesures}", mesures)
+        .replace("{agregation}", agregation)
+        .replace("{valeur}", valeur);
+
+    let points = diesel::sql_query(sql)
+        .bind::<Integer, _>(ruche_id)
+        .bind::<Text, _>(parametres.bucket.duree_sql())
+        .bind::<Nullable<Timestamp>, _>(parametres.debut.map(|debut| debut.debut()))
+        .bind::<Nullable<Timestamp>, _>(parametres.fin.and_then(Borne::fin_exclue))
+        .bind::<Text, _>(&type_mesure)
+        .bind::<Timestamp, _>(limite_brute)
+        .bind::<Timestamp, _>(limite_horaire)
+        .load::<PointAgrege>(conn)?;
+
+    let dates = points.iter().map(|point| point.date.date());
+    let floraisons = match periode(parametres.debut.map(Borne::jour), parametres.fin.map(Borne::jour), dates) {
+        Some((depuis, jusqu_a)) => floraison_service::get_floraisons_ruche(conn, ruche_id, depuis, jusqu_a)?,
+        None => Vec::new(),
+    };
+
+    Ok(SerieAgregee {
+        points,
+        floraisons,
+        id_ruche: ruche_id,
+        type_mesure,
+        unite,
+        bucket: parametres.bucket,
+        agg: parametres.agg,
+        debut: parametres.debut,
+        fin: parametres.fin,
+    })
+}
+
+// Agrège par heure ou par jour ({unite}) les mesures marquées, filtrées par {filtre}. Une mesure
+// arrivée en retard, antidatée ou réintégrée après le rejet d'une anomalie est fusionnée avec l'agrégat existant de
+// son intervalle, dont la première et la dernière mesure sont conservées si leur instant est inconnu ; à instant égal,
+// la mesure fusionnée l'emporte (une pesée corrigée après avoir été retirée de son agrégat)
+const FUSION_AGREGATS_SQL: &str = "
+        INSERT INTO {table} AS a (id_ruche, type_mesure, debut, nombre_mesures, somme, minimum, maximum, premiere,
+                                  derniere, instant_premiere, instant_derniere)
+        SELECT id_ruche, type_mesure, date_trunc('{unite}', instant), count(*), sum(valeur), min(valeur), max(valeur),
+               (array_agg(valeur ORDER BY instant))[1], (array_agg(valeur ORDER BY instant DESC))[1], min(instant),
+               max(instant)
+        FROM mesures
+        WHERE {filtre}
+        GROUP BY 1, 2, 3
+        ON CONFLICT (id_ruche, type_mesure, debut) DO UPDATE SET
+            nombre_mesures = a.nombre_mesures + EXCLUDED.nombre_mesures,
+            somme = a.somme + EXCLUDED.somme,
+            minimum = LEAST(a.minimum, EXCLUDED.minimum),
+            maximum = GREATEST(a.maximum, EXCLUDED.maximum),
+            premiere = CASE WHEN EXCLUDED.instant_premiere <= a.instant_premiere THEN EXCLUDED.premiere ELSE a.premiere END,
+            derniere = CASE WHEN EXCLUDED.instant_derniere >= a.instant_derniere THEN EXCLUDED.derniere ELSE a.derniere END,
+            instant_premiere = CASE WHEN EXCLUDED.instant_premiere <= a.instant_premiere
+                                    THEN EXCLUDED.instant_premiere ELSE a.instant_premiere END,
+            instant_derniere = CASE WHEN EXCLUDED.instant_derniere >= a.instant_derniere
+                                    THEN EXCLUDED.instant_derniere ELSE a.instant_derniere END
+        RETURNING 1";
+
+// Marque les mesures brutes antérieures à $2 pas encore agrégées et les ajoute aux agrégats horaires et
+// journaliers, en une seule requête afin qu'aucune mesure ne soit marquée sans avoir été agrégée
+const AGREGATION_SQL: &str = "
+    WITH pesees AS (
+        UPDATE poids SET agrege = TRUE
+        WHERE id_ruche IS NOT NULL AND horodatage IS NOT NULL AND poids_ruche IS NOT NULL AND NOT exclu AND NOT agrege
+          AND horodatage < $2
+        RETURNING id_ruche, $3::varchar AS type_mesure, horodatage AS instant, poids_ruche::float8 AS valeur
+    ),
+    releves AS (
+        UPDATE releve_capteur SET agrege = TRUE
+        WHERE NOT agrege AND date_releve < $2
+        RETURNING id_ruche, type_mesure, date_releve AS instant, valeur
+    ),
+    mesures AS (
+        SELECT * FROM pesees
+        UNION ALL
+        SELECT * FROM releves
+    ),
+    horaires AS ({horaires}),
+    journaliers AS ({journaliers})
+    SELECT (SELECT count(*) FROM horaires) AS agregats_horaires, (SELECT count(*) FROM journaliers) AS agregats_journaliers";
+
+// Élague les pesées antérieures à $1 en ne conservant que la dernière de chaque jour pour chaque ruche, hors
+// pesées écartées pour anomalie, qui sont conservées avec leur anomalie jusqu'à leur correction ou suppression
+const ELAGAGE_POIDS_SQL: &str = "
+    DELETE FROM poids p
+    WHERE p.horodatage < $1
+      AND NOT p.exclu
+      AND EXISTS (
+          SELECT 1 FROM poids q
+          WHERE q.id_ruche = p.id_ruche
+            AND q.horodatage >= date_trunc('day', p.horodatage)
+            AND q.horodatage < date_trunc('day', p.horodatage) + INTERVAL '1 day'
+            AND (q.horodatage, q.id) > (p.horodatage, p.id)
+            AND NOT q.exclu
+      )";
+
+// Élague les relevés antérieurs à $1 en ne conservant que le dernier de chaque jour pour chaque ruche et type
+const ELAGAGE_RELEVES_SQL: &str = "
+    DELETE FROM releve_capteur r
+    WHERE r.date_releve < $1
+      AND EXISTS (
+          SELECT 1 FROM releve_capteur s
+          WHERE s.id_ruche = r.id_ruche
+            AND s.type_mesure = r.type_mesure
+            AND s.date_releve >= date_trunc('day', r.date_releve)
+            AND s.date_releve < date_trunc('day', r.date_releve) + INTERVAL '1 day'
+            AND (s.date_releve, s.id) > (r.date_releve, r.id)
+      )";
+
+// Retire une pesée ($1 ruche, $2 type de mesure, $3 instant, $4 valeur, $5 identifiant) de son agrégat {table} par
+// {unite}. Les extrêmes, la première et la dernière valeur qu'elle fixait sont recalculés sur les pesées restantes de
+// l'intervalle, les pesées élaguées n'étant plus connues ; ils sont conservés avec leur instant si aucune pesée ne
+// reste
+const RETRAIT_AGREGAT_SQL: &str = "
+    WITH restantes AS (
+        SELECT min(poids_ruche)::float8 AS minimum, max(poids_ruche)::float8 AS maximum,
+               (array_agg(poids_ruche::float8 ORDER BY horodatage))[1] AS premiere,
+               (array_agg(poids_ruche::float8 ORDER BY horodatage DESC))[1] AS derniere,
+               min(horodatage) AS instant_premiere, max(horodatage) AS instant_derniere
+        FROM poids
+        WHERE id_ruche = $1 AND agrege AND NOT exclu AND poids_ruche IS NOT NULL AND id <> $5
+          AND horodatage >= date_trunc('{unite}', $3) AND horodatage < date_trunc('{unite}', $3) + INTERVAL '1 {unite}'
+    )
+    UPDATE {table} a SET
+        nombre_mesures = a.nombre_mesures - 1,
+        somme = a.somme - $4,
+        minimum = CASE WHEN a.minimum = $4 THEN COALESCE(r.minimum, a.minimum) ELSE a.minimum END,
+        maximum = CASE WHEN a.maximum = $4 THEN COALESCE(r.maximum, a.maximum) ELSE a.maximum END,
+        premiere = CASE WHEN a.instant_premiere = $3 THEN COALESCE(r.premiere, a.premiere) ELSE a.premiere END,
+        derniere = CASE WHEN a.instant_derniere = $3 THEN COALESCE(r.derniere, a.derniere) ELSE a.derniere END,
+        instant_premiere = CASE WHEN a.instant_premiere = $3 THEN COALESCE(r.instant_premiere, a.instant_premiere)
+                                ELSE a.instant_premiere END,
+        instant_derniere = CASE WHEN a.instant_derniere = $3 THEN COALESCE(r.instant_derniere, a.instant_derniere)
+                                ELSE a.instant_derniere END
+    FROM restantes r
+    WHERE a.id_ruche = $1 AND a.type_mesure = $2 AND a.debut = date_trunc('{unite}', $3)";
+
+/// Retire des agrégats horaires et journaliers une pesée déjà agrégée, avant sa correction ou sa suppression ;
+/// un agrégat qui ne compte plus aucune mesure est supprimé
+pub fn retirer_pesee_des_agregats(conn: &mut DbConnection, pesee: &Poids) -> Result<(), Error> {
+    let (true, Some(ruche_id), Some(valeur), Some(instant)) = (pesee.agrege, pesee.id_ruche, pesee.poids_ruche, pesee.horodatage) else {
+        return Ok(());
+    };
+
+    for (table, unite) in [("mesure_horaire", "hour"), ("mesure_journaliere", "day")] {
+        diesel::sql_query(RETRAIT_AGREGAT_SQL.replace("{table}", table).replace("{unite}", unite))
+            .bind::<Integer, _>(ruche_id)
+            .bind::<Text, _>(SERIE_POIDS)
+            .bind::<Timestamp, _>(instant)
+            .bind::<Double, _>(valeur as f64)
+            .bind::<Integer, _>(pesee.id)
+            .execute(conn)?;
+    }
+    diesel::delete(mesure_horaire::table.filter(mesure_horaire::nombre_mesures.le(0))).execute(conn)?;
+    diesel::delete(mesure_journaliere::table.filter(mesure_journaliere::nombre_mesures.le(0))).execute(conn)?;
+    Ok(())
+}
+
+// Ajoute aux agrégats les mesures sorties de la rétention brute et pas encore agrégées
+fn agreger_en_attente(conn: &mut DbConnection, limite_brute: NaiveDateTime, limite_horaire: NaiveDateTime) -> Result<NombreAgregats, Error> {
+    let agregation = AGREGATION_SQL
+        .replace("{horaires}", &FUSION_AGREGATS_SQL.replace("{table}", "mesure_horaire").replace("{unite}", "hour")
+            .replace("{filtre}", "instant >= $1"))
+        .replace("{journaliers}", &FUSION_AGREGATS_SQL.replace("{table}", "mesure_journaliere").replace("{unite}", "day")
+            .replace("{filtre}", "TRUE"));
+
+    // Les heures déjà sorties de leur rétention ne sont pas reconstituées à partir des mesures restantes
+    diesel::sql_query(agregation)
+        .bind::<Timestamp, _>(limite_horaire)
+        .bind::<Timestamp, _>(limite_brute)
+        .bind::<Text, _>(SERIE_POIDS)
+        .get_result::<NombreAgregats>(conn)
+}
+
+/// Ajoute sans attendre la tâche de fond les mesures sorties de la rétention brute et pas encore agrégées,
+/// par exemple une pesée corrigée après avoir été retirée de ses agrégats
+pub fn agreger_mesures_en_attente(conn: &mut DbConnection, retention: &RetentionMesures) -> Result<(), Error> {
+    let aujourd_hui = Utc::now().date_naive();
+    agreger_en_attente(conn, retention.limite_brute(aujourd_hui), retention.limite_horaire(aujourd_hui))?;
+    Ok(())
+}
+
+/// Applique la politique de conservation : les mesures sorties de la rétention brute et pas encore agrégées sont
+/// ajoutées aux agrégats horaires et journaliers, puis élaguées en ne conservant que la dernière mesure de chaque
+/// jour (une pesée saisie à la main est donc conservée), et les agrégats horaires sortis de leur rétention sont
+/// supprimés
+pub fn appliquer_retention(conn: &mut DbConnection, retention: &RetentionMesures) -> Result<BilanRetention, Error> {
+    let aujourd_hui = Utc::now().date_naive();
+    let limite_brute = retention.limite_brute(aujourd_hui);
+    let limite_horaire = retention.limite_horaire(aujourd_hui);
+
+    conn.transaction(|conn| {
+        let agregats = agreger_en_attente(conn, limite_brute, limite_horaire)?;
+
+        Ok(BilanRetention {
+            agregats_horaires: agregats.agregats_horaires as usize,
+            agregats_journaliers: agregats.agregats_journaliers as usize,
+            pesees_elaguees: diesel::sql_query(ELAGAGE_POIDS_SQL)
+                .bind::<Timestamp, _>(limite_brute)
+                .execute(conn)?,
+            releves_elagues: diesel::sql_query(ELAGAGE_RELEVES_SQL)
+                .bind::<Timestamp, _>(limite_brute)
+                .execute(conn)?,
+            agregats_horaires_supprimes: diesel::delete(mesure_horaire::table)
+                .filter(mesure_horaire::debut.lt(limite_horaire))
+                .execute(conn)?,
+        })
+    })
+}
+
+/// Tâche de fond appliquant périodiquement la politique de conservation des mesures, lancée au démarrage du
+/// serveur
+pub async fn agreger_mesures(pool: Pool, retention: RetentionMesures) {
+    let mut intervalle = rt::time::interval(retention.intervalle_agregation);
+    loop {
+        intervalle.tick().await;
+
+        let pool = pool.clone();
+        let retention = retention.clone();
+        let result = web::block(move || {
+            let mut conn = pool.get().map_err(|e| e.to_string())?;
+            appliquer_retention(&mut conn, &retention).map_err(|e| e.to_string())
+        }).await;
+
+        match result {
+            Ok(Ok(bilan)) => {
+                if bilan.agregats_horaires + bilan.agregats_journaliers + bilan.pesees_elaguees + bilan.releves_elagues > 0 {
+                    info!(
+                        "Rétention des mesures : {} agrégat(s) horaire(s) et {} journalier(s) créés, {} pesée(s) et {} relevé(s) élagués",
+                        bilan.agregats_horaires,
+                        bilan.agregats_journaliers,
+                        bilan.pesees_elaguees,
+                        bilan.releves_elagues
+                    );
+                }
+                if bilan.agregats_horaires_supprimes > 0 {
+                    info!("Rétention des mesures : {} agrégat(s) horaire(s) supprimé(s)", bilan.agregats_horaires_supprimes);
+                }
+            }
+            Ok(Err(e)) => error!("Erreur lors de l'application de la rétention des mesures: {}", e),
+            Err(e) => error!("Erreur lors de l'exécution de la rétention des mesures: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connexion_de_test;
+    use crate::models::poids_models::{NewPoids, UpdatePoids};
+    use crate::schema::{mesure_journaliere, ruche};
+    use crate::services::poids_service;
+    use chrono::{Datelike, Days};
+    use std::time::Duration;
+
+    #[test]
+    #[ignore = "nécessite une base PostgreSQL migrée (DATABASE_URL)"]
+    fn une_pesee_antidatee_complete_l_agregat_journalier() {
+        let retention = RetentionMesures {
+            jours_bruts: 90,
+            jours_horaires: 730,
+            intervalle_agregation: Duration::from_secs(3600),
+        };
+        let mut conn = connexion_de_test();
+        conn.test_transaction::<_, Error, _>(|conn| {
+            let ruche_id = diesel::insert_into(ruche::table)
+                .default_values()
+                .returning(ruche::id)
+                .get_result::<i32>(conn)?;
+            let jour = Utc::now().date_naive() - Days::new(100);
+            let peser = |conn: &mut DbConnection, heure: u32, poids_ruche: i32| {
+                diesel::insert_into(poids::table)
+                    .values(NewPoids {
+                        id_ruche: Some(ruche_id),
+                        poids_ruche: Some(poids_ruche),
+                        date_creation: Some(jour),
+                        horodatage: jour.and_hms_opt(heure, 0, 0),
+                    })
+                    .execute(conn)
+            };
+            let agregat = |conn: &mut DbConnection| {
+                mesure_journaliere::table
+                    .filter(mesure_journaliere::id_ruche.eq(ruche_id))
+                    .select((
+                        mesure_journaliere::nombre_mesures,
+                        mesure_journaliere::somme,
+                        mesure_journaliere::premiere,
+                        mesure_journaliere::derniere,
+                    ))
+                    .first::<(i32, f64, f64, f64)>(conn)
+            };
+
+            peser(conn, 8, 30000)?;
+            peser(conn, 20, 32000)?;
+            appliquer_retention(conn, &retention)?;
+            assert_eq!(agregat(conn)?, (2, 62000.0, 30000.0, 32000.0));
+
+            // Pesée antidatée reçue après l'élagage du jour
+            peser(conn, 6, 28000)?;
+            appliquer_retention(conn, &retention)?;
+            assert_eq!(agregat(conn)?, (3, 90000.0, 28000.0, 32000.0));
+            let restantes = poids::table.filter(poids::id_ruche.eq(ruche_id)).count().get_result::<i64>(conn)?;
+            assert_eq!(restantes, 1);
+
+            let moyennes = poids_service::get_annual_average_weight_by_month(
+                conn,
+                ruche_id,
+                jour.year(),
+                TypePoids::Brut,
+                &retention,
+            )?;
+            assert_eq!(moyennes.get(&jour.month()), Some(&30000.0));
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[ignore = "nécessite une base PostgreSQL migrée (DATABASE_URL)"]
+    fn une_pesee_agregee_corrigee_ou_supprimee_met_a_jour_ses_agregats() {
+        let retention = RetentionMesures {
+            jours_bruts: 90,
+            jours_horaires: 730,
+            intervalle_agregation: Duration::from_secs(3600),
+        };
+        let mut conn = connexion_de_test();
+        conn.test_transaction::<_, Error, _>(|conn| {
+            let ruche_id = diesel::insert_into(ruche::table)
+                .default_values()
+                .returning(ruche::id)
+                .get_result::<i32>(conn)?;
+            let jour = Utc::now().date_naive() - Days::new(100);
+            for (heure, poids_ruche) in [(8, 30000), (20, 32000)] {
+                diesel::insert_into(poids::table)
+                    .values(NewPoids {
+                        id_ruche: Some(ruche_id),
+                        poids_ruche: Some(poids_ruche),
+                        date_creation: Some(jour),
+                        horodatage: jour.and_hms_opt(heure, 0, 0),
+                    })
+                    .execute(conn)?;
+            }
+            let agregat = |conn: &mut DbConnection| {
+                mesure_journaliere::table
+                    .filter(mesure_journaliere::id_ruche.eq(ruche_id))
+                    .select((mesure_journaliere::nombre_mesures, mesure_journaliere::somme, mesure_journaliere::derniere))
+                    .first::<(i32, f64, f64)>(conn)
+                    .optional()
+            };
+            appliquer_retention(conn, &retention)?;
+            let restante = poids::table.filter(poids::id_ruche.eq(ruche_id)).first::<Poids>(conn)?;
+
+            let corrigee = poids_service::update_poids(conn, restante.id, UpdatePoids {
+                id: restante.id,
+                id_ruche: Some(ruche_id),
+                poids_ruche: Some(34000),
+                date_creation: Some(jour),
+                horodatage: restante.horodatage,
+            }, None, &retention)?;
+            assert!(corrigee.agrege);
+            assert_eq!(agregat(conn)?, Some((2, 64000.0, 34000.0)));
+
+            poids_service::delete_poids(conn, restante.id)?;
+            assert_eq!(agregat(conn)?.map(|(nombre, somme, _)| (nombre, somme)), Some((1, 30000.0)));
+            Ok(())
+        });
+    }
+}
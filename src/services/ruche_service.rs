@@ -1,40 +1,91 @@
 use crate::db::DbConnection;
-use crate::models::ruche_models::{Ruche, NewRuche, UpdateRuche};
+use crate::models::ruche_models::{Ruche, RucheAvecSante, RucheRow, NewRuche, UpdateRuche};
 use crate::schema::ruche;
+use crate::services::{configuration_ruche_service, hausse_service, sante_service};
+use chrono::NaiveDate;
 use diesel::prelude::*;
 use diesel::result::Error;
+use std::collections::HashMap;
+
+/// Complète des lignes de ruche avec les compteurs de leur dernier relevé de cadres
+fn with_configurations(conn: &mut DbConnection, rows: Vec<RucheRow>) -> Result<Vec<Ruche>, Error> {
+    let ids: Vec<i32> = rows.iter().map(|r| r.id).collect();
+    let configurations: HashMap<i32, _> = configuration_ruche_service::get_last_configurations_by_ruche_ids(conn, &ids)?
+        .into_iter()
+        .map(|c| (c.id_ruche, c))
+        .collect();
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let configuration = configurations.get(&row.id);
+            Ruche::from_row(row, configuration)
+        })
+        .collect())
+}
 
 /// Récupère toutes les ruches
 pub fn get_all_ruches(conn: &mut DbConnection) -> Result<Vec<Ruche>, Error> {
-    ruche::table.load::<Ruche>(conn)
+    let rows = ruche::table.select(RucheRow::as_select()).load::<RucheRow>(conn)?;
+    with_configurations(conn, rows)
 }
 
-/// Récupère une ruche par son ID
-pub fn get_ruche_by_id(conn: &mut DbConnection, id: i32) -> Result<Ruche, Error> {
-    ruche::table.find(id).first::<Ruche>(conn)
+/// Récupère une ruche par son ID, avec son score de santé à une date
+pub fn get_ruche_by_id(conn: &mut DbConnection, id: i32, date: NaiveDate) -> Result<RucheAvecSante, Error> {
+    let row = ruche::table.find(id).select(RucheRow::as_select()).first::<RucheRow>(conn)?;
+    let configuration = configuration_ruche_service::get_last_configuration_by_ruche_id(conn, id)?;
+    let sante = sante_service::get_sante_ruche(conn, id, date)?;
+    Ok(RucheAvecSante { ruche: Ruche::from_row(row, configuration.as_ref()), sante })
 }
 
 /// Récupère toutes les ruches associées à un utilisateur spécifique
 pub fn get_ruches_by_utilisateur(conn: &mut DbConnection, utilisateur_id_val: i32) -> Result<Vec<Ruche>, Error> {
     use crate::schema::ruche::dsl::*;
 
-    ruche
+    let rows = ruche
         .filter(id_apiculteur.eq(utilisateur_id_val))
-        .load::<Ruche>(conn)
+        .select(RucheRow::as_select())
+        .load::<RucheRow>(conn)?;
+    with_configurations(conn, rows)
 }
 
-/// Crée une nouvelle ruche
+/// Crée une nouvelle ruche et son premier relevé de cadres
 pub fn create_ruche(conn: &mut DbConnection, new_ruche: NewRuche) -> Result<Ruche, Error> {
-    diesel::insert_into(ruche::table)
-        .values(&new_ruche)
-        .get_result(conn)
+    conn.transaction(|conn| {
+        let row: RucheRow = diesel::insert_into(ruche::table)
+            .values(&new_ruche)
+            .returning(RucheRow::as_returning())
+            .get_result(conn)?;
+        let configuration = configuration_ruche_service::create_configuration(conn, new_ruche.configuration(row.id))?;
+        Ok(Ruche::from_row(row, Some(&configuration)))
+    })
 }
 
-/// Met à jour une ruche existante
+/// Met à jour une ruche existante, en historisant les compteurs de cadres s'ils ont changé ; le nombre de hausses
+/// transmis est ignoré tant que des hausses suivies individuellement sont posées sur la ruche
 pub fn update_ruche(conn: &mut DbConnection, id: i32, updated_ruche: UpdateRuche) -> Result<Ruche, Error> {
-    diesel::update(ruche::table.find(id))
-        .set(&updated_ruche)
-        .get_result(conn)
+    conn.transaction(|conn| {
+        let row: RucheRow = diesel::update(ruche::table.find(id))
+            .set((
+                ruche::id_apiculteur.eq(updated_ruche.id_apiculteur),
+                ruche::id_rucher.eq(updated_ruche.id_rucher),
+                ruche::photo_ruche.eq(&updated_ruche.photo_ruche),
+                ruche::numero_ruche.eq(updated_ruche.numero_ruche),
+                ruche::nom_ruche.eq(&updated_ruche.nom_ruche),
+                ruche::annee_reine.eq(updated_ruche.annee_reine),
+                ruche::nombre_cadres_hausse.eq(updated_ruche.nombre_cadres_hausse),
+            ))
+            .returning(RucheRow::as_returning())
+            .get_result(conn)?;
+        let mut configuration = updated_ruche.configuration(id);
+        // Le nombre de hausses d'une ruche dont les hausses sont suivies individuellement découle de leurs poses
+        if hausse_service::count_hausses_by_ruche_id(conn, id)? > 0 {
+            configuration.nombre_hausses = configuration_ruche_service::get_last_configuration_by_ruche_id(conn, id)?
+                .and_then(|c| c.nombre_hausses);
+        }
+        let configuration = configuration_ruche_service::record_configuration_if_changed(conn, configuration)?;
+        Ok(Ruche::from_row(row, Some(&configuration)))
+    })
 }
 
 /// Supprime une ruche
@@ -43,16 +94,47 @@ pub fn delete_ruche(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
 }
 
 pub fn get_ruche_by_nom(conn: &mut DbConnection, nom: String) -> Result<Vec<Ruche>, Error> {
-    ruche::table.filter(ruche::nom_ruche.eq(nom)).load::<Ruche>(conn)
+    let rows = ruche::table
+        .filter(ruche::nom_ruche.eq(nom))
+        .select(RucheRow::as_select())
+        .load::<RucheRow>(conn)?;
+    with_configurations(conn, rows)
+}
+
+/// Récupère les ruches d'un rucher
+pub fn get_ruches_by_rucher(conn: &mut DbConnection, rucher_id: i32) -> Result<Vec<Ruche>, Error> {
+    let rows = ruche::table
+        .filter(ruche::id_rucher.eq(rucher_id))
+        .select(RucheRow::as_select())
+        .load::<RucheRow>(conn)?;
+    with_configurations(conn, rows)
 }
 
 // Fonctions supplémentaires pour enrichir le service
 pub fn get_ruches_by_user_id(conn: &mut DbConnection, user_id: i32) -> Result<Vec<Ruche>, Error> {
-    ruche::table.filter(ruche::id_apiculteur.eq(user_id)).load::<Ruche>(conn)
+    let rows = ruche::table
+        .filter(ruche::id_apiculteur.eq(user_id))
+        .select(RucheRow::as_select())
+        .load::<RucheRow>(conn)?;
+    with_configurations(conn, rows)
 }
 //pub fn get_ruches_by_numero_ruche(conn: &mut DbConnection, numero_ruche: String) -> Result<Vec<Ruche>, Error> {
 //    ruche::table.filter(ruche::numero_ruche.eq(numero_ruche)).load::<Ruche>(conn)
 //}
-pub fn count_ruches(conn: &mut DbConnection) -> Result<i64, Error> {
-    ruche::table.count().get_result(conn)
+/// Vérifie qu'une ruche appartient à un apiculteur (`NotFound` sinon)
+pub fn verifier_proprietaire(conn: &mut DbConnection, ruche_id: i32, apiculteur_id: i32) -> Result<(), Error> {
+    ruche::table
+        .find(ruche_id)
+        .filter(ruche::id_apiculteur.eq(apiculteur_id))
+        .select(ruche::id)
+        .first::<i32>(conn)
+        .map(|_| ())
+}
+
+/// Compte les ruches d'un apiculteur
+pub fn count_ruches(conn: &mut DbConnection, apiculteur_id: i32) -> Result<i64, Error> {
+    ruche::table
+        .filter(ruche::id_apiculteur.eq(apiculteur_id))
+        .count()
+        .get_result(conn)
 }
\ No newline at end of file
@@ -0,0 +1,34 @@
+use crate::db::DbConnection;
+use crate::models::rucher_models::{Rucher, NewRucher, UpdateRucher};
+use crate::schema::rucher;
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Récupère tous les ruchers
+pub fn get_all_ruchers(conn: &mut DbConnection) -> Result<Vec<Rucher>, Error> {
+    rucher::table.load::<Rucher>(conn)
+}
+
+/// Récupère un rucher par son ID
+pub fn get_rucher_by_id(conn: &mut DbConnection, id: i32) -> Result<Rucher, Error> {
+    rucher::table.find(id).first::<Rucher>(conn)
+}
+
+/// Crée un nouveau rucher
+pub fn create_rucher(conn: &mut DbConnection, new_rucher: NewRucher) -> Result<Rucher, Error> {
+    diesel::insert_into(rucher::table)
+        .values(&new_rucher)
+        .get_result(conn)
+}
+
+/// Met à jour un rucher existant
+pub fn update_rucher(conn: &mut DbConnection, id: i32, updated_rucher: UpdateRucher) -> Result<Rucher, Error> {
+    diesel::update(rucher::table.find(id))
+        .set(&updated_rucher)
+        .get_result(conn)
+}
+
+/// Supprime un rucher
+pub fn delete_rucher(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(rucher::table.find(id)).execute(conn)
+}
\ No newline at end of file
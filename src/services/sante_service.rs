@@ -0,0 +1,283 @@
+use crate::db::DbConnection;
+use crate::models::configuration_ruche_models::ConfigurationRuche;
+use crate::models::miellee_models::PoidsNetJour;
+use crate::models::ruche_models::RucheRow;
+use crate::models::sante_models::{CritereSante, SanteRuche, TriSante, Ordre};
+use crate::models::varroa_models::ComptageVarroa;
+use crate::schema::{alerte, comptage_varroa, poids, ruche};
+use crate::services::poids_service::tare_ruche;
+use crate::services::{configuration_ruche_service, varroa_service};
+use chrono::{Datelike, Days, NaiveDate};
+use diesel::prelude::*;
+use diesel::result::Error;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// Cadres de couvain et de nourriture d'une colonie en bonne santé
+const CADRES_COUVAIN_CIBLE: f64 = 6.0;
+const CADRES_NOURRITURE_CIBLE: f64 = 3.0;
+
+// Fenêtre d'évolution du poids net, en jours, et perte sur cette fenêtre qui donne la note 0, en grammes
+const FENETRE_POIDS: u64 = 14;
+const PERTE_POIDS_MAX: f64 = 3000.0;
+
+// Au-delà, un comptage de varroas n'est plus représentatif, en jours
+const FENETRE_VARROA: u64 = 90;
+
+// Poids des critères dans le score
+const POIDS_COUVAIN: f64 = 25.0;
+const POIDS_NOURRITURE: f64 = 15.0;
+const POIDS_EVOLUTION_POIDS: f64 = 15.0;
+const POIDS_VARROA: f64 = 25.0;
+const POIDS_REINE: f64 = 10.0;
+const POIDS_ALERTES: f64 = 10.0;
+
+// Données de santé d'un lot de ruches, chargées en quelques requêtes
+struct DonneesSante {
+    configurations: HashMap<i32, ConfigurationRuche>,
+    // Premier et dernier poids nets journaliers de la fenêtre
+    evolutions: HashMap<i32, (PoidsNetJour, PoidsNetJour)>,
+    comptages: HashMap<i32, ComptageVarroa>,
+    alertes: HashMap<i32, i64>,
+}
+
+/// Indique si les fenêtres d'évaluation avant une date restent dans le calendrier
+pub fn date_evaluable(date: NaiveDate) -> bool {
+    date.checked_sub_days(Days::new(FENETRE_POIDS.max(FENETRE_VARROA))).is_some()
+}
+
+fn charger_donnees(conn: &mut DbConnection, ruche_ids: &[i32], date: NaiveDate) -> Result<DonneesSante, Error> {
+    // Une date hors calendrier est écartée par `date_evaluable` ; les fenêtres sont à défaut tronquées
+    let debut_poids = date.checked_sub_days(Days::new(FENETRE_POIDS)).unwrap_or(NaiveDate::MIN);
+    let debut_varroa = date.checked_sub_days(Days::new(FENETRE_VARROA)).unwrap_or(NaiveDate::MIN);
+
+    let configurations = configuration_ruche_service::get_last_configurations_by_ruche_ids(conn, ruche_ids)?
+        .into_iter()
+        .map(|c| (c.id_ruche, c))
+        .collect();
+
+    // Dernière pesée nette non exclue de chaque jour
+    let pesees = poids::table
+        .filter(poids::id_ruche.eq_any(ruche_ids))
+        .filter(poids::date_creation.ge(debut_poids))
+        .filter(poids::date_creation.le(date))
+        .filter(poids::poids_ruche.is_not_null())
+        .filter(poids::exclu.eq(false))
+        .distinct_on((poids::id_ruche, poids::date_creation))
+        .order((poids::id_ruche, poids::date_creation.asc(), poids::horodatage.desc(), poids::id.desc()))
+        .select((
+            poids::id_ruche,
+            poids::date_creation,
+            poids::poids_ruche - tare_ruche(poids::id_ruche, poids::date_creation),
+        ))
+        .load::<(Option<i32>, Option<NaiveDate>, Option<i32>)>(conn)?;
+    let mut evolutions: HashMap<i32, (PoidsNetJour, PoidsNetJour)> = HashMap::new();
+    for (ruche_id, jour, poids_net) in pesees {
+        let (Some(ruche_id), Some(date), Some(poids_net)) = (ruche_id, jour, poids_net) else {
+            continue;
+        };
+        evolutions
+            .entry(ruche_id)
+            .and_modify(|(_, dernier)| *dernier = PoidsNetJour { date, poids_net })
+            .or_insert((PoidsNetJour { date, poids_net }, PoidsNetJour { date, poids_net }));
+    }
+
+    let comptages = comptage_varroa::table
+        .filter(comptage_varroa::id_ruche.eq_any(ruche_ids))
+        .filter(comptage_varroa::date_comptage.ge(debut_varroa))
+        .filter(comptage_varroa::date_comptage.le(date))
+        .distinct_on(comptage_varroa::id_ruche)
+        .order((comptage_varroa::id_ruche, comptage_varroa::date_comptage.desc(), comptage_varroa::id.desc()))
+        .load::<ComptageVarroa>(conn)?
+        .into_iter()
+        .map(|c| (c.id_ruche, c))
+        .collect();
+
+    let alertes = alerte::table
+        .filter(alerte::id_ruche.eq_any(ruche_ids))
+        // Les alertes d'appareil (batterie, connexion) ne disent rien de la colonie
+        .filter(alerte::id_appareil.is_null())
+        .filter(alerte::date_resolution.is_null())
+        .group_by(alerte::id_ruche)
+        .select((alerte::id_ruche, diesel::dsl::count_star()))
+        .load::<(Option<i32>, i64)>(conn)?
+        .into_iter()
+        .filter_map(|(ruche_id, nombre)| Some((ruche_id?, nombre)))
+        .collect();
+
+    Ok(DonneesSante { configurations, evolutions, comptages, alertes })
+}
+
+fn critere(critere: &str, note: f64, poids: f64, detail: String) -> CritereSante {
+    CritereSante { critere: critere.to_string(), note: note.clamp(0.0, 1.0), poids, detail }
+}
+
+// Note de chaque critère disponible pour une ruche ; les seuils varroa sont mis en cache par méthode et mois
+fn evaluer(
+    conn: &mut DbConnection,
+    row: RucheRow,
+    donnees: &DonneesSante,
+    seuils: &mut HashMap<(String, i32), Option<f64>>,
+    date: NaiveDate,
+) -> Result<SanteRuche, Error> {
+    let mut criteres = Vec::new();
+    let mut donnees_manquantes = Vec::new();
+    let configuration = donnees.configurations.get(&row.id);
+
+    match configuration.and_then(|c| c.nombre_cadre_couvain) {
+        Some(couvain) => criteres.push(critere(
+            "couvain",
+            couvain as f64 / CADRES_COUVAIN_CIBLE,
+            POIDS_COUVAIN,
+            format!("{} cadre(s) de couvain", couvain),
+        )),
+        None => donnees_manquantes.push("couvain".to_string()),
+    }
+
+    match configuration.and_then(|c| c.nombre_cadre_nourriture) {
+        Some(nourriture) => criteres.push(critere(
+            "nourriture",
+            nourriture as f64 / CADRES_NOURRITURE_CIBLE,
+            POIDS_NOURRITURE,
+            format!("{} cadre(s) de nourriture", nourriture),
+        )),
+        None => donnees_manquantes.push("nourriture".to_string()),
+    }
+
+    // Une prise de poids est toujours bonne, une perte est pénalisée linéairement
+    match donnees.evolutions.get(&row.id) {
+        Some((premier, dernier)) if premier.date < dernier.date => {
+            let variation = dernier.poids_net - premier.poids_net;
+            criteres.push(critere(
+                "poids",
+                1.0 + (variation.min(0) as f64) / PERTE_POIDS_MAX,
+                POIDS_EVOLUTION_POIDS,
+                format!("{:+.1} kg nets du {} au {}", variation as f64 / 1000.0, premier.date, dernier.date),
+            ));
+        }
+        _ => donnees_manquantes.push("poids".to_string()),
+    }
+
+    // Note 1 jusqu'à la moitié du seuil d'alerte, 0,5 au seuil, 0 à une fois et demie le seuil
+    let varroa = match donnees.comptages.get(&row.id) {
+        Some(comptage) => match comptage.taux_infestation {
+            Some(taux) => {
+                let cle = (comptage.methode.clone(), comptage.date_comptage.month() as i32);
+                let seuil = match seuils.get(&cle) {
+                    Some(seuil) => *seuil,
+                    None => {
+                        let seuil = varroa_service::get_seuil_applicable(conn, &cle.0, cle.1)?.map(|s| s.seuil);
+                        seuils.insert(cle, seuil);
+                        seuil
+                    }
+                };
+                seuil.filter(|s| *s > 0.0).map(|seuil| critere(
+                    "varroa",
+                    1.5 - taux / seuil,
+                    POIDS_VARROA,
+                    format!(
+                        "{:.2} ({}) le {}, seuil de {:.2}",
+                        taux, comptage.methode, comptage.date_comptage, seuil
+                    ),
+                ))
+            }
+            None => None,
+        },
+        None => None,
+    };
+    match varroa {
+        Some(varroa) => criteres.push(varroa),
+        None => donnees_manquantes.push("varroa".to_string()),
+    }
+
+    match row.annee_reine {
+        Some(annee) => {
+            let age = (date.year() - annee).max(0);
+            let note = match age {
+                0 | 1 => 1.0,
+                2 => 0.7,
+                3 => 0.4,
+                _ => 0.1,
+            };
+            criteres.push(critere("reine", note, POIDS_REINE, format!("reine de {} ({} an(s))", annee, age)));
+        }
+        None => donnees_manquantes.push("reine".to_string()),
+    }
+
+    // Sans aucun critère sur la colonie, les seules alertes ne suffisent pas à la juger
+    let colonie_evaluee = !criteres.is_empty();
+    let alertes = donnees.alertes.get(&row.id).copied().unwrap_or(0);
+    criteres.push(critere(
+        "alertes",
+        1.0 - 0.5 * alertes as f64,
+        POIDS_ALERTES,
+        format!("{} alerte(s) ouverte(s)", alertes),
+    ));
+
+    let poids_total: f64 = criteres.iter().map(|c| c.poids).sum();
+    let score = colonie_evaluee
+        .then(|| criteres.iter().map(|c| c.note * c.poids).sum::<f64>() / poids_total * 100.0);
+
+    Ok(SanteRuche {
+        id_ruche: row.id,
+        nom_ruche: row.nom_ruche,
+        id_rucher: row.id_rucher,
+        score: score.map(|score| (score * 10.0).round() / 10.0),
+        criteres,
+        donnees_manquantes,
+    })
+}
+
+fn evaluer_ruches(conn: &mut DbConnection, rows: Vec<RucheRow>, date: NaiveDate) -> Result<Vec<SanteRuche>, Error> {
+    let ids: Vec<i32> = rows.iter().map(|r| r.id).collect();
+    let donnees = charger_donnees(conn, &ids, date)?;
+    let mut seuils = HashMap::new();
+    rows.into_iter()
+        .map(|row| evaluer(conn, row, &donnees, &mut seuils, date))
+        .collect()
+}
+
+/// Calcule le score de santé d'une ruche à une date
+pub fn get_sante_ruche(conn: &mut DbConnection, ruche_id: i32, date: NaiveDate) -> Result<SanteRuche, Error> {
+    let row = ruche::table.find(ruche_id).select(RucheRow::as_select()).first::<RucheRow>(conn)?;
+    evaluer_ruches(conn, vec![row], date)?.pop().ok_or(Error::NotFound)
+}
+
+/// Calcule le score de santé des ruches, éventuellement d'un rucher ou d'un apiculteur,
+/// triées par défaut des moins bonnes aux meilleures pour prioriser les visites, les ruches sans score en tête
+pub fn get_sante_ruches(
+    conn: &mut DbConnection,
+    rucher_id: Option<i32>,
+    apiculteur_id: Option<i32>,
+    tri: TriSante,
+    ordre: Ordre,
+    date: NaiveDate,
+) -> Result<Vec<SanteRuche>, Error> {
+    let mut query = ruche::table.select(RucheRow::as_select()).into_boxed();
+    if let Some(rucher_id) = rucher_id {
+        query = query.filter(ruche::id_rucher.eq(rucher_id));
+    }
+    if let Some(apiculteur_id) = apiculteur_id {
+        query = query.filter(ruche::id_apiculteur.eq(apiculteur_id));
+    }
+    let rows = query.order(ruche::id).load::<RucheRow>(conn)?;
+
+    let mut santes = evaluer_ruches(conn, rows, date)?;
+    santes.sort_by(|a, b| {
+        let ordre_tri = match tri {
+            TriSante::Score => match (a.score, b.score) {
+                // Les ruches sans score, dont on ne sait rien, sont à visiter en premier dans les deux ordres
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            },
+            TriSante::Ruche => a.id_ruche.cmp(&b.id_ruche),
+        };
+        match ordre {
+            Ordre::Asc => ordre_tri,
+            Ordre::Desc => ordre_tri.reverse(),
+        }
+    });
+    Ok(santes)
+}
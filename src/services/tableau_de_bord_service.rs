@@ -0,0 +1,41 @@
+use crate::db::DbConnection;
+use crate::models::production_models::Perimetre;
+use crate::models::tableau_de_bord_models::{DernierPoidsRuche, TableauDeBord};
+use crate::services::{
+    alerte_service, intervention_service, materiel_service, poids_service, production_service, ruche_service,
+};
+use chrono::{Datelike, NaiveDate};
+use diesel::result::Error;
+
+/// Construit le tableau de bord d'un apiculteur à une date, en une requête par rubrique
+pub fn get_tableau_de_bord(conn: &mut DbConnection, apiculteur_id: i32, date: NaiveDate) -> Result<TableauDeBord, Error> {
+    let nombre_ruches = ruche_service::count_ruches(conn, apiculteur_id)?;
+    let derniers_poids = poids_service::get_derniers_poids_by_apiculteur(conn, apiculteur_id)?
+        .into_iter()
+        .map(|(poids, nom_ruche)| DernierPoidsRuche { nom_ruche, poids })
+        .collect();
+
+    let saison = date.year();
+    let production_saison = production_service::get_total_production_by_type(
+        conn,
+        Perimetre::Apiculteur,
+        apiculteur_id,
+        None,
+        NaiveDate::from_ymd_opt(saison, 1, 1),
+    )?;
+
+    let materiels_a_reparer = materiel_service::get_materiels_a_reparer_by_apiculteur(conn, apiculteur_id)?;
+    let interventions_a_venir = intervention_service::get_interventions_a_venir_by_apiculteur(conn, apiculteur_id, date)?;
+    let alertes_ouvertes = alerte_service::get_alertes(conn, None, Some(apiculteur_id), true)?;
+
+    Ok(TableauDeBord {
+        id_apiculteur: apiculteur_id,
+        nombre_ruches,
+        derniers_poids,
+        saison,
+        production_saison,
+        materiels_a_reparer,
+        interventions_a_venir,
+        alertes_ouvertes,
+    })
+}
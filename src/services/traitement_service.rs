@@ -0,0 +1,142 @@
+use crate::db::DbConnection;
+use crate::models::traitement_models::{
+    Traitement, NewTraitement, UpdateTraitement, ProductionEnDelaiAttente, LigneRegistre, RegistreTraitements,
+};
+use crate::schema::{ruche, rucher, traitement};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::{Integer, Nullable};
+
+/// Récupère les traitements d'une ruche, du plus récent au plus ancien
+pub fn get_traitements_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Vec<Traitement>, Error> {
+    traitement::table
+        .filter(traitement::id_ruche.eq(ruche_id))
+        .order((traitement::date_debut.desc(), traitement::id.desc()))
+        .load::<Traitement>(conn)
+}
+
+/// Récupère un traitement par son ID
+pub fn get_traitement_by_id(conn: &mut DbConnection, id: i32) -> Result<Traitement, Error> {
+    traitement::table.find(id).first::<Traitement>(conn)
+}
+
+/// Inscrit un traitement au registre
+pub fn create_traitement(conn: &mut DbConnection, new_traitement: NewTraitement) -> Result<Traitement, Error> {
+    diesel::insert_into(traitement::table)
+        .values(&new_traitement)
+        .get_result(conn)
+}
+
+/// Met à jour un traitement existant
+pub fn update_traitement(conn: &mut DbConnection, id: i32, updated_traitement: UpdateTraitement) -> Result<Traitement, Error> {
+    diesel::update(traitement::table.find(id))
+        .set(&updated_traitement)
+        .get_result(conn)
+}
+
+/// Supprime un traitement
+pub fn delete_traitement(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(traitement::table.find(id)).execute(conn)
+}
+
+// Productions datées du début d'un traitement de leur ruche au dernier jour de son délai d'attente, filtrées par
+// ruche ($1), apiculteur ($2), année ($3) ou production ($4). Un traitement sans date de fin est considéré en cours
+const PRODUCTIONS_EN_DELAI_ATTENTE_SQL: &str = "
+    SELECT p.id AS id_production,
+           p.id_ruche,
+           p.date_creation AS date_production,
+           p.quantite_production,
+           p.unite,
+           p.type_produit,
+           t.id AS id_traitement,
+           t.produit,
+           t.date_debut,
+           t.date_fin,
+           t.date_fin + t.delai_attente AS fin_delai_attente
+    FROM production p
+    JOIN traitement t ON t.id_ruche = p.id_ruche
+    JOIN ruche r ON r.id = p.id_ruche
+    WHERE p.date_creation >= t.date_debut
+      AND (t.date_fin IS NULL OR p.date_creation <= t.date_fin + t.delai_attente)
+      AND ($1::int4 IS NULL OR p.id_ruche = $1)
+      AND ($2::int4 IS NULL OR r.id_apiculteur = $2)
+      AND ($3::int4 IS NULL OR EXTRACT(YEAR FROM p.date_creation) = $3)
+      AND ($4::int4 IS NULL OR p.id = $4)
+    ORDER BY p.date_creation, p.id, t.id";
+
+/// Productions récoltées pendant un délai d'attente, filtrées par ruche, apiculteur ou année
+pub fn get_productions_en_delai_attente(
+    conn: &mut DbConnection,
+    ruche_id: Option<i32>,
+    apiculteur_id: Option<i32>,
+    annee: Option<i32>,
+) -> Result<Vec<ProductionEnDelaiAttente>, Error> {
+    diesel::sql_query(PRODUCTIONS_EN_DELAI_ATTENTE_SQL)
+        .bind::<Nullable<Integer>, _>(ruche_id)
+        .bind::<Nullable<Integer>, _>(apiculteur_id)
+        .bind::<Nullable<Integer>, _>(annee)
+        .bind::<Nullable<Integer>, _>(None::<i32>)
+        .load::<ProductionEnDelaiAttente>(conn)
+}
+
+/// Traitements dont une production est datée pendant le délai d'attente, aucun si elle n'est pas concernée
+pub fn get_delais_attente_production(
+    conn: &mut DbConnection,
+    production_id: i32,
+) -> Result<Vec<ProductionEnDelaiAttente>, Error> {
+    diesel::sql_query(PRODUCTIONS_EN_DELAI_ATTENTE_SQL)
+        .bind::<Nullable<Integer>, _>(None::<i32>)
+        .bind::<Nullable<Integer>, _>(None::<i32>)
+        .bind::<Nullable<Integer>, _>(None::<i32>)
+        .bind::<Nullable<Integer>, _>(production_id)
+        .load::<ProductionEnDelaiAttente>(conn)
+}
+
+/// Registre des traitements en cours durant une année, pour toutes les ruches ou celles d'un apiculteur
+pub fn get_registre_traitements(
+    conn: &mut DbConnection,
+    annee: i32,
+    apiculteur_id: Option<i32>,
+) -> Result<RegistreTraitements, Error> {
+    let debut_annee = NaiveDate::from_ymd_opt(annee, 1, 1).ok_or(Error::NotFound)?;
+    let fin_annee = NaiveDate::from_ymd_opt(annee, 12, 31).ok_or(Error::NotFound)?;
+
+    let mut query = traitement::table
+        .inner_join(ruche::table.left_join(rucher::table))
+        .filter(traitement::date_debut.le(fin_annee))
+        .filter(traitement::date_fin.is_null().or(traitement::date_fin.ge(debut_annee)))
+        .select((
+            Traitement::as_select(),
+            ruche::numero_ruche,
+            ruche::nom_ruche,
+            rucher::nom_rucher.nullable(),
+        ))
+        .order((traitement::date_debut.asc(), traitement::id.asc()))
+        .into_boxed();
+
+    if let Some(apiculteur_id) = apiculteur_id {
+        query = query.filter(ruche::id_apiculteur.eq(apiculteur_id));
+    }
+
+    let traitements = query
+        .load::<(Traitement, Option<i32>, Option<String>, Option<String>)>(conn)?
+        .into_iter()
+        .map(|(traitement, numero_ruche, nom_ruche, nom_rucher)| LigneRegistre {
+            fin_delai_attente: traitement.fin_delai_attente(),
+            traitement,
+            numero_ruche,
+            nom_ruche,
+            nom_rucher,
+        })
+        .collect();
+
+    let productions_en_delai_attente = get_productions_en_delai_attente(conn, None, apiculteur_id, Some(annee))?;
+
+    Ok(RegistreTraitements {
+        annee,
+        id_apiculteur: apiculteur_id,
+        traitements,
+        productions_en_delai_attente,
+    })
+}
\ No newline at end of file
@@ -0,0 +1,178 @@
+use crate::db::DbConnection;
+use crate::models::alerte_models::{NewAlerte, ALERTE_VARROA};
+use crate::models::varroa_models::{
+    ComptageVarroa, NewComptageVarroa, SeuilVarroa, NewSeuilVarroa, ComptageVarroaResultat, EfficaciteTraitement,
+};
+use crate::schema::{comptage_varroa, seuil_varroa, traitement};
+use crate::services::alerte_service;
+use chrono::{Datelike, Duration, NaiveDate};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+// Nombre de jours avant le début et après la fin d'un traitement
+// dans lesquels sont recherchés les comptages comparés
+const FENETRE_EFFICACITE_JOURS: i64 = 30;
+
+/// Récupère les comptages de varroas d'une ruche, du plus récent au plus ancien
+pub fn get_comptages_by_ruche_id(conn: &mut DbConnection, ruche_id: i32) -> Result<Vec<ComptageVarroa>, Error> {
+    comptage_varroa::table
+        .filter(comptage_varroa::id_ruche.eq(ruche_id))
+        .order((comptage_varroa::date_comptage.desc(), comptage_varroa::id.desc()))
+        .load::<ComptageVarroa>(conn)
+}
+
+/// Enregistre un comptage et le compare au seuil de la saison : une alerte varroa est levée
+/// lorsque le seuil est atteint, et les alertes ouvertes sont résolues lorsqu'il ne l'est plus
+pub fn create_comptage(conn: &mut DbConnection, new_comptage: NewComptageVarroa) -> Result<ComptageVarroaResultat, Error> {
+    conn.transaction(|conn| {
+        let comptage = diesel::insert_into(comptage_varroa::table)
+            .values(&new_comptage)
+            .get_result::<ComptageVarroa>(conn)?;
+
+        let seuil = get_seuil_applicable(conn, &comptage.methode, comptage.date_comptage.month() as i32)?;
+        let mut alerte = None;
+        if let (Some(seuil), Some(taux)) = (&seuil, comptage.taux_infestation) {
+            if taux >= seuil.seuil {
+                alerte = Some(alerte_service::lever_alerte(conn, NewAlerte {
+                    id_ruche: Some(comptage.id_ruche),
+                    type_alerte: ALERTE_VARROA.to_string(),
+                    message: format!(
+                        "Infestation varroa de {:.2} ({}) le {}, seuil {} de {:.2}",
+                        taux,
+                        comptage.methode,
+                        comptage.date_comptage,
+                        seuil.libelle.as_deref().unwrap_or("de saison"),
+                        seuil.seuil
+                    ),
+                    valeur: Some(taux),
+                    seuil: Some(seuil.seuil),
+                    id_appareil: None,
+                    id_apiculteur: None,
+                })?);
+            } else {
+                alerte_service::resoudre_alertes(conn, comptage.id_ruche, ALERTE_VARROA)?;
+            }
+        }
+
+        Ok(ComptageVarroaResultat { comptage, seuil, alerte })
+    })
+}
+
+/// Supprime un comptage
+pub fn delete_comptage(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(comptage_varroa::table.find(id)).execute(conn)
+}
+
+/// Récupère tous les seuils d'alerte
+pub fn get_all_seuils(conn: &mut DbConnection) -> Result<Vec<SeuilVarroa>, Error> {
+    seuil_varroa::table
+        .order((seuil_varroa::methode, seuil_varroa::mois_debut))
+        .load::<SeuilVarroa>(conn)
+}
+
+/// Seuil applicable à une méthode pour un mois (le plus strict si plusieurs saisons se chevauchent)
+pub fn get_seuil_applicable(conn: &mut DbConnection, methode: &str, mois: i32) -> Result<Option<SeuilVarroa>, Error> {
+    use crate::schema::seuil_varroa::dsl;
+
+    dsl::seuil_varroa
+        .filter(dsl::methode.eq(methode))
+        .filter(
+            dsl::mois_debut.le(dsl::mois_fin)
+                .and(dsl::mois_debut.le(mois))
+                .and(dsl::mois_fin.ge(mois))
+                .or(dsl::mois_debut.gt(dsl::mois_fin)
+                    .and(dsl::mois_debut.le(mois).or(dsl::mois_fin.ge(mois)))),
+        )
+        .order(dsl::seuil.asc())
+        .first::<SeuilVarroa>(conn)
+        .optional()
+}
+
+/// Crée un seuil d'alerte
+pub fn create_seuil(conn: &mut DbConnection, new_seuil: NewSeuilVarroa) -> Result<SeuilVarroa, Error> {
+    diesel::insert_into(seuil_varroa::table)
+        .values(&new_seuil)
+        .get_result(conn)
+}
+
+/// Met à jour un seuil d'alerte
+pub fn update_seuil(conn: &mut DbConnection, id: i32, updated_seuil: NewSeuilVarroa) -> Result<SeuilVarroa, Error> {
+    diesel::update(seuil_varroa::table.find(id))
+        .set(&updated_seuil)
+        .get_result(conn)
+}
+
+/// Supprime un seuil d'alerte
+pub fn delete_seuil(conn: &mut DbConnection, id: i32) -> Result<usize, Error> {
+    diesel::delete(seuil_varroa::table.find(id)).execute(conn)
+}
+
+/// Compare le dernier comptage précédant un traitement au premier comptage suivant sa fin, réalisé avec la même
+/// méthode lorsque c'est possible et avec une autre à défaut
+pub fn get_efficacite_traitement(conn: &mut DbConnection, traitement_id: i32) -> Result<EfficaciteTraitement, Error> {
+    let (ruche_id, date_debut, date_fin) = traitement::table
+        .find(traitement_id)
+        .select((traitement::id_ruche, traitement::date_debut, traitement::date_fin))
+        .first::<(i32, NaiveDate, Option<NaiveDate>)>(conn)?;
+    let fenetre = Duration::days(FENETRE_EFFICACITE_JOURS);
+
+    let avant = comptage_varroa::table
+        .filter(comptage_varroa::id_ruche.eq(ruche_id))
+        .filter(comptage_varroa::date_comptage.between(date_debut - fenetre, date_debut))
+        .order((comptage_varroa::date_comptage.desc(), comptage_varroa::id.desc()))
+        .first::<ComptageVarroa>(conn)
+        .optional()?;
+
+    // Pas de comptage « après » tant que le traitement est en cours. Le premier comptage suivant la fin avec la
+    // même méthode est préféré, à défaut celui de toute autre méthode
+    let apres = match date_fin {
+        Some(date_fin) => {
+            let premier_apres = |conn: &mut DbConnection, methode: Option<&str>| {
+                let mut query = comptage_varroa::table
+                    .filter(comptage_varroa::id_ruche.eq(ruche_id))
+                    .filter(comptage_varroa::date_comptage.gt(date_fin))
+                    .filter(comptage_varroa::date_comptage.le(date_fin + fenetre))
+                    .into_boxed();
+                if let Some(methode) = methode {
+                    query = query.filter(comptage_varroa::methode.eq(methode.to_string()));
+                }
+                query
+                    .order((comptage_varroa::date_comptage.asc(), comptage_varroa::id.asc()))
+                    .first::<ComptageVarroa>(conn)
+                    .optional()
+            };
+            match &avant {
+                Some(avant) => match premier_apres(conn, Some(&avant.methode))? {
+                    Some(apres) => Some(apres),
+                    None => premier_apres(conn, None)?,
+                },
+                None => premier_apres(conn, None)?,
+            }
+        }
+        None => None,
+    };
+    let meme_methode = match (&avant, &apres) {
+        (Some(avant), Some(apres)) => Some(avant.methode == apres.methode),
+        _ => None,
+    };
+
+    // Des taux obtenus par des méthodes différentes ne sont pas comparables
+    let reduction_pourcentage = match (&avant, &apres) {
+        (Some(avant), Some(apres)) if avant.methode == apres.methode => match (avant.taux_infestation, apres.taux_infestation) {
+            (Some(taux_avant), Some(taux_apres)) if taux_avant > 0.0 => {
+                Some((taux_avant - taux_apres) / taux_avant * 100.0)
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    Ok(EfficaciteTraitement {
+        id_traitement: traitement_id,
+        id_ruche: ruche_id,
+        avant,
+        apres,
+        meme_methode,
+        reduction_pourcentage,
+    })
+}